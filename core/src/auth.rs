@@ -1,4 +1,21 @@
-use axum::{extract::Request, http::StatusCode, middleware::Next, response::Response};
+use std::{
+    marker::PhantomData,
+    time::{Duration, SystemTime},
+};
+
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
+use hmac::{Hmac, Mac};
+use sea_orm::prelude::ChronoDateTimeUtc;
+use sha2::Sha256;
 
 pub trait AuthenticatableUser {
     type Username;
@@ -24,10 +41,182 @@ where
     fn generate_token(&self, user: T) -> impl std::future::Future<Output = Self::Token> + Send;
 
     fn verify_header_name(&self) -> &'static str;
+
+    /// Cookie holding the token when the header is absent, e.g. a `session` cookie
+    fn verify_cookie_name(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Token of the request, read from the `verify_header_name` header, then from the
+    /// `verify_cookie_name` cookie
+    ///
+    /// Takes the headers rather than the whole request so the middleware future stays `Send`.
+    ///
+    /// A cookie session authenticator only needs to name its cookie:
+    /// ```rust,ignore
+    /// impl Authenticator<SessionUser> for CookieSessionAuthenticator {
+    ///     fn verify_header_name(&self) -> &'static str {
+    ///         "Authorization"
+    ///     }
+    ///
+    ///     fn verify_cookie_name(&self) -> Option<&'static str> {
+    ///         Some("session")
+    ///     }
+    ///
+    ///     async fn verify(&self, token: &str) -> Result<SessionUser, StatusCode> {
+    ///         self.sessions.find(token).await.ok_or(StatusCode::UNAUTHORIZED)
+    ///     }
+    ///
+    ///     // attempt, generate_token...
+    /// }
+    /// ```
+    fn extract_token(&self, headers: &HeaderMap) -> Option<String> {
+        if let Some(header) = headers.get(self.verify_header_name()) {
+            return header.to_str().ok().map(str::to_string);
+        }
+
+        let cookie_name = self.verify_cookie_name()?;
+
+        headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .find_map(|cookie| {
+                let (name, value) = cookie.trim().split_once('=')?;
+
+                (name == cookie_name).then(|| value.trim_matches('"').to_string())
+            })
+    }
     fn verify(
         &self,
         token: &str,
     ) -> impl std::future::Future<Output = Result<T, StatusCode>> + Send;
+
+    /// When `token` expires, read without verifying it, `None` when unknown
+    ///
+    /// A JWT authenticator reads the `exp` claim with [`jwt_expiry`]:
+    /// ```rust,ignore
+    /// fn token_expiry(&self, token: &str) -> Option<SystemTime> {
+    ///     argon_core::auth::jwt_expiry(token)
+    /// }
+    /// ```
+    fn token_expiry(&self, _token: &str) -> Option<SystemTime> {
+        None
+    }
+
+    /// Audiences `token` is intended for, read without verifying it, `None` when unknown
+    ///
+    /// A JWT authenticator reads the `aud` claim with [`jwt_audiences`]:
+    /// ```rust,ignore
+    /// fn token_audiences(&self, token: &str) -> Option<Vec<String>> {
+    ///     argon_core::auth::jwt_audiences(token)
+    /// }
+    /// ```
+    fn token_audiences(&self, _token: &str) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Value of a token made by `generate_token`, sent by [`SlidingSession`] refreshes
+    ///
+    /// `None` by default, so nothing is sent until an authenticator implements it:
+    /// ```rust,ignore
+    /// fn token_value(&self, token: Self::Token) -> Option<String> {
+    ///     token.ok()
+    /// }
+    /// ```
+    fn token_value(&self, _token: Self::Token) -> Option<String> {
+        None
+    }
+}
+
+/// Sliding sessions, opted into by registering it next to the authenticator
+///
+/// Once a token is verified, [`auth_middleware`] generates a new one when the verified
+/// one expires within `refresh_within`, and sends it in the `header` response header
+/// and the `cookie` cookie. The expiry comes from [`Authenticator::token_expiry`] and
+/// the new token from [`Authenticator::token_value`].
+///
+/// Only successful responses carry the new token, so a failed request doesn't extend
+/// the session. The cookie is `Secure` unless `.secure(false)` is set for local HTTP.
+///
+/// Usage:
+/// ```rust,ignore
+/// let state = state
+///     .with(JwtAuthenticator::new(secret))
+///     .with(SlidingSession::new(Duration::from_secs(5 * 60)).cookie("session"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlidingSession {
+    pub refresh_within: Duration,
+    /// `x-refreshed-token` by default
+    pub header: Option<String>,
+    pub cookie: Option<String>,
+    /// `true` by default, browsers then only send the cookie over HTTPS
+    pub secure: bool,
+}
+
+impl SlidingSession {
+    pub fn new(refresh_within: Duration) -> Self {
+        Self {
+            refresh_within,
+            header: Some("x-refreshed-token".to_string()),
+            cookie: None,
+            secure: true,
+        }
+    }
+
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+
+    /// Only send the refreshed token as a cookie
+    pub fn without_header(mut self) -> Self {
+        self.header = None;
+        self
+    }
+
+    pub fn cookie(mut self, cookie: impl Into<String>) -> Self {
+        self.cookie = Some(cookie.into());
+        self
+    }
+
+    /// Whether the cookie is marked `Secure`, turn it off when serving plain HTTP locally
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    fn send(&self, response: &mut Response, token: &str) {
+        if !response.status().is_success() {
+            return;
+        }
+
+        if let Some(header) = &self.header {
+            match (HeaderName::try_from(header.as_str()), HeaderValue::from_str(token)) {
+                (Ok(name), Ok(value)) => {
+                    response.headers_mut().insert(name, value);
+                }
+                _ => tracing::error!("cannot send the refreshed token in the `{}` header", header),
+            }
+        }
+
+        if let Some(cookie) = &self.cookie {
+            let mut cookie = format!("{}={}; Path=/; HttpOnly; SameSite=Lax", cookie, token);
+
+            if self.secure {
+                cookie.push_str("; Secure");
+            }
+
+            match HeaderValue::from_str(&cookie) {
+                Ok(value) => {
+                    response.headers_mut().append(header::SET_COOKIE, value);
+                }
+                Err(_) => tracing::error!("cannot send the refreshed token in a cookie"),
+            }
+        }
+    }
 }
 
 #[tracing::instrument(level = "debug", skip(request, next))]
@@ -36,21 +225,398 @@ where
     T: Authenticator<R> + Send + Sync + 'static,
     R: AuthenticatableUser + Send + Sync + Clone + 'static,
 {
-    let Some(authenticator) = request.extensions().get::<T>() else {
+    let Some(authenticator) = extension_of::<T>(request.extensions()) else {
         tracing::error!("no Authenticator Extension available");
 
         return Err(StatusCode::INTERNAL_SERVER_ERROR);
     };
 
-    let Some(header) = request.headers().get(authenticator.verify_header_name()) else {
-        return Err(StatusCode::UNAUTHORIZED);
-    };
+    let user = verify_request(authenticator, request.headers()).await?;
+    let refreshed = refresh_token(authenticator, request.extensions(), request.headers(), &user).await;
+
+    request.extensions_mut().insert(user);
+
+    let mut response = next.run(request).await;
 
-    let header = header.to_str().map_err(|_| StatusCode::UNAUTHORIZED)?;
+    if let Some((session, token)) = refreshed {
+        session.send(&mut response, &token);
+    }
+
+    Ok(response)
+}
 
-    let user = authenticator.verify(header).await?;
+/// Same as [`auth_middleware`] but takes the authenticator from router state
+///
+/// Use it with `axum::middleware::from_fn_with_state`, so a missing authenticator
+/// is a compile error instead of a `500` at request time:
+/// ```rust,ignore
+/// router.layer(axum::middleware::from_fn_with_state(
+///     authenticator,
+///     auth_middleware_with_state::<BasicAuthenticator, BasicUser>,
+/// ))
+/// ```
+#[tracing::instrument(level = "debug", skip(authenticator, request, next))]
+pub async fn auth_middleware_with_state<T, R>(
+    State(authenticator): State<T>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode>
+where
+    T: Authenticator<R> + Clone + Send + Sync + 'static,
+    R: AuthenticatableUser + Send + Sync + Clone + 'static,
+{
+    let user = verify_request(&authenticator, request.headers()).await?;
+    let refreshed = refresh_token(&authenticator, request.extensions(), request.headers(), &user).await;
 
     request.extensions_mut().insert(user);
 
+    let mut response = next.run(request).await;
+
+    if let Some((session, token)) = refreshed {
+        session.send(&mut response, &token);
+    }
+
+    Ok(response)
+}
+
+/// Adds `X-Token-Expires-At` with the RFC 3339 expiry of the request's token
+///
+/// Layer it next to [`auth_middleware`], it finds the authenticator the same way and
+/// relies on [`Authenticator::token_expiry`]. Requests without a token or with an
+/// unknown expiry are left untouched.
+pub async fn token_expiry_header<T, R>(request: Request, next: Next) -> Response
+where
+    T: Authenticator<R> + Send + Sync + 'static,
+    R: AuthenticatableUser + Send + Sync + Clone + 'static,
+{
+    let expiry = extension_of::<T>(request.extensions()).and_then(|authenticator| {
+        let token = authenticator.extract_token(request.headers())?;
+
+        authenticator.token_expiry(&token)
+    });
+
+    let mut response = next.run(request).await;
+
+    if let Some(expiry) = expiry {
+        let expiry = ChronoDateTimeUtc::from(expiry).to_rfc3339();
+
+        if let Ok(value) = HeaderValue::from_str(&expiry) {
+            response.headers_mut().insert("x-token-expires-at", value);
+        }
+    }
+
+    response
+}
+
+/// Audience a service requires of the tokens it accepts, see [`require_audience`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequiredAudience(pub String);
+
+impl RequiredAudience {
+    pub fn new(audience: impl Into<String>) -> Self {
+        Self(audience.into())
+    }
+}
+
+/// Rejects with `403 Forbidden` tokens whose audiences don't include the required one
+///
+/// Audiences come from [`Authenticator::token_audiences`], a token without any is
+/// rejected too. The token isn't verified here, layer it inside [`auth_middleware`]
+/// so only verified tokens reach it:
+/// ```rust,ignore
+/// router
+///     .layer(axum::middleware::from_fn_with_state(
+///         RequiredAudience::new("billing"),
+///         require_audience::<JwtAuthenticator, JwtUser>,
+///     ))
+///     .layer(axum::middleware::from_fn(auth_middleware::<JwtAuthenticator, JwtUser>))
+/// ```
+#[tracing::instrument(level = "debug", skip(request, next))]
+pub async fn require_audience<T, R>(
+    State(audience): State<RequiredAudience>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode>
+where
+    T: Authenticator<R> + Send + Sync + 'static,
+    R: AuthenticatableUser + Send + Sync + Clone + 'static,
+{
+    let Some(authenticator) = extension_of::<T>(request.extensions()) else {
+        tracing::error!("no Authenticator Extension available");
+
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    };
+
+    let token = authenticator
+        .extract_token(request.headers())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let audiences = authenticator.token_audiences(&token).unwrap_or_default();
+    if !audiences.contains(&audience.0) {
+        tracing::debug!("rejecting a token for {:?}, expected `{}`", audiences, audience.0);
+
+        return Err(StatusCode::FORBIDDEN);
+    }
+
     Ok(next.run(request).await)
 }
+
+/// `exp` claim of a JWT, with or without the `Bearer ` prefix
+///
+/// The signature is not checked, only use it on tokens that are verified anyway.
+pub fn jwt_expiry(token: &str) -> Option<SystemTime> {
+    let claims = jwt_claims(token)?;
+
+    let exp = Duration::try_from_secs_f64(claims.get("exp")?.as_f64()?).ok()?;
+
+    SystemTime::UNIX_EPOCH.checked_add(exp)
+}
+
+/// `aud` claim of a JWT, a single audience or a list of them
+///
+/// The signature is not checked, only use it on tokens that are verified anyway.
+pub fn jwt_audiences(token: &str) -> Option<Vec<String>> {
+    let claims = jwt_claims(token)?;
+
+    match claims.get("aud")? {
+        serde_json::Value::String(audience) => Some(vec![audience.clone()]),
+        serde_json::Value::Array(audiences) => Some(
+            audiences
+                .iter()
+                .filter_map(|audience| audience.as_str().map(str::to_string))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn jwt_claims(token: &str) -> Option<serde_json::Value> {
+    let token = token.trim();
+    let token = match token.split_once(' ') {
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") => token.trim(),
+        _ => token,
+    };
+
+    let payload = token.split('.').nth(1)?;
+    let payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+
+    serde_json::from_slice(&payload).ok()
+}
+
+/// Check the hex HMAC-SHA256 of `body` sent in a webhook signature header
+///
+/// The signature may carry a `sha256=` prefix as GitHub sends it, and is compared in
+/// constant time. Read the body with [`crate::extract::RawBody`] so it is exactly what was signed:
+/// ```rust,ignore
+/// async fn webhook(headers: HeaderMap, RawBody(body): RawBody) -> StatusCode {
+///     let signature = headers.get("x-hub-signature-256").and_then(|value| value.to_str().ok());
+///
+///     match signature {
+///         Some(signature) if verify_hmac(SECRET, signature, &body) => StatusCode::NO_CONTENT,
+///         _ => StatusCode::UNAUTHORIZED,
+///     }
+/// }
+/// ```
+pub fn verify_hmac(secret: impl AsRef<[u8]>, signature_header: &str, body: &[u8]) -> bool {
+    let signature = signature_header.trim();
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_ref()) else {
+        return false;
+    };
+    mac.update(body);
+
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// A standalone Extension wins over the one registered in AppState
+fn extension_of<T>(extensions: &axum::http::Extensions) -> Option<&T>
+where
+    T: Send + Sync + 'static,
+{
+    extensions
+        .get::<T>()
+        .or_else(|| extensions.get::<crate::state::AppState>()?.get::<T>())
+}
+
+async fn verify_request<T, R>(authenticator: &T, headers: &HeaderMap) -> Result<R, StatusCode>
+where
+    T: Authenticator<R>,
+    R: AuthenticatableUser,
+{
+    let Some(token) = authenticator.extract_token(headers) else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+
+    authenticator.verify(&token).await
+}
+
+/// New token for `user` when a [`SlidingSession`] is registered and the request's
+/// token expires soon
+async fn refresh_token<T, R>(
+    authenticator: &T,
+    extensions: &axum::http::Extensions,
+    headers: &HeaderMap,
+    user: &R,
+) -> Option<(SlidingSession, String)>
+where
+    T: Authenticator<R>,
+    R: AuthenticatableUser + Clone,
+{
+    let session = extension_of::<SlidingSession>(extensions)?;
+
+    let token = authenticator.extract_token(headers)?;
+    let remaining = authenticator
+        .token_expiry(&token)?
+        .duration_since(SystemTime::now())
+        .unwrap_or_default();
+
+    if remaining > session.refresh_within {
+        return None;
+    }
+
+    let token = authenticator.token_value(authenticator.generate_token(user.clone()).await)?;
+    tracing::debug!("refreshing a token expiring in {:?}", remaining);
+
+    Some((session.clone(), token))
+}
+
+/// Credentials of an `Authorization: Basic base64(username:password)` header
+///
+/// Malformed headers, broken base64 and a missing `:` are answered with `401`.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[post("/login")]
+/// async fn login(
+///     Service(authenticator): Service<BasicAuthenticator>,
+///     credentials: BasicCredentials,
+/// ) -> Result<String, StatusCode> {
+///     let user = authenticator
+///         .attempt(credentials.username, credentials.password)
+///         .await
+///         .map_err(|_| StatusCode::UNAUTHORIZED)?;
+///
+///     authenticator.generate_token(user).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct BasicCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl BasicCredentials {
+    /// Parse an `Authorization` header value, the scheme is case-insensitive
+    pub fn parse(value: &str) -> Option<Self> {
+        let (scheme, encoded) = value.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("basic") {
+            return None;
+        }
+
+        let decoded = STANDARD.decode(encoded.trim()).ok()?;
+        let decoded = String::from_utf8(decoded).ok()?;
+        let (username, password) = decoded.split_once(':')?;
+
+        Some(Self {
+            username: username.to_string(),
+            password: password.to_string(),
+        })
+    }
+}
+
+// never print the password
+impl std::fmt::Debug for BasicCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BasicCredentials")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
+impl<S> FromRequestParts<S> for BasicCredentials
+where
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Self::parse)
+            .ok_or(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// Users that carry fine-grained permissions
+pub trait HasPermissions {
+    fn has_permission(&self, permission: &str) -> bool;
+}
+
+/// Permission checked by [`RequirePermission`]
+pub trait Permission {
+    const NAME: &'static str;
+}
+
+/// Guard extractor only letting users holding the `P` permission through
+///
+/// Answers `401` when no user was authenticated and `403` when the permission is
+/// missing. The authenticated `U` is available as `user`.
+///
+/// Usage:
+/// ```rust,ignore
+/// struct EditPosts;
+///
+/// impl Permission for EditPosts {
+///     const NAME: &'static str = "posts.edit";
+/// }
+///
+/// #[put("/posts/{id}")]
+/// async fn update_post(guard: RequirePermission<EditPosts, BasicUser>, ...) { ... }
+/// ```
+pub struct RequirePermission<P, U> {
+    pub user: U,
+    permission: PhantomData<P>,
+}
+
+impl<P, U> RequirePermission<P, U>
+where
+    P: Permission,
+{
+    pub fn permission(&self) -> &'static str {
+        P::NAME
+    }
+}
+
+impl<S, P, U> FromRequestParts<S> for RequirePermission<P, U>
+where
+    S: Send + Sync,
+    P: Permission,
+    U: HasPermissions + Clone + Send + Sync + 'static,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(user) = parts.extensions.get::<U>() else {
+            return Err(StatusCode::UNAUTHORIZED);
+        };
+
+        if !user.has_permission(P::NAME) {
+            tracing::debug!("permission `{}` denied", P::NAME);
+
+            return Err(StatusCode::FORBIDDEN);
+        }
+
+        Ok(Self {
+            user: user.clone(),
+            permission: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,56 @@
+use std::future::Future;
+
+use futures_util::future::BoxFuture;
+
+use crate::state::AppState;
+
+type Hook = Box<dyn FnOnce(AppState) -> BoxFuture<'static, anyhow::Result<AppState>> + Send>;
+
+/// Startup code run once, in registration order, before the server starts serving
+///
+/// Every hook receives the [`AppState`] built so far and returns it, so hooks can
+/// register services, warm caches or check external dependencies. The first failing
+/// hook aborts the startup.
+///
+/// Usage:
+/// ```rust,ignore
+/// let state = StartupHooks::new()
+///     .on_startup("authenticator", |state| async move {
+///         let authenticator = BasicAuthenticator::new(state.db().clone());
+///
+///         Ok(state.with(authenticator))
+///     })
+///     .run(AppState::new(db))
+///     .await?;
+/// ```
+#[derive(Default)]
+pub struct StartupHooks {
+    hooks: Vec<(&'static str, Hook)>,
+}
+
+impl StartupHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_startup<F, Fut>(mut self, name: &'static str, hook: F) -> Self
+    where
+        F: FnOnce(AppState) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<AppState>> + Send + 'static,
+    {
+        self.hooks.push((name, Box::new(move |state| Box::pin(hook(state)))));
+        self
+    }
+
+    pub async fn run(self, mut state: AppState) -> anyhow::Result<AppState> {
+        for (name, hook) in self.hooks {
+            tracing::info!("running startup hook `{}`", name);
+
+            state = hook(state)
+                .await
+                .map_err(|err| err.context(format!("startup hook `{}` failed", name)))?;
+        }
+
+        Ok(state)
+    }
+}
@@ -1,3 +1,5 @@
+pub mod env;
+
 use anyhow::Result;
 
 /// Trait for building configuration structs
@@ -13,3 +15,80 @@ pub trait ConfigBuilder: Clone + Send + Sync + 'static {
     fn build() -> Result<Self>;
 }
 
+
+/// Environment the application runs in, read from `APP_ENV`
+///
+/// The profile only changes defaults, every setting it affects can still be set
+/// explicitly.
+///
+/// | default             | development | test   | production |
+/// |---------------------|-------------|--------|------------|
+/// | docs and `/openapi` | on          | off    | off        |
+/// | log format          | pretty      | pretty | JSON       |
+/// | permissive CORS     | on          | off    | off        |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    #[default]
+    Development,
+    Test,
+    Production,
+}
+
+impl Profile {
+    /// Profile of `APP_ENV`, `Development` when unset
+    ///
+    /// An unknown profile is an error rather than a silent `Development`, which would
+    /// serve the docs and permissive CORS from a mistyped production setting.
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("APP_ENV").ok().filter(|value| !value.trim().is_empty()) {
+            Some(value) => value
+                .parse()
+                .map_err(|err| anyhow::anyhow!("cannot parse `APP_ENV`: {}", err)),
+            None => Ok(Profile::default()),
+        }
+    }
+
+    /// Whether the spec is written to `api.json` and served
+    pub fn docs(&self) -> bool {
+        matches!(self, Profile::Development)
+    }
+
+    /// Whether logs are written as JSON lines instead of the human readable format
+    pub fn json_logs(&self) -> bool {
+        matches!(self, Profile::Production)
+    }
+
+    /// Whether any origin may call the API from a browser
+    pub fn permissive_cors(&self) -> bool {
+        matches!(self, Profile::Development)
+    }
+}
+
+impl std::str::FromStr for Profile {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "development" | "dev" => Ok(Profile::Development),
+            "test" => Ok(Profile::Test),
+            "production" | "prod" => Ok(Profile::Production),
+            other => Err(anyhow::anyhow!(
+                "unknown profile `{}`, expected `development`, `test` or `production`",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Profile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let profile = match self {
+            Profile::Development => "development",
+            Profile::Test => "test",
+            Profile::Production => "production",
+        };
+
+        write!(f, "{}", profile)
+    }
+}
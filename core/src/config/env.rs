@@ -0,0 +1,45 @@
+//! Typed reading of environment variables for [`super::ConfigBuilder`] implementations
+//!
+//! Usage:
+//! ```rust,ignore
+//! let port = env_or("SERVER_PORT", 3000);
+//! let secret: String = env_required("JWT_SECRET")?;
+//! ```
+
+use std::str::FromStr;
+
+/// Parse an optional variable, falling back to `default` when unset, empty or invalid
+///
+/// An invalid value is logged as a warning.
+pub fn env_or<T>(key: &str, default: T) -> T
+where
+    T: FromStr + std::fmt::Display,
+    T::Err: std::fmt::Debug,
+{
+    let Some(value) = read(key) else {
+        return default;
+    };
+
+    value.parse().unwrap_or_else(|err| {
+        tracing::warn!("cannot parse `{}`. defaulting to {} {:?}", key, default, err);
+        default
+    })
+}
+
+/// Parse a variable the application can't run without, failing when unset, empty or invalid
+pub fn env_required<T>(key: &str) -> anyhow::Result<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Debug,
+{
+    let value = read(key).ok_or_else(|| anyhow::anyhow!("cannot read `{}`: it is not set", key))?;
+
+    value
+        .parse()
+        .map_err(|err| anyhow::anyhow!("cannot parse `{}`: {:?}", key, err))
+}
+
+/// The value of `key`, `None` when unset or blank
+fn read(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.trim().is_empty())
+}
@@ -1,3 +1,144 @@
+use std::sync::Mutex;
+
+use axum::Router;
+use utoipa::openapi::OpenApi;
+
+// docs of every controller mounted with a prefix, by controller and prefix, see `mounted_docs`
+static MOUNTED_DOCS: Mutex<Vec<MountedDoc>> = Mutex::new(Vec::new());
+
+struct MountedDoc {
+    controller: &'static str,
+    prefix: String,
+    docs: OpenApi,
+}
+
 pub trait Controller {
     fn router() -> axum::Router;
+
+    /// [`Controller::router`] for controllers whose setup can fail, so the error reaches startup
+    fn try_router() -> anyhow::Result<axum::Router> {
+        Ok(Self::router())
+    }
+
+    /// [`Controller::try_router`] without the [`Controller::preflight_router`], to be wrapped
+    /// in layers the preflights should skip
+    fn try_guarded_router() -> anyhow::Result<axum::Router> {
+        Self::try_router()
+    }
+
+    /// `OPTIONS` routes of `#[controller(preflight)]`, which [`Controller::router`] merges
+    /// outside the controller middleware
+    fn preflight_router() -> Option<axum::Router> {
+        None
+    }
+
+    /// `(method, path)` of every route in [`Controller::router`]
+    fn routes() -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// `(method, path)` of every operation in [`Controller::api_doc`], e.g. `("get", "/users/{id}")`
+    ///
+    /// Unlike [`Controller::routes`] it leaves out `#[skip_docs]` routes and names
+    /// catch-all captures like the spec does, to check what the docs picked up.
+    fn operation_paths() -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    fn operation_count() -> usize {
+        Self::operation_paths().len()
+    }
+
+    /// OpenAPI document of the controller's paths, to be merged or nested by the caller
+    fn api_doc() -> utoipa::openapi::OpenApi {
+        utoipa::openapi::OpenApi::default()
+    }
+
+    /// Nest the controller's routes under `prefix` and record its docs under the same
+    /// prefix, see [`mounted_docs`]
+    ///
+    /// Usage:
+    /// ```rust,ignore
+    /// let router = UserController::mount(Router::new(), "/api");
+    ///
+    /// let spec = MainApiDoc::openapi().merge_from(mounted_docs());
+    /// ```
+    fn mount(router: Router, prefix: &str) -> Router {
+        let prefix = record_docs(std::any::type_name::<Self>(), prefix, Self::api_doc());
+
+        nest(router, &prefix, Self::router())
+    }
+
+    /// [`Controller::mount`] for controllers whose setup can fail
+    fn try_mount(router: Router, prefix: &str) -> anyhow::Result<Router> {
+        let controller = Self::try_router()?;
+        let prefix = record_docs(std::any::type_name::<Self>(), prefix, Self::api_doc());
+
+        Ok(nest(router, &prefix, controller))
+    }
+
+    /// [`Controller::try_mount`] leaving out the [`Controller::preflight_router`], so layers
+    /// put around the result don't turn preflights away. `routes!` mounts the preflights
+    /// outside its group layers with [`Controller::mount_preflight`]
+    fn try_mount_guarded(router: Router, prefix: &str) -> anyhow::Result<Router> {
+        let controller = Self::try_guarded_router()?;
+        let prefix = record_docs(std::any::type_name::<Self>(), prefix, Self::api_doc());
+
+        Ok(nest(router, &prefix, controller))
+    }
+
+    /// Nest the [`Controller::preflight_router`] under `prefix`, if the controller has one
+    fn mount_preflight(router: Router, prefix: &str) -> Router {
+        match Self::preflight_router() {
+            Some(preflight) => nest(router, prefix.trim_end_matches('/'), preflight),
+            None => router,
+        }
+    }
+}
+
+/// Docs of every controller mounted so far, with their paths under the mount prefix
+///
+/// Mounting a controller again at the same prefix, e.g. when the routes are built twice,
+/// replaces its docs rather than adding them again.
+pub fn mounted_docs() -> OpenApi {
+    MOUNTED_DOCS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+        .map(|mounted| mounted.docs.clone())
+        .fold(OpenApi::default(), OpenApi::merge_from)
+}
+
+// "/" and "" mount at the root, a trailing slash is dropped so paths don't get "//"
+fn record_docs(controller: &'static str, prefix: &str, docs: OpenApi) -> String {
+    let prefix = prefix.trim_end_matches('/').to_string();
+
+    let docs = OpenApi::default().nest_with_path_composer(prefix.as_str(), docs, |prefix, path| {
+        // the generated docs hold paths without their leading slash
+        format!("{}/{}", prefix, path.trim_start_matches('/'))
+    });
+
+    let mut mounted = MOUNTED_DOCS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    match mounted
+        .iter_mut()
+        .find(|mounted| mounted.controller == controller && mounted.prefix == prefix)
+    {
+        Some(mounted) => mounted.docs = docs,
+        None => mounted.push(MountedDoc {
+            controller,
+            prefix: prefix.clone(),
+            docs,
+        }),
+    }
+
+    prefix
+}
+
+fn nest(router: Router, prefix: &str, controller: Router) -> Router {
+    // axum refuses to nest at the root
+    if prefix.is_empty() {
+        router.merge(controller)
+    } else {
+        router.nest(prefix, controller)
+    }
 }
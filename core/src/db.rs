@@ -0,0 +1,259 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, DbErr, Statement};
+
+use crate::auth::AuthenticatableUser;
+
+pub const TENANT_HEADER: &str = "X-Tenant-Id";
+
+/// Maps a request to its tenant and a tenant to its database
+///
+/// Usage:
+/// ```rust,ignore
+/// #[derive(Clone)]
+/// struct EnvTenants;
+///
+/// impl TenantResolver for EnvTenants {
+///     async fn connect(&self, tenant: &str) -> Result<DatabaseConnection, DbErr> {
+///         let url = std::env::var(format!("DATABASE_URL_{}", tenant.to_uppercase()))
+///             .map_err(|_| DbErr::Custom(format!("unknown tenant `{}`", tenant)))?;
+///
+///         Database::connect(url).await
+///     }
+/// }
+///
+/// router.layer(axum::middleware::from_fn_with_state(
+///     TenantConnections::new(EnvTenants),
+///     tenant_middleware::<EnvTenants>,
+/// ))
+/// ```
+pub trait TenantResolver: Send + Sync + 'static {
+    /// Tenant of the request, read from the `X-Tenant-Id` header by default
+    ///
+    /// Override it to resolve tenants from the subdomain in the `Host` header instead.
+    fn tenant(&self, headers: &HeaderMap) -> Option<String> {
+        headers
+            .get(TENANT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|tenant| !tenant.is_empty())
+            .map(str::to_string)
+    }
+
+    fn connect(
+        &self,
+        tenant: &str,
+    ) -> impl std::future::Future<Output = Result<DatabaseConnection, DbErr>> + Send;
+}
+
+/// Tenants whose connections [`TenantConnections`] keeps by default
+pub const DEFAULT_MAX_TENANTS: usize = 64;
+
+/// A resolver plus the connections it opened, so each tenant is connected once
+///
+/// Only the tenants `connect` succeeds for are kept, at most [`DEFAULT_MAX_TENANTS`] of
+/// them unless changed with [`TenantConnections::max_tenants`]. Past it, an arbitrary
+/// tenant is dropped to make room and reconnected on its next request.
+pub struct TenantConnections<R> {
+    resolver: Arc<R>,
+    connections: Arc<RwLock<HashMap<String, DatabaseConnection>>>,
+    max_tenants: usize,
+}
+
+impl<R> TenantConnections<R>
+where
+    R: TenantResolver,
+{
+    pub fn new(resolver: R) -> Self {
+        Self {
+            resolver: Arc::new(resolver),
+            connections: Arc::default(),
+            max_tenants: DEFAULT_MAX_TENANTS,
+        }
+    }
+
+    /// Keep the connections of at most `max_tenants` tenants, at least one
+    pub fn max_tenants(mut self, max_tenants: usize) -> Self {
+        self.max_tenants = max_tenants.max(1);
+        self
+    }
+
+    /// Cached connection of `tenant`, connecting on first use
+    pub async fn connection(&self, tenant: &str) -> Result<DatabaseConnection, DbErr> {
+        if let Some(db) = self
+            .connections
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(tenant)
+        {
+            return Ok(db.clone());
+        }
+
+        let db = self.resolver.connect(tenant).await?;
+
+        let mut connections = self.connections.write().unwrap_or_else(|e| e.into_inner());
+
+        // another request may have connected meanwhile, keep the first connection
+        if let Some(db) = connections.get(tenant) {
+            return Ok(db.clone());
+        }
+
+        if connections.len() >= self.max_tenants
+            && let Some(evicted) = connections.keys().next().cloned()
+        {
+            tracing::debug!("too many tenant connections, dropping the one of `{}`", evicted);
+            connections.remove(&evicted);
+        }
+
+        connections.insert(tenant.to_string(), db.clone());
+
+        Ok(db)
+    }
+
+    /// Number of tenants with a cached connection
+    pub fn cached(&self) -> usize {
+        self.connections.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+impl<R> Clone for TenantConnections<R> {
+    fn clone(&self) -> Self {
+        Self {
+            resolver: self.resolver.clone(),
+            connections: self.connections.clone(),
+            max_tenants: self.max_tenants,
+        }
+    }
+}
+
+/// Tenant of the current request, inserted into the extensions by [`tenant_middleware`]
+///
+/// The [`crate::state::Db`] extractor yields its connection instead of the default one.
+#[derive(Clone)]
+pub struct Tenant {
+    id: String,
+    db: DatabaseConnection,
+}
+
+impl Tenant {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+}
+
+/// Resolves the tenant of the request and inserts a [`Tenant`] into the extensions
+///
+/// Requests without a tenant are rejected with `400 Bad Request`.
+#[tracing::instrument(level = "debug", skip(tenants, request, next))]
+pub async fn tenant_middleware<R>(
+    State(tenants): State<TenantConnections<R>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode>
+where
+    R: TenantResolver,
+{
+    let Some(id) = tenants.resolver.tenant(request.headers()) else {
+        return Err(StatusCode::BAD_REQUEST);
+    };
+
+    let db = tenants.connection(&id).await.map_err(|err| {
+        tracing::error!("cannot connect to the database of tenant `{}`: {:?}", id, err);
+
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    request.extensions_mut().insert(Tenant { id, db });
+
+    Ok(next.run(request).await)
+}
+
+/// Where an authenticator looks its users up
+///
+/// Depending on a store rather than on a `DatabaseConnection` lets authenticators run
+/// against `argon_core::testing::MemoryUserStore` in tests. The database backed store is
+/// implemented by the app, usually on `DatabaseConnection` itself:
+/// ```rust,ignore
+/// impl UserStore<BasicUser> for DatabaseConnection {
+///     async fn find_by_username(&self, username: &String) -> Result<Option<BasicUser>, DbErr> {
+///         let user = user::Entity::find()
+///             .filter(user::Column::Username.eq(username))
+///             .one(self)
+///             .await?;
+///
+///         Ok(user.map(BasicUser::from))
+///     }
+///
+///     // find_by_id...
+/// }
+/// ```
+pub trait UserStore<U>: Send + Sync
+where
+    U: AuthenticatableUser,
+{
+    fn find_by_username(
+        &self,
+        username: &U::Username,
+    ) -> impl std::future::Future<Output = Result<Option<U>, DbErr>> + Send;
+
+    fn find_by_id(&self, id: &U::Id) -> impl std::future::Future<Output = Result<Option<U>, DbErr>> + Send;
+}
+
+/// Checks the database is usable before serving: it answers and `tables` exist
+///
+/// Catches wrong credentials and a database the migrations haven't run on at startup,
+/// instead of on the first request.
+///
+/// Usage:
+/// ```rust,ignore
+/// let db = Database::connect(url).await?;
+///
+/// check_database(&db, &["user"]).await?;
+/// ```
+pub async fn check_database(db: &DatabaseConnection, tables: &[&str]) -> anyhow::Result<()> {
+    db.ping()
+        .await
+        .map_err(|err| anyhow::anyhow!("the database doesn't answer, check `DATABASE_URL`: {}", err))?;
+
+    for table in tables {
+        if !table_exists(db, table).await? {
+            anyhow::bail!(
+                "the `{}` table is missing, run the migrations first with `cargo run -p migration -- up`",
+                table
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn table_exists(db: &DatabaseConnection, table: &str) -> Result<bool, DbErr> {
+    let backend = db.get_database_backend();
+
+    let sql = match backend {
+        DbBackend::Postgres => {
+            "SELECT 1 FROM information_schema.tables WHERE table_schema = current_schema() AND table_name = $1"
+        }
+        DbBackend::MySql => "SELECT 1 FROM information_schema.tables WHERE table_schema = DATABASE() AND table_name = ?",
+        _ => "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?",
+    };
+
+    let row = db
+        .query_one_raw(Statement::from_sql_and_values(backend, sql, [table.into()]))
+        .await?;
+
+    Ok(row.is_some())
+}
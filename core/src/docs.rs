@@ -0,0 +1,258 @@
+use std::{
+    collections::BTreeMap,
+    sync::{LazyLock, RwLock},
+};
+
+use utoipa::openapi::{ComponentsBuilder, OpenApi, RefOr, schema::Schema, tag::TagBuilder};
+
+/// Tag names and descriptions, in registration order
+static TAGS: LazyLock<RwLock<Vec<(String, String)>>> = LazyLock::new(RwLock::default);
+
+/// Response schemas of a `#[controller]`, registered by the macro
+///
+/// Collecting them here rather than in every generated `...Api` struct defines a
+/// schema shared by several controllers once, see [`with_schemas`].
+pub struct SchemaEntry {
+    /// Path of the controller, `concat!(module_path!(), "::", stringify!(UserController))`
+    pub controller: &'static str,
+    pub collect: fn(&mut Vec<(String, RefOr<Schema>)>),
+}
+
+inventory::collect!(SchemaEntry);
+
+/// Registered schemas by name, of one controller or of all of them
+///
+/// Two different schemas under one name are reported, the first registered one is kept.
+pub fn registered_schemas(controller: Option<&str>) -> BTreeMap<String, RefOr<Schema>> {
+    let mut collected = Vec::new();
+
+    for entry in inventory::iter::<SchemaEntry> {
+        if controller.is_none_or(|controller| controller == entry.controller) {
+            (entry.collect)(&mut collected);
+        }
+    }
+
+    let mut schemas = BTreeMap::new();
+
+    for (name, schema) in collected {
+        match schemas.get(&name) {
+            None => {
+                schemas.insert(name, schema);
+            }
+            Some(existing) if !same_schema(existing, &schema) => {
+                tracing::warn!("conflicting OpenAPI schemas named `{}`, keeping the first one", name);
+            }
+            Some(_) => {}
+        }
+    }
+
+    schemas
+}
+
+/// `docs` with every registered schema as a component
+///
+/// Usage:
+/// ```rust,ignore
+/// let docs = argon_core::docs::with_schemas(MainApiDoc::openapi());
+/// ```
+pub fn with_schemas(docs: OpenApi) -> OpenApi {
+    add_schemas(docs, registered_schemas(None))
+}
+
+/// `docs` with the schemas registered by `controller` as components
+///
+/// `controller` is the path the controller is keyed by, see [`SchemaEntry::controller`].
+pub fn with_schemas_of(docs: OpenApi, controller: &str) -> OpenApi {
+    add_schemas(docs, registered_schemas(Some(controller)))
+}
+
+fn add_schemas(mut docs: OpenApi, schemas: BTreeMap<String, RefOr<Schema>>) -> OpenApi {
+    let components = docs.components.take().unwrap_or_else(|| ComponentsBuilder::new().build());
+
+    docs.components = Some(
+        schemas
+            .into_iter()
+            .fold(ComponentsBuilder::from(components), |components, (name, schema)| {
+                components.schema(name, schema)
+            })
+            .build(),
+    );
+
+    docs
+}
+
+/// Describe a tag in the top-level `tags` of the spec, replacing a previous description
+///
+/// Usage:
+/// ```rust,ignore
+/// argon_core::docs::register_tag("Users", "User management endpoints");
+///
+/// let docs = argon_core::docs::with_tags(MainApiDoc::openapi());
+/// ```
+pub fn register_tag(name: impl Into<String>, description: impl Into<String>) {
+    let (name, description) = (name.into(), description.into());
+    let mut tags = TAGS.write().unwrap_or_else(|err| err.into_inner());
+
+    match tags.iter_mut().find(|(candidate, _)| *candidate == name) {
+        Some((_, existing)) => *existing = description,
+        None => tags.push((name, description)),
+    }
+}
+
+/// `docs` with the registered tags, a tag it already lists keeps its own description
+pub fn with_tags(mut docs: OpenApi) -> OpenApi {
+    let registered = TAGS.read().unwrap_or_else(|err| err.into_inner());
+    let tags = docs.tags.get_or_insert_with(Vec::new);
+
+    for (name, description) in registered.iter() {
+        match tags.iter_mut().find(|tag| tag.name == *name) {
+            Some(tag) => {
+                tag.description.get_or_insert_with(|| description.clone());
+            }
+            None => tags.push(TagBuilder::new().name(name).description(Some(description)).build()),
+        }
+    }
+
+    if tags.is_empty() {
+        docs.tags = None;
+    }
+
+    docs
+}
+
+/// Plausible example of `T` built from its schema, what `#[controller]` shows for a
+/// response body without an explicit example
+///
+/// Documented `example`s and defaults are used as is, otherwise strings become
+/// `"string"` (or a sample of their format), numbers `0`, booleans `false` and arrays
+/// hold a single item.
+///
+/// Usage:
+/// ```rust,ignore
+/// let example = argon_core::docs::example_for::<SimpleResponse>();
+///
+/// assert_eq!(example, json!({ "message": "string" }));
+/// ```
+pub fn example_for<T>() -> serde_json::Value
+where
+    T: utoipa::ToSchema,
+{
+    let mut dependencies = Vec::new();
+    T::schemas(&mut dependencies);
+
+    // a recursive `T` refers to itself
+    dependencies.push((T::name().to_string(), T::schema()));
+
+    let components = dependencies
+        .into_iter()
+        .filter_map(|(name, schema)| Some((name, serde_json::to_value(schema).ok()?)))
+        .collect();
+
+    match serde_json::to_value(T::schema()) {
+        Ok(schema) => example_value(&schema, &components, &mut vec![T::name().to_string()]),
+        Err(_) => serde_json::Value::Null,
+    }
+}
+
+fn example_value(
+    schema: &serde_json::Value,
+    components: &BTreeMap<String, serde_json::Value>,
+    expanding: &mut Vec<String>,
+) -> serde_json::Value {
+    use serde_json::{Value, json};
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.rsplit('/').next().unwrap_or(reference);
+
+        // a schema nested in itself is left out, so recursive schemas terminate
+        let Some(schema) = components.get(name).filter(|_| !expanding.iter().any(|expanded| expanded == name)) else {
+            return Value::Null;
+        };
+
+        expanding.push(name.to_string());
+        let example = example_value(schema, components, expanding);
+        expanding.pop();
+
+        return example;
+    }
+
+    for keyword in ["example", "default"] {
+        if let Some(example) = schema.get(keyword) {
+            return example.clone();
+        }
+    }
+    if let Some(example) = schema.get("examples").and_then(Value::as_array).and_then(|examples| examples.first()) {
+        return example.clone();
+    }
+    if let Some(first) = schema.get("enum").and_then(Value::as_array).and_then(|values| values.first()) {
+        return first.clone();
+    }
+
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = serde_json::Map::new();
+
+        for part in parts {
+            match example_value(part, components, expanding) {
+                Value::Object(fields) => merged.extend(fields),
+                other if parts.len() == 1 => return other,
+                _ => {}
+            }
+        }
+
+        return Value::Object(merged);
+    }
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(options) = schema.get(keyword).and_then(Value::as_array) {
+            // the first option that isn't `null`, e.g. the value of an `Option<T>`
+            return options
+                .iter()
+                .map(|option| example_value(option, components, expanding))
+                .find(|example| !example.is_null())
+                .unwrap_or(Value::Null);
+        }
+    }
+
+    let ty = match schema.get("type") {
+        Some(Value::String(ty)) => ty.as_str(),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|ty| *ty != "null")
+            .unwrap_or("null"),
+        _ if schema.get("properties").is_some() => "object",
+        _ => return Value::Null,
+    };
+
+    match ty {
+        "string" => match schema.get("format").and_then(Value::as_str) {
+            Some("date-time") => json!("2024-01-01T00:00:00Z"),
+            Some("date") => json!("2024-01-01"),
+            Some("uuid") => json!("00000000-0000-0000-0000-000000000000"),
+            Some("email") => json!("user@example.com"),
+            Some("uri") => json!("https://example.com"),
+            _ => json!("string"),
+        },
+        "integer" | "number" => json!(0),
+        "boolean" => json!(false),
+        "array" => match schema.get("items").map(|items| example_value(items, components, expanding)) {
+            Some(Value::Null) | None => json!([]),
+            Some(item) => json!([item]),
+        },
+        "object" => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+
+            Value::Object(
+                properties
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, property)| (name.clone(), example_value(property, components, expanding)))
+                    .collect(),
+            )
+        }
+        _ => Value::Null,
+    }
+}
+
+fn same_schema(a: &RefOr<Schema>, b: &RefOr<Schema>) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
@@ -0,0 +1,164 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use sea_orm::{DbErr, SqlErr};
+
+use crate::response::BaseErrorResponse;
+
+/// Error type for handlers that want to use `?` on anything convertible to `anyhow::Error`
+///
+/// The full error chain is logged and the client gets a generic JSON body, so internal
+/// details never leak into the response. The status is `500`, except for SeaORM errors:
+/// `RecordNotFound` becomes `404` and connection errors become `503`.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[get("/report")]
+/// async fn report(Db(db): Db) -> Result<Json<Report>, HandlerError> {
+///     let rows = load_rows(&db).await?;
+///
+///     Ok(Json(Report::build(rows)?))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct HandlerError(pub anyhow::Error);
+
+impl<E> From<E> for HandlerError
+where
+    E: Into<anyhow::Error>,
+{
+    fn from(err: E) -> Self {
+        Self(err.into())
+    }
+}
+
+impl HandlerError {
+    pub fn status(&self) -> StatusCode {
+        match self.0.downcast_ref::<DbErr>() {
+            Some(DbErr::RecordNotFound(_)) => StatusCode::NOT_FOUND,
+            Some(DbErr::Conn(_) | DbErr::ConnectionAcquire(_)) => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for HandlerError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+
+        let message = match status {
+            StatusCode::NOT_FOUND => {
+                tracing::debug!("handler failed: {:?}", self.0);
+
+                "not found"
+            }
+            StatusCode::SERVICE_UNAVAILABLE => {
+                tracing::error!("handler failed: {:?}", self.0);
+
+                "service unavailable"
+            }
+            _ => {
+                tracing::error!("handler failed: {:?}", self.0);
+
+                "internal server error"
+            }
+        };
+
+        (status, Json(BaseErrorResponse::<()>::new(message, None))).into_response()
+    }
+}
+
+/// Error of the data access layer, telling unique constraint violations apart
+///
+/// `Conflict` answers `409` naming the conflicting column when the database reports it,
+/// other database errors are answered like [`HandlerError`].
+#[derive(Debug)]
+pub enum AppError {
+    /// A unique constraint was violated, on the given column when known
+    Conflict(Option<String>),
+    Db(DbErr),
+}
+
+impl AppError {
+    /// Classify a database error of a write on `table`
+    pub fn from_db(err: DbErr, table: &str) -> Self {
+        match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(message)) => {
+                AppError::Conflict(conflicting_column(&message, table))
+            }
+            _ => AppError::Db(err),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            AppError::Conflict(Some(column)) => format!("`{}` is already taken", column),
+            AppError::Conflict(None) => "the record already exists".to_string(),
+            AppError::Db(err) => err.to_string(),
+        }
+    }
+}
+
+impl From<DbErr> for AppError {
+    fn from(err: DbErr) -> Self {
+        match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => AppError::Conflict(None),
+            _ => AppError::Db(err),
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::Conflict(_) => None,
+            AppError::Db(err) => Some(err),
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        match self {
+            AppError::Conflict(_) => (
+                StatusCode::CONFLICT,
+                Json(BaseErrorResponse::<()>::new(self.message(), None)),
+            )
+                .into_response(),
+            AppError::Db(err) => HandlerError::from(err).into_response(),
+        }
+    }
+}
+
+/// Column named by a unique violation message
+///
+/// Postgres names the constraint (`"user_username_key"`), SQLite the column
+/// (`UNIQUE constraint failed: user.username`) and MySQL the key (`for key 'user.username'`).
+fn conflicting_column(message: &str, table: &str) -> Option<String> {
+    if let Some((_, columns)) = message.split_once("UNIQUE constraint failed:") {
+        let column = columns.split(',').next()?.trim();
+
+        return Some(column.rsplit('.').next()?.to_string());
+    }
+
+    let name = message
+        .rsplit(['"', '\''])
+        .find(|part| !part.trim().is_empty())?;
+    let name = name.rsplit('.').next()?;
+
+    let column = name
+        .strip_prefix(&format!("{}_", table))
+        .unwrap_or(name)
+        .trim_end_matches("_key")
+        .trim_end_matches("_unique");
+
+    (!column.is_empty()).then(|| column.to_string())
+}
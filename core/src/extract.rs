@@ -0,0 +1,866 @@
+use std::{
+    marker::PhantomData,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+use axum::{
+    extract::{
+        ConnectInfo, FromRequest, FromRequestParts, MatchedPath, OptionalFromRequestParts, Request,
+        path::ErrorKind,
+        rejection::{FormRejection, JsonRejection, PathRejection, QueryRejection},
+    },
+    body::Bytes,
+    http::{HeaderMap, StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+
+use crate::response::{BaseErrorResponse, ValidationErrorResponse};
+
+/// Rejection of the extractors in this module, rendered as a JSON `BaseErrorResponse`
+#[derive(Debug, Clone)]
+pub struct ExtractRejection {
+    status: StatusCode,
+    message: String,
+}
+
+impl ExtractRejection {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl IntoResponse for ExtractRejection {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            axum::Json(BaseErrorResponse::<()>::new(self.message, None)),
+        )
+            .into_response()
+    }
+}
+
+macro_rules! impl_from_rejection {
+    ($($rejection:ty),*) => {
+        $(
+        impl From<$rejection> for ExtractRejection {
+            fn from(rejection: $rejection) -> Self {
+                Self::new(rejection.status(), rejection.body_text())
+            }
+        }
+        )*
+    };
+}
+
+impl_from_rejection!(FormRejection, JsonRejection, PathRejection, QueryRejection);
+
+/// `axum::Json` with a JSON error body when the request body can't be parsed
+///
+/// The body is checked against the [`JsonLimits`] found in the request extensions or
+/// in `AppState`, the defaults otherwise, before being deserialized.
+///
+/// It serializes like `axum::Json` when used as a response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+impl<S, T> FromRequest<S> for Json<T>
+where
+    T: serde::de::DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ExtractRejection;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !has_json_content_type(request.headers()) {
+            return Err(ExtractRejection::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Expected request with `Content-Type: application/json`",
+            ));
+        }
+
+        // a standalone Extension wins over the one registered in AppState
+        let limits = request
+            .extensions()
+            .get::<JsonLimits>()
+            .or_else(|| request.extensions().get::<crate::state::AppState>()?.get::<JsonLimits>())
+            .copied()
+            .unwrap_or_default();
+
+        let bytes = Bytes::from_request(request, state)
+            .await
+            .map_err(|rejection| ExtractRejection::new(rejection.status(), rejection.body_text()))?;
+
+        limits.check(&bytes)?;
+
+        let axum::Json(value) = axum::Json::<T>::from_bytes(&bytes)?;
+
+        Ok(Json(value))
+    }
+}
+
+/// Body sent as JSON or as an urlencoded form, read according to its `Content-Type`
+///
+/// JSON bodies are read like [`Json`], limits included: malformed JSON answers
+/// `400 Bad Request`, and a body of either format that doesn't fit `T` answers
+/// `422 Unprocessable Entity`. Other content types answer `415 Unsupported Media Type`.
+/// `#[controller]` documents both content types as the request body.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[post("/subscribe")]
+/// async fn subscribe(Body(subscription): Body<Subscription>) -> StatusCode { ... }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Body<T>(pub T);
+
+impl<S, T> FromRequest<S> for Body<T>
+where
+    T: serde::de::DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ExtractRejection;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if has_json_content_type(request.headers()) {
+            let Json(value) = Json::<T>::from_request(request, state).await?;
+
+            return Ok(Body(value));
+        }
+
+        if !has_form_content_type(request.headers()) {
+            return Err(ExtractRejection::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Expected request with `Content-Type: application/json` or `application/x-www-form-urlencoded`",
+            ));
+        }
+
+        let axum::Form(value) = axum::Form::<T>::from_request(request, state).await?;
+
+        Ok(Body(value))
+    }
+}
+
+/// The request body exactly as sent, e.g. to check a webhook signature before parsing it
+///
+/// Buffered up to the `axum::extract::DefaultBodyLimit` of the route, larger bodies
+/// answer `413 Payload Too Large`. See [`crate::auth::verify_hmac`].
+#[derive(Debug, Clone, Default)]
+pub struct RawBody(pub Bytes);
+
+impl<S> FromRequest<S> for RawBody
+where
+    S: Send + Sync,
+{
+    type Rejection = ExtractRejection;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let bytes = Bytes::from_request(request, state)
+            .await
+            .map_err(|rejection| ExtractRejection::new(rejection.status(), rejection.body_text()))?;
+
+        Ok(RawBody(bytes))
+    }
+}
+
+/// Bounds on the shape of the JSON bodies read by [`Json`]
+///
+/// serde_json gives up past 128 levels of nesting on its own, these limits are checked
+/// before anything is deserialized and are meant to be much lower.
+///
+/// Usage:
+/// ```rust,ignore
+/// let state = AppState::new(db).with(JsonLimits::new(32, 1000));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonLimits {
+    /// Arrays and objects nested deeper answer `400 Bad Request`
+    pub max_depth: usize,
+    /// More object fields in the whole body answer `413 Payload Too Large`
+    pub max_fields: usize,
+}
+
+impl Default for JsonLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 32,
+            max_fields: 1000,
+        }
+    }
+}
+
+impl JsonLimits {
+    pub fn new(max_depth: usize, max_fields: usize) -> Self {
+        Self { max_depth, max_fields }
+    }
+
+    /// Scan `body` without parsing it, malformed JSON is left to the deserializer
+    pub fn check(&self, body: &[u8]) -> Result<(), ExtractRejection> {
+        let mut depth = 0usize;
+        let mut fields = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        for &byte in body {
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+
+                continue;
+            }
+
+            match byte {
+                b'"' => in_string = true,
+                b'{' | b'[' => {
+                    depth += 1;
+                    if depth > self.max_depth {
+                        return Err(ExtractRejection::new(
+                            StatusCode::BAD_REQUEST,
+                            format!("JSON body is nested deeper than {} levels", self.max_depth),
+                        ));
+                    }
+                }
+                b'}' | b']' => depth = depth.saturating_sub(1),
+                // every object field has exactly one colon outside of strings
+                b':' => {
+                    fields += 1;
+                    if fields > self.max_fields {
+                        return Err(ExtractRejection::new(
+                            StatusCode::PAYLOAD_TOO_LARGE,
+                            format!("JSON body has more than {} fields", self.max_fields),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// `application/json` or any `application/*+json`, like `axum::Json`
+fn has_json_content_type(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers.get(header::CONTENT_TYPE).and_then(|value| value.to_str().ok()) else {
+        return false;
+    };
+
+    let essence = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+
+    essence == "application/json" || (essence.starts_with("application/") && essence.ends_with("+json"))
+}
+
+fn has_form_content_type(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|content_type| content_type.split(';').next())
+        .is_some_and(|essence| essence.trim().eq_ignore_ascii_case("application/x-www-form-urlencoded"))
+}
+
+impl<T> IntoResponse for Json<T>
+where
+    T: serde::Serialize,
+{
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// `axum::extract::Path` with a JSON error body when a segment can't be parsed
+///
+/// The message names the failing segment as declared on the route, also when the
+/// params are extracted as a tuple, e.g. ``invalid path segment `pid`: `abc` is not a valid u64``.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Path<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for Path<T>
+where
+    axum::extract::Path<T>: FromRequestParts<S, Rejection = PathRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ExtractRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Path(value) = axum::extract::Path::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| path_rejection(rejection, parts.extensions.get::<MatchedPath>()))?;
+
+        Ok(Path(value))
+    }
+}
+
+fn path_rejection(rejection: PathRejection, matched: Option<&MatchedPath>) -> ExtractRejection {
+    let PathRejection::FailedToDeserializePathParams(failed) = &rejection else {
+        return rejection.into();
+    };
+
+    let names = matched.map(|path| path_param_names(path.as_str())).unwrap_or_default();
+
+    let (segment, value, expected) = match failed.kind() {
+        ErrorKind::ParseErrorAtKey {
+            key,
+            value,
+            expected_type,
+        } => (Some(key.as_str()), value, *expected_type),
+        ErrorKind::ParseErrorAtIndex {
+            index,
+            value,
+            expected_type,
+        } => (names.get(*index).copied(), value, *expected_type),
+        // a single value extracted from a single param
+        ErrorKind::ParseError { value, expected_type } => {
+            (names.first().copied().filter(|_| names.len() == 1), value, *expected_type)
+        }
+        _ => return rejection.into(),
+    };
+
+    let message = match segment {
+        Some(segment) => format!("invalid path segment `{}`: `{}` is not a valid {}", segment, value, expected),
+        None => format!("invalid path segment: `{}` is not a valid {}", value, expected),
+    };
+
+    ExtractRejection::new(StatusCode::BAD_REQUEST, message)
+}
+
+/// Param names of a route path in order, `/users/{uid}/posts/{pid}` gives `uid` and `pid`
+fn path_param_names(path: &str) -> Vec<&str> {
+    path.split('{')
+        .skip(1)
+        .filter_map(|param| param.split_once('}'))
+        .map(|(name, _)| name.trim_start_matches('*'))
+        .collect()
+}
+
+/// `axum::extract::Query` with a JSON error body when the query string can't be parsed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for Query<T>
+where
+    axum::extract::Query<T>: FromRequestParts<S, Rejection = QueryRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ExtractRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Query(value) = axum::extract::Query::<T>::from_request_parts(parts, state).await?;
+
+        Ok(Query(value))
+    }
+}
+
+/// Header read by [`RequiredHeader`]
+pub trait HeaderName {
+    const NAME: &'static str;
+}
+
+/// Required header parsed into `T`, answering `400` when it is missing or doesn't parse
+///
+/// `#[controller]` documents it as a required header parameter.
+///
+/// Usage:
+/// ```rust,ignore
+/// struct ClientVersion;
+///
+/// impl HeaderName for ClientVersion {
+///     const NAME: &'static str = "X-Client-Version";
+/// }
+///
+/// #[get("/feed")]
+/// async fn feed(version: RequiredHeader<ClientVersion, u32>) -> Json<Feed> {
+///     if version.value < 3 { ... }
+/// }
+/// ```
+pub struct RequiredHeader<H, T> {
+    pub value: T,
+    header: PhantomData<H>,
+}
+
+impl<H, T> RequiredHeader<H, T> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<S, H, T> FromRequestParts<S> for RequiredHeader<H, T>
+where
+    S: Send + Sync,
+    H: HeaderName,
+    T: FromStr,
+{
+    type Rejection = ExtractRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(H::NAME) else {
+            return Err(ExtractRejection::new(
+                StatusCode::BAD_REQUEST,
+                format!("missing `{}` header", H::NAME),
+            ));
+        };
+
+        let value = value
+            .to_str()
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .ok_or_else(|| ExtractRejection::new(StatusCode::BAD_REQUEST, format!("invalid `{}` header", H::NAME)))?;
+
+        Ok(Self {
+            value,
+            header: PhantomData,
+        })
+    }
+}
+
+impl<H, T> utoipa::IntoParams for RequiredHeader<H, T>
+where
+    H: HeaderName,
+    T: utoipa::PartialSchema,
+{
+    fn into_params(
+        _parameter_in_provider: impl Fn() -> Option<utoipa::openapi::path::ParameterIn>,
+    ) -> Vec<utoipa::openapi::path::Parameter> {
+        vec![
+            utoipa::openapi::path::ParameterBuilder::new()
+                .name(H::NAME)
+                .parameter_in(utoipa::openapi::path::ParameterIn::Header)
+                .required(utoipa::openapi::Required::True)
+                .schema(Some(T::schema()))
+                .build(),
+        ]
+    }
+}
+
+/// Entity tag of a conditional header, `"abc"` or the weak `W/"abc"`
+///
+/// Its `Display` is the header form, for the `ETag` response header.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntityTag {
+    pub tag: String,
+    pub weak: bool,
+}
+
+impl EntityTag {
+    pub fn strong(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            weak: false,
+        }
+    }
+
+    pub fn weak(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            weak: true,
+        }
+    }
+
+    /// Same tag and neither is weak, the comparison of `If-Match`
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Same tag regardless of weakness, the comparison of `If-None-Match`
+    pub fn weak_eq(&self, other: &EntityTag) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl FromStr for EntityTag {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let (weak, quoted) = match value.strip_prefix("W/") {
+            Some(quoted) => (true, quoted),
+            None => (false, value),
+        };
+
+        let tag = quoted
+            .strip_prefix('"')
+            .and_then(|quoted| quoted.strip_suffix('"'))
+            .filter(|tag| !tag.contains('"'))
+            .ok_or(())?;
+
+        Ok(Self {
+            tag: tag.to_string(),
+            weak,
+        })
+    }
+}
+
+impl std::fmt::Display for EntityTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.weak {
+            write!(f, "W/")?;
+        }
+
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+/// Value of a conditional header: `*` or a list of entity tags
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ETagMatch {
+    Any,
+    Tags(Vec<EntityTag>),
+}
+
+impl ETagMatch {
+    /// Parse every line of `header`, `None` when it is absent
+    fn from_headers(headers: &HeaderMap, header: header::HeaderName, name: &str) -> Result<Option<Self>, ExtractRejection> {
+        let invalid = || ExtractRejection::new(StatusCode::BAD_REQUEST, format!("invalid `{}` header", name));
+
+        let mut tags = Vec::new();
+        let mut any = false;
+        let mut present = false;
+
+        for line in headers.get_all(header) {
+            present = true;
+
+            for value in line.to_str().map_err(|_| invalid())?.split(',') {
+                match value.trim() {
+                    "*" => any = true,
+                    "" => {}
+                    value => tags.push(value.parse().map_err(|_| invalid())?),
+                }
+            }
+        }
+
+        match (present, any) {
+            (false, _) => Ok(None),
+            // `*` can't be combined with tags
+            (true, true) if !tags.is_empty() => Err(invalid()),
+            (true, true) => Ok(Some(ETagMatch::Any)),
+            (true, false) if tags.is_empty() => Err(invalid()),
+            (true, false) => Ok(Some(ETagMatch::Tags(tags))),
+        }
+    }
+}
+
+/// `If-Match` header, for updates that must only apply to the version the client read
+///
+/// Missing, it answers `428 Precondition Required`. Take an `Option<IfMatch>` for the
+/// header to be optional. `#[controller]` documents it as a header parameter.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[put("/posts/{id}")]
+/// async fn update_post(Path(id): Path<i32>, if_match: IfMatch, Json(post): Json<NewPost>) -> Response {
+///     let current = repository.find(id).await?;
+///
+///     if !if_match.matches(&current.etag()) {
+///         return StatusCode::PRECONDITION_FAILED.into_response();
+///     }
+///     ...
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IfMatch(pub ETagMatch);
+
+impl IfMatch {
+    /// Whether the resource tagged `current` may be changed, by strong comparison
+    pub fn matches(&self, current: &EntityTag) -> bool {
+        match &self.0 {
+            ETagMatch::Any => true,
+            ETagMatch::Tags(tags) => tags.iter().any(|tag| tag.strong_eq(current)),
+        }
+    }
+}
+
+/// `If-None-Match` header, for answering `304 Not Modified` to a client holding the
+/// current version, or refusing to create a resource that exists with `*`
+///
+/// Missing, it answers `428 Precondition Required`. Take an `Option<IfNoneMatch>` for
+/// the header to be optional. `#[controller]` documents it as a header parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IfNoneMatch(pub ETagMatch);
+
+impl IfNoneMatch {
+    /// Whether the client holds the resource tagged `current`, by weak comparison
+    pub fn matches(&self, current: &EntityTag) -> bool {
+        match &self.0 {
+            ETagMatch::Any => true,
+            ETagMatch::Tags(tags) => tags.iter().any(|tag| tag.weak_eq(current)),
+        }
+    }
+}
+
+macro_rules! impl_conditional_header {
+    ($($extractor:ident => ($header:expr, $name:literal)),*) => {
+        $(
+            impl<S> FromRequestParts<S> for $extractor
+            where
+                S: Send + Sync,
+            {
+                type Rejection = ExtractRejection;
+
+                async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+                    ETagMatch::from_headers(&parts.headers, $header, $name)?.map($extractor).ok_or_else(|| {
+                        ExtractRejection::new(StatusCode::PRECONDITION_REQUIRED, concat!("missing `", $name, "` header"))
+                    })
+                }
+            }
+
+            impl<S> OptionalFromRequestParts<S> for $extractor
+            where
+                S: Send + Sync,
+            {
+                type Rejection = ExtractRejection;
+
+                async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Option<Self>, Self::Rejection> {
+                    Ok(ETagMatch::from_headers(&parts.headers, $header, $name)?.map($extractor))
+                }
+            }
+
+            impl utoipa::IntoParams for $extractor {
+                fn into_params(
+                    _parameter_in_provider: impl Fn() -> Option<utoipa::openapi::path::ParameterIn>,
+                ) -> Vec<utoipa::openapi::path::Parameter> {
+                    vec![
+                        utoipa::openapi::path::ParameterBuilder::new()
+                            .name($name)
+                            .parameter_in(utoipa::openapi::path::ParameterIn::Header)
+                            .required(utoipa::openapi::Required::True)
+                            .schema(Some(<String as utoipa::PartialSchema>::schema()))
+                            .build(),
+                    ]
+                }
+            }
+        )*
+    };
+}
+
+impl_conditional_header!(
+    IfMatch => (header::IF_MATCH, "If-Match"),
+    IfNoneMatch => (header::IF_NONE_MATCH, "If-None-Match")
+);
+
+/// Checks a deserialized request body
+///
+/// Usage:
+/// ```rust,ignore
+/// impl Validate for NewUser {
+///     fn validate(&self) -> Result<(), ValidationErrorResponse> {
+///         let mut errors = ValidationErrorResponse::new();
+///
+///         if self.username.len() < 3 {
+///             errors = errors.field("username", "must be at least 3 characters");
+///         }
+///
+///         errors.into_result()
+///     }
+/// }
+///
+/// async fn create_user(Valid(user): Valid<NewUser>) -> Json<User> { ... }
+/// ```
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrorResponse>;
+}
+
+/// [`Json`] body that also passes [`Validate`], answering `422` when it doesn't
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Valid<T>(pub T);
+
+impl<S, T> FromRequest<S> for Valid<T>
+where
+    Json<T>: FromRequest<S, Rejection = ExtractRejection>,
+    T: Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        value.validate().map_err(IntoResponse::into_response)?;
+
+        Ok(Valid(value))
+    }
+}
+
+/// Forwarding header written by the trusted reverse proxies, see [`TrustedProxies::header`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ForwardedHeader {
+    /// `X-Forwarded-For`, e.g. nginx' `$proxy_add_x_forwarded_for`
+    #[default]
+    XForwardedFor,
+    /// `Forwarded` of RFC 7239, its `for=` parameter
+    Forwarded,
+    /// `X-Real-IP`, a single address
+    XRealIp,
+}
+
+impl ForwardedHeader {
+    fn name(&self) -> &'static str {
+        match self {
+            ForwardedHeader::XForwardedFor => "x-forwarded-for",
+            ForwardedHeader::Forwarded => "forwarded",
+            ForwardedHeader::XRealIp => "x-real-ip",
+        }
+    }
+}
+
+impl FromStr for ForwardedHeader {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "x-forwarded-for" => Ok(ForwardedHeader::XForwardedFor),
+            "forwarded" => Ok(ForwardedHeader::Forwarded),
+            "x-real-ip" => Ok(ForwardedHeader::XRealIp),
+            other => Err(anyhow::anyhow!(
+                "unknown forwarded header `{}`, expected `x-forwarded-for`, `forwarded` or `x-real-ip`",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ForwardedHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Addresses of the reverse proxies allowed to report the client address
+///
+/// Register it in `AppState` (or as an `Extension`) for [`ClientIp`] to read
+/// forwarding headers. Without it, every forwarding header is ignored.
+///
+/// Usage:
+/// ```rust,ignore
+/// let proxies = TrustedProxies::new(vec!["10.0.0.1".parse()?]).header(ForwardedHeader::Forwarded);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    proxies: Vec<IpAddr>,
+    header: ForwardedHeader,
+}
+
+impl TrustedProxies {
+    /// Proxies writing `X-Forwarded-For`
+    pub fn new(proxies: Vec<IpAddr>) -> Self {
+        Self {
+            proxies,
+            header: ForwardedHeader::default(),
+        }
+    }
+
+    /// The only header read for the client address, the others may come from the client
+    pub fn header(mut self, header: ForwardedHeader) -> Self {
+        self.header = header;
+        self
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        self.proxies.contains(ip)
+    }
+}
+
+/// Address of the client, as seen through the trusted reverse proxies
+///
+/// When the peer is a trusted proxy, the address is taken from the header the proxies
+/// write, see [`TrustedProxies::header`], the other forwarding headers are ignored. The
+/// forwarded chain is read from the right, skipping trusted proxies, so a client can't
+/// spoof its address by prepending entries. A hop that doesn't parse, e.g. `for=unknown`,
+/// ends the walk at the proxy that reported it. Otherwise the peer address is the client.
+///
+/// Needs the server to run with `into_make_service_with_connect_info::<SocketAddr>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(ConnectInfo(peer)) = parts.extensions.get::<ConnectInfo<SocketAddr>>() else {
+            tracing::error!("no ConnectInfo available, serve the app with `into_make_service_with_connect_info`");
+
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        };
+
+        // a standalone Extension wins over the one registered in AppState
+        let trusted = parts.extensions.get::<TrustedProxies>().or_else(|| {
+            parts
+                .extensions
+                .get::<crate::state::AppState>()?
+                .get::<TrustedProxies>()
+        });
+
+        let peer = peer.ip();
+
+        let Some(trusted) = trusted.filter(|trusted| trusted.contains(&peer)) else {
+            return Ok(ClientIp(peer));
+        };
+
+        // the closest untrusted hop is the client, if every hop is a proxy the first one is
+        let mut client = peer;
+        for hop in forwarded_chain(&parts.headers, trusted.header).iter().rev() {
+            let Some(ip) = hop else {
+                break;
+            };
+
+            client = *ip;
+            if !trusted.contains(ip) {
+                break;
+            }
+        }
+
+        Ok(ClientIp(client))
+    }
+}
+
+/// Hops of the forwarding `header`, client first, `None` for the ones that don't parse
+fn forwarded_chain(headers: &HeaderMap, header: ForwardedHeader) -> Vec<Option<IpAddr>> {
+    let entries = headers
+        .get_all(header.name())
+        .iter()
+        .flat_map(|value| match value.to_str() {
+            Ok(value) => value.split(',').map(|entry| Some(entry.trim())).collect(),
+            Err(_) => vec![None],
+        });
+
+    match header {
+        ForwardedHeader::Forwarded => entries
+            .map(|element| {
+                element?.split(';').find_map(|pair| {
+                    let (key, value) = pair.trim().split_once('=')?;
+
+                    key.trim().eq_ignore_ascii_case("for").then(|| parse_ip(value))?
+                })
+            })
+            .collect(),
+        ForwardedHeader::XForwardedFor | ForwardedHeader::XRealIp => entries.map(|ip| parse_ip(ip?)).collect(),
+    }
+}
+
+/// Parse `1.2.3.4`, `1.2.3.4:80`, `2001:db8::1` or `"[2001:db8::1]:4711"`
+fn parse_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim().trim_matches('"');
+
+    value
+        .parse::<IpAddr>()
+        .ok()
+        .or_else(|| value.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .or_else(|| value.trim_start_matches('[').trim_end_matches(']').parse().ok())
+}
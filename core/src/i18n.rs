@@ -0,0 +1,126 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    sync::{LazyLock, RwLock},
+};
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+/// Language used when none of the requested languages has a message
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Error messages keyed by language and then by error code
+static CATALOG: LazyLock<RwLock<HashMap<String, HashMap<String, String>>>> = LazyLock::new(|| {
+    let english = [
+        ("bad_request", "The request is invalid."),
+        ("unauthorized", "Authentication is required."),
+        ("forbidden", "You are not allowed to perform this action."),
+        ("not_found", "The requested resource was not found."),
+        ("conflict", "The resource already exists."),
+        ("validation_failed", "The given data is invalid."),
+        ("too_many_requests", "Too many requests, please try again later."),
+        ("internal_error", "Something went wrong on our side."),
+        ("service_unavailable", "The service is temporarily unavailable."),
+    ]
+    .into_iter()
+    .map(|(code, message)| (code.to_string(), message.to_string()))
+    .collect();
+
+    RwLock::new(HashMap::from([(DEFAULT_LANGUAGE.to_string(), english)]))
+});
+
+/// Register (or extend) the messages of a language
+///
+/// Usage:
+/// ```rust,ignore
+/// argon_core::i18n::register_language("fa", [
+///     ("not_found", "منبع درخواستی یافت نشد."),
+///     ("unauthorized", "احراز هویت لازم است."),
+/// ]);
+/// ```
+pub fn register_language<I, C, M>(language: &str, messages: I)
+where
+    I: IntoIterator<Item = (C, M)>,
+    C: Into<String>,
+    M: Into<String>,
+{
+    let mut catalog = CATALOG.write().unwrap_or_else(|err| err.into_inner());
+
+    catalog
+        .entry(language.to_lowercase())
+        .or_default()
+        .extend(messages.into_iter().map(|(code, message)| (code.into(), message.into())));
+}
+
+/// Resolve the message for `code` in the first requested language that has one
+///
+/// Falls back to [`DEFAULT_LANGUAGE`] and then to the code itself.
+pub fn translate(code: &str, languages: &AcceptLanguage) -> String {
+    let catalog = CATALOG.read().unwrap_or_else(|err| err.into_inner());
+
+    languages
+        .candidates()
+        .chain(std::iter::once(DEFAULT_LANGUAGE))
+        .find_map(|language| catalog.get(language)?.get(code))
+        .cloned()
+        .unwrap_or_else(|| code.to_string())
+}
+
+/// Languages from the `Accept-Language` header, most preferred first
+///
+/// Never rejects: a missing or malformed header yields an empty list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AcceptLanguage(pub Vec<String>);
+
+impl AcceptLanguage {
+    pub fn parse(header: &str) -> Self {
+        let mut languages: Vec<(String, f32)> = header
+            .split(',')
+            .filter_map(|part| {
+                let mut pieces = part.trim().split(';');
+                let language = pieces.next()?.trim().to_lowercase();
+
+                if language.is_empty() || language == "*" {
+                    return None;
+                }
+
+                let quality = pieces
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|quality| quality.parse().ok())
+                    .unwrap_or(1.0);
+
+                Some((language, quality))
+            })
+            .collect();
+
+        // stable sort keeps the header order for equal weights
+        languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        Self(languages.into_iter().map(|(language, _)| language).collect())
+    }
+
+    /// Requested languages followed by their primary subtag (`fa-IR` then `fa`)
+    fn candidates(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().flat_map(|language| {
+            let primary = language.split('-').next().filter(|primary| *primary != language);
+
+            std::iter::once(language.as_str()).chain(primary)
+        })
+    }
+}
+
+impl<S> FromRequestParts<S> for AcceptLanguage
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .headers
+            .get(axum::http::header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(AcceptLanguage::parse)
+            .unwrap_or_default())
+    }
+}
@@ -1,5 +1,31 @@
 pub mod auth;
+pub mod bootstrap;
 pub mod config;
 pub mod controller;
+pub mod db;
+pub mod docs;
+pub mod error;
+pub mod extract;
+pub mod i18n;
+pub mod lifecycle;
+pub mod limits;
+pub mod logging;
+pub mod maintenance;
 pub mod model;
+pub mod panic;
+pub mod plugin;
+pub mod repository;
+pub mod resilience;
 pub mod response;
+pub mod routing;
+pub mod server;
+pub mod singleflight;
+pub mod state;
+pub mod tasks;
+#[cfg(feature = "test-util")]
+pub mod testing;
+pub mod ws;
+
+// used by the code generated by `#[controller]`
+#[doc(hidden)]
+pub use inventory;
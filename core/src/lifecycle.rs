@@ -0,0 +1,48 @@
+use std::{net::SocketAddr, sync::LazyLock};
+
+use crate::ws::{Broadcaster, Received, Subscription};
+
+/// Stage of the server process, published by the bootstrap code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    Starting,
+    /// The listener is bound, connections to this address are accepted from now on
+    Listening(SocketAddr),
+    ShuttingDown,
+    Stopped,
+}
+
+// a handful of events per process, subscribers never lag behind
+static EVENTS: LazyLock<Broadcaster<LifecycleEvent>> = LazyLock::new(|| Broadcaster::new(16));
+
+/// Publish `event` to every current subscriber
+pub fn publish(event: LifecycleEvent) {
+    tracing::debug!("lifecycle event {:?}", event);
+
+    EVENTS.publish(event);
+}
+
+/// Events published from now on, earlier ones are not replayed
+///
+/// Usage:
+/// ```rust,ignore
+/// let mut events = lifecycle::subscribe();
+/// tokio::spawn(init_server());
+///
+/// let addr = lifecycle::listening(&mut events).await.expect("the server should start");
+/// reqwest::get(format!("http://{}/health", addr)).await?;
+/// ```
+pub fn subscribe() -> Subscription<LifecycleEvent> {
+    EVENTS.subscribe()
+}
+
+/// Wait for the `Listening` event, `None` if the server stopped before
+pub async fn listening(events: &mut Subscription<LifecycleEvent>) -> Option<SocketAddr> {
+    loop {
+        match events.recv().await? {
+            Received::Message(LifecycleEvent::Listening(addr)) => return Some(addr),
+            Received::Message(LifecycleEvent::Stopped) => return None,
+            Received::Message(_) | Received::Lagged(_) => {}
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::future::Ready;
+
+use axum::{
+    BoxError, Json,
+    error_handling::HandleErrorLayer,
+    extract::{Request, State},
+    http::{HeaderMap, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tower::{
+    ServiceBuilder,
+    layer::util::{Identity, Stack},
+    limit::GlobalConcurrencyLimitLayer,
+    load_shed::LoadShedLayer,
+};
+
+use crate::response::BaseErrorResponse;
+
+/// Seconds a client is asked to wait before retrying once the server is saturated
+const OVERLOADED_RETRY_AFTER_SECS: u64 = 1;
+
+/// Upper bounds on the request line and headers, checked before any routing
+///
+/// Usage:
+/// ```rust,ignore
+/// router.layer(axum::middleware::from_fn_with_state(
+///     RequestLimits::new(8 * 1024, 16 * 1024),
+///     limit_request,
+/// ))
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RequestLimits {
+    pub max_uri_len: usize,
+    pub max_header_bytes: usize,
+}
+
+impl RequestLimits {
+    pub fn new(max_uri_len: usize, max_header_bytes: usize) -> Self {
+        Self {
+            max_uri_len,
+            max_header_bytes,
+        }
+    }
+}
+
+/// Rejects requests with `414 URI Too Long` or `431 Request Header Fields Too Large`
+///
+/// The header size is the sum of every header name and value length.
+pub async fn limit_request(
+    State(limits): State<RequestLimits>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let uri_len = request.uri().to_string().len();
+    if uri_len > limits.max_uri_len {
+        tracing::debug!("rejecting a {} bytes uri", uri_len);
+
+        return Err(StatusCode::URI_TOO_LONG);
+    }
+
+    let header_bytes: usize = request
+        .headers()
+        .iter()
+        .map(|(name, value)| name.as_str().len() + value.len())
+        .sum();
+    if header_bytes > limits.max_header_bytes {
+        tracing::debug!("rejecting {} bytes of headers", header_bytes);
+
+        return Err(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Bodies [`check_content_length`] buffers to compare with their `Content-Length`
+///
+/// Larger declared lengths are passed through unchecked rather than held in memory,
+/// the default matches `axum::extract::DefaultBodyLimit`.
+#[derive(Debug, Clone, Copy)]
+pub struct ContentLengthCheck {
+    pub max_buffered: usize,
+}
+
+impl Default for ContentLengthCheck {
+    fn default() -> Self {
+        Self {
+            max_buffered: 2 * 1024 * 1024,
+        }
+    }
+}
+
+impl ContentLengthCheck {
+    pub fn new(max_buffered: usize) -> Self {
+        Self { max_buffered }
+    }
+}
+
+/// Rejects with `400 Bad Request` a body whose length differs from its `Content-Length`
+///
+/// Conflicting `Content-Length` values and a `Content-Length` sent along with
+/// `Transfer-Encoding` are rejected too, both are ways to smuggle a request past a
+/// proxy. Checked bodies are buffered, handlers then read them from memory.
+///
+/// Usage:
+/// ```rust,ignore
+/// router.layer(axum::middleware::from_fn_with_state(
+///     ContentLengthCheck::default(),
+///     check_content_length,
+/// ))
+/// ```
+pub async fn check_content_length(
+    State(check): State<ContentLengthCheck>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let mut declared = request.headers().get_all(header::CONTENT_LENGTH).iter();
+    let Some(length) = declared.next() else {
+        return Ok(next.run(request).await);
+    };
+
+    if declared.any(|other| other != length) || request.headers().contains_key(header::TRANSFER_ENCODING) {
+        tracing::debug!("rejecting a request with an ambiguous body length");
+
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let length: usize = length
+        .to_str()
+        .ok()
+        .and_then(|length| length.trim().parse().ok())
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    if length > check.max_buffered {
+        return Ok(next.run(request).await);
+    }
+
+    let (parts, body) = request.into_parts();
+
+    // one byte over the declared length is enough to tell the body is longer
+    let Ok(body) = axum::body::to_bytes(body, length + 1).await else {
+        tracing::debug!("rejecting a body longer than its {} bytes content length", length);
+
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    if body.len() != length {
+        tracing::debug!("rejecting a {} bytes body declared as {} bytes", body.len(), length);
+
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    Ok(next.run(Request::from_parts(parts, axum::body::Body::from(body))).await)
+}
+
+/// Media types [`require_content_type`] accepts for the body of a route
+#[derive(Debug, Clone)]
+pub struct AcceptedContentTypes {
+    pub types: Vec<String>,
+}
+
+impl AcceptedContentTypes {
+    pub fn new<I, T>(types: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            types: types.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn json() -> Self {
+        Self::new(["application/json"])
+    }
+
+    /// Whether the `Content-Type` is one of the types, parameters like `charset` aside
+    pub fn accepts(&self, headers: &HeaderMap) -> bool {
+        let Some(content_type) = headers.get(header::CONTENT_TYPE).and_then(|value| value.to_str().ok()) else {
+            return false;
+        };
+
+        let essence = content_type.split(';').next().unwrap_or("").trim();
+
+        self.types.iter().any(|accepted| accepted.eq_ignore_ascii_case(essence))
+    }
+}
+
+/// Rejects with `415 Unsupported Media Type` a `POST`, `PUT` or `PATCH` whose body isn't
+/// one of the accepted types
+///
+/// The error lists the accepted types, and so does the `Accept-Post` or `Accept-Patch`
+/// header. Other methods and requests without a body go through. Routes of a controller
+/// declare their types with `#[accepts(...)]` instead.
+///
+/// Usage:
+/// ```rust,ignore
+/// router.layer(axum::middleware::from_fn_with_state(
+///     AcceptedContentTypes::json(),
+///     require_content_type,
+/// ))
+/// ```
+pub async fn require_content_type(
+    State(accepted): State<AcceptedContentTypes>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let mutating = method == Method::POST || method == Method::PUT || method == Method::PATCH;
+
+    let has_body = request.headers().contains_key(header::TRANSFER_ENCODING)
+        || request
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .is_some_and(|length| length.as_bytes() != b"0");
+
+    if !mutating || accepted.accepts(request.headers()) || (!has_body && !request.headers().contains_key(header::CONTENT_TYPE)) {
+        return next.run(request).await;
+    }
+
+    let types = accepted.types.join(", ");
+    tracing::debug!("rejecting a {} body, expected one of {}", method, types);
+
+    let mut response = (
+        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        Json(BaseErrorResponse::<()>::new(
+            format!("unsupported content type, expected one of: {}", types),
+            None,
+        )),
+    )
+        .into_response();
+
+    let accept_header = match method {
+        Method::POST => Some("accept-post"),
+        Method::PATCH => Some("accept-patch"),
+        _ => None,
+    };
+    if let Some(name) = accept_header
+        && let Ok(value) = types.parse()
+    {
+        response.headers_mut().insert(name, value);
+    }
+
+    response
+}
+
+/// Layer returned by [`concurrency_limit`]
+pub type ConcurrencyLimit = ServiceBuilder<
+    Stack<GlobalConcurrencyLimitLayer, Stack<LoadShedLayer, Stack<HandleErrorLayer<fn(BoxError) -> Ready<Response>, ()>, Identity>>>,
+>;
+
+/// Caps the requests handled at once, answering the excess with `503 Service Unavailable`
+///
+/// Requests over the limit are shed right away with a `Retry-After` header instead of
+/// waiting for a slot, so a burst can't pile up unboundedly. The limit is shared by every
+/// route the layer is applied to.
+///
+/// Usage:
+/// ```rust,ignore
+/// router.layer(concurrency_limit(1024))
+/// ```
+pub fn concurrency_limit(max_in_flight: usize) -> ConcurrencyLimit {
+    ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(overloaded as fn(BoxError) -> Ready<Response>))
+        .load_shed()
+        // `Router::layer` wraps each route, a plain `ConcurrencyLimitLayer` would limit them one by one
+        .layer(GlobalConcurrencyLimitLayer::new(max_in_flight))
+}
+
+fn overloaded(err: BoxError) -> Ready<Response> {
+    let response = if err.is::<tower::load_shed::error::Overloaded>() {
+        tracing::debug!("too many requests in flight, shedding the request");
+
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, OVERLOADED_RETRY_AFTER_SECS.to_string())],
+        )
+            .into_response()
+    } else {
+        tracing::error!("unhandled error while limiting concurrency: {:?}", err);
+
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    };
+
+    std::future::ready(response)
+}
@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures_util::{StreamExt, stream};
+
+const REDACTED: &str = "***";
+
+/// Largest body [`BodyLogger`] buffers by default, see [`BodyLogger::max_body_bytes`]
+pub const DEFAULT_MAX_LOGGED_BODY: usize = 64 * 1024;
+
+/// Logs request and response bodies at `trace` level, redacting sensitive JSON fields
+///
+/// Fields named `password`, `token` or `secret` (case insensitive, at any depth) are
+/// replaced with `***`. Non JSON bodies are only logged by size. Bodies are buffered
+/// only while `trace` is enabled and handed on unchanged. Past
+/// [`BodyLogger::max_body_bytes`] they are streamed on and logged as `<N bytes, truncated>`.
+///
+/// Usage:
+/// ```rust,ignore
+/// router.layer(axum::middleware::from_fn_with_state(
+///     BodyLogger::new().redact("api_key"),
+///     log_bodies,
+/// ))
+/// ```
+#[derive(Clone)]
+pub struct BodyLogger {
+    redacted: Arc<Vec<String>>,
+    max_body_bytes: usize,
+}
+
+impl Default for BodyLogger {
+    fn default() -> Self {
+        Self {
+            redacted: Arc::new(vec![
+                "password".to_string(),
+                "token".to_string(),
+                "secret".to_string(),
+            ]),
+            max_body_bytes: DEFAULT_MAX_LOGGED_BODY,
+        }
+    }
+}
+
+impl BodyLogger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Redact another field name on top of the defaults
+    pub fn redact(mut self, field: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.redacted).push(field.into().to_lowercase());
+        self
+    }
+
+    /// Buffer and log bodies up to `max_body_bytes`, [`DEFAULT_MAX_LOGGED_BODY`] by default
+    pub fn max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// The body as it would be logged
+    pub fn render(&self, headers: &HeaderMap, body: &[u8]) -> String {
+        let is_json = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("application/json"));
+
+        if !is_json {
+            return format!("<{} bytes>", body.len());
+        }
+
+        match serde_json::from_slice::<serde_json::Value>(body) {
+            Ok(mut value) => {
+                self.redact_value(&mut value);
+                value.to_string()
+            }
+            Err(_) => format!("<{} bytes of invalid json>", body.len()),
+        }
+    }
+
+    fn redact_value(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    if self.redacted.contains(&key.to_lowercase()) {
+                        *value = serde_json::Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_value(value);
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => values.iter_mut().for_each(|value| self.redact_value(value)),
+            _ => {}
+        }
+    }
+}
+
+pub async fn log_bodies(State(logger): State<BodyLogger>, request: Request, next: Next) -> Response {
+    if !tracing::enabled!(tracing::Level::TRACE) {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let Some(buffered) = buffer(body, logger.max_body_bytes).await else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    tracing::trace!(
+        method = %parts.method,
+        uri = %parts.uri,
+        "request body: {}",
+        buffered.render(&logger, &parts.headers)
+    );
+
+    let response = next.run(Request::from_parts(parts, buffered.into_body())).await;
+
+    let (parts, body) = response.into_parts();
+    let Some(buffered) = buffer(body, logger.max_body_bytes).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    tracing::trace!(
+        status = %parts.status,
+        "response body: {}",
+        buffered.render(&logger, &parts.headers)
+    );
+
+    Response::from_parts(parts, buffered.into_body())
+}
+
+/// A body read for logging, whole or only up to the cap
+enum Buffered {
+    Full(Bytes),
+    /// The untouched body and its size, or the bytes read before passing the cap
+    Truncated(Body, u64),
+}
+
+impl Buffered {
+    fn render(&self, logger: &BodyLogger, headers: &HeaderMap) -> String {
+        match self {
+            Buffered::Full(bytes) => logger.render(headers, bytes),
+            Buffered::Truncated(_, size) => format!("<{} bytes, truncated>", size),
+        }
+    }
+
+    fn into_body(self) -> Body {
+        match self {
+            Buffered::Full(bytes) => Body::from(bytes),
+            Buffered::Truncated(body, _) => body,
+        }
+    }
+}
+
+/// Read `body` up to `max` bytes, handing the rest on as a stream past it
+async fn buffer(body: Body, max: usize) -> Option<Buffered> {
+    if let Some(size) = body.size_hint().exact()
+        && size > max as u64
+    {
+        return Some(Buffered::Truncated(body, size));
+    }
+
+    let mut data = body.into_data_stream();
+    let mut chunks = Vec::new();
+    let mut read = 0;
+
+    while let Some(chunk) = data.next().await {
+        let chunk = chunk
+            .inspect_err(|err| tracing::error!("cannot buffer the body for logging: {:?}", err))
+            .ok()?;
+
+        read += chunk.len();
+        chunks.push(chunk);
+
+        if read > max {
+            let body = Body::from_stream(stream::iter(chunks.into_iter().map(Ok)).chain(data));
+
+            return Some(Buffered::Truncated(body, read as u64));
+        }
+    }
+
+    Some(Buffered::Full(chunks.concat().into()))
+}
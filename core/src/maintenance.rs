@@ -0,0 +1,110 @@
+use std::{
+    net::IpAddr,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use axum::{
+    Json,
+    extract::{FromRequestParts, Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{extract::ClientIp, response::BaseErrorResponse};
+
+/// Whether [`maintenance`] turns requests away, flipped at runtime by [`set_enabled`]
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn maintenance mode on or off, taking effect on the next request
+///
+/// Usage:
+/// ```rust,ignore
+/// // e.g. from an admin route or a signal handler
+/// argon_core::maintenance::set_enabled(true);
+/// ```
+pub fn set_enabled(enabled: bool) {
+    if ENABLED.swap(enabled, Ordering::Relaxed) != enabled {
+        tracing::warn!("maintenance mode turned {}", if enabled { "on" } else { "off" });
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Requests [`maintenance`] lets through while maintenance mode is on
+///
+/// `/health` is allowed by default so orchestrators don't restart the instance.
+#[derive(Debug, Clone)]
+pub struct MaintenanceMode {
+    pub allowed_ips: Vec<IpAddr>,
+    pub allowed_paths: Vec<String>,
+    pub retry_after: Duration,
+}
+
+impl Default for MaintenanceMode {
+    fn default() -> Self {
+        Self {
+            allowed_ips: Vec::new(),
+            allowed_paths: vec!["/health".to_string()],
+            retry_after: Duration::from_secs(60),
+        }
+    }
+}
+
+impl MaintenanceMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allowed_ips(mut self, allowed_ips: Vec<IpAddr>) -> Self {
+        self.allowed_ips = allowed_ips;
+        self
+    }
+
+    pub fn allow_path(mut self, path: impl Into<String>) -> Self {
+        self.allowed_paths.push(path.into());
+        self
+    }
+
+    pub fn retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+}
+
+/// Answers `503 Service Unavailable` with a `Retry-After` while maintenance mode is on
+///
+/// The allowed paths and the requests of allowed IPs still go through, the client IP is
+/// read as [`ClientIp`] does. Nothing is checked while maintenance mode is off.
+///
+/// Usage:
+/// ```rust,ignore
+/// router.layer(axum::middleware::from_fn_with_state(
+///     MaintenanceMode::new().allowed_ips(vec!["10.0.0.5".parse()?]),
+///     maintenance,
+/// ))
+/// ```
+pub async fn maintenance(State(mode): State<MaintenanceMode>, request: Request, next: Next) -> Response {
+    if !is_enabled() || mode.allowed_paths.iter().any(|path| path == request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+
+    if !mode.allowed_ips.is_empty()
+        && let Ok(ClientIp(ip)) = ClientIp::from_request_parts(&mut parts, &()).await
+        && mode.allowed_ips.contains(&ip)
+    {
+        return next.run(Request::from_parts(parts, body)).await;
+    }
+
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, mode.retry_after.as_secs().to_string())],
+        Json(BaseErrorResponse::<()>::new("the service is under maintenance", None)),
+    )
+        .into_response()
+}
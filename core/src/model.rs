@@ -1,14 +1,20 @@
 // use std::ops::Deref;
 
-use std::fmt::Display;
+pub mod audit;
 
-use sea_orm::FromJsonQueryResult;
-use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+use chrono::{DateTime, FixedOffset, NaiveDateTime, SecondsFormat, Utc};
+use sea_orm::{ActiveValue, FromJsonQueryResult};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub use audit::Auditable;
+
+/// Content in several languages, one entry per language
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult, utoipa::ToSchema)]
 pub struct MultilangField(pub Vec<LangField>);
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct LangField {
     pub lang: String,
     pub content: String,
@@ -22,6 +28,13 @@ impl MultilangField {
     pub fn get_language(&self, language: &str) -> Option<&LangField> {
         self.0.iter().find(|x| x.lang == language)
     }
+
+    /// Content for `language`, falling back to the first available language
+    pub fn localized(&self, language: &str) -> Option<String> {
+        self.get_language(language)
+            .or_else(|| self.0.first())
+            .map(|field| field.content.clone())
+    }
 }
 
 impl LangField {
@@ -36,6 +49,203 @@ impl Display for LangField {
     }
 }
 
+/// `(lang, content)`
+impl From<(String, String)> for LangField {
+    fn from((lang, content): (String, String)) -> Self {
+        LangField::new(lang, content)
+    }
+}
+
+impl From<(&str, &str)> for LangField {
+    fn from((lang, content): (&str, &str)) -> Self {
+        LangField::new(lang.to_string(), content.to_string())
+    }
+}
+
+impl From<LangField> for (String, String) {
+    fn from(field: LangField) -> Self {
+        (field.lang, field.content)
+    }
+}
+
+/// Field of a PATCH body telling "leave unchanged" apart from "set to null"
+///
+/// An absent field is `Missing`, an explicit `null` is `Null` and anything else is `Value`.
+/// The field needs `#[serde(default)]` for absent fields to deserialize, and
+/// `#[schema(value_type = Option<T>)]` to be documented.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[derive(Deserialize, ToSchema)]
+/// pub struct UpdateUser {
+///     #[serde(default)]
+///     #[schema(value_type = Option<String>)]
+///     pub nickname: Patch<String>,
+/// }
+///
+/// let mut user: user::ActiveModel = user.into();
+/// body.nickname.apply(&mut user.nickname);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Patch<T> {
+    #[default]
+    Missing,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Patch::Missing)
+    }
+
+    /// `None` when missing, `Some(None)` when null
+    pub fn into_option(self) -> Option<Option<T>> {
+        match self {
+            Patch::Missing => None,
+            Patch::Null => Some(None),
+            Patch::Value(value) => Some(Some(value)),
+        }
+    }
+
+    /// Set a nullable column of an `ActiveModel`, leaving it untouched when missing
+    pub fn apply(self, field: &mut ActiveValue<Option<T>>)
+    where
+        Option<T>: Into<sea_orm::Value>,
+    {
+        if let Some(value) = self.into_option() {
+            *field = ActiveValue::Set(value);
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Patch<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // only called when the field is present, absent fields fall back to `Default`
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => Patch::Value(value),
+            None => Patch::Null,
+        })
+    }
+}
+
+/// UTC instant always written as RFC 3339, e.g. `2025-01-31T12:00:00Z`
+///
+/// Drivers hand timestamps back in different shapes, convert them to a `Timestamp` in
+/// responses so they are all written the same way. Naive timestamps, like the `created_at`
+/// of the `user` table, are read as UTC.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[derive(Serialize, ToSchema)]
+/// pub struct UserResponse {
+///     pub created_at: Timestamp,
+/// }
+///
+/// let response = UserResponse { created_at: user.created_at.into() };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub DateTime<Utc>);
+
+impl Timestamp {
+    pub fn now() -> Self {
+        Timestamp(Utc::now())
+    }
+
+    /// Seconds and only the fraction digits needed, with a `Z` offset
+    pub fn to_rfc3339(&self) -> String {
+        self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_rfc3339())
+    }
+}
+
+/// Any RFC 3339 offset is accepted and converted to UTC
+impl FromStr for Timestamp {
+    type Err = chrono::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Timestamp(DateTime::parse_from_rfc3339(s.trim())?.with_timezone(&Utc)))
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_rfc3339())
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        value
+            .parse()
+            .map_err(|err| serde::de::Error::custom(format!("`{}` is not an RFC 3339 timestamp: {}", value, err)))
+    }
+}
+
+impl utoipa::PartialSchema for Timestamp {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .format(Some(utoipa::openapi::SchemaFormat::KnownFormat(
+                utoipa::openapi::KnownFormat::DateTime,
+            )))
+            .into()
+    }
+}
+
+impl utoipa::ToSchema for Timestamp {}
+
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(value: DateTime<Utc>) -> Self {
+        Timestamp(value)
+    }
+}
+
+impl From<DateTime<FixedOffset>> for Timestamp {
+    fn from(value: DateTime<FixedOffset>) -> Self {
+        Timestamp(value.with_timezone(&Utc))
+    }
+}
+
+/// Read as UTC
+impl From<NaiveDateTime> for Timestamp {
+    fn from(value: NaiveDateTime) -> Self {
+        Timestamp(value.and_utc())
+    }
+}
+
+impl From<Timestamp> for DateTime<Utc> {
+    fn from(value: Timestamp) -> Self {
+        value.0
+    }
+}
+
+/// In UTC
+impl From<Timestamp> for NaiveDateTime {
+    fn from(value: Timestamp) -> Self {
+        value.0.naive_utc()
+    }
+}
+
 // idk? should i?
 // impl Deref for LangField {
 //     type Target = str;
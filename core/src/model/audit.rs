@@ -0,0 +1,235 @@
+use std::fmt::Display;
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ConnectionTrait, DbErr, EntityName, EntityTrait, Iterable, ModelTrait,
+    PrimaryKeyToColumn,
+    sea_query::{Alias, Query, SimpleExpr, Value, sea_value_to_json_value},
+};
+
+use crate::auth::AuthenticatableUser;
+
+/// Table the audit events are written to, see the `create_audit_log_table` migration
+pub const AUDIT_TABLE: &str = "audit_log";
+
+tokio::task_local! {
+    static ACTOR: Option<String>;
+}
+
+/// Entities whose changes are written to the `audit_log` table
+///
+/// Auditing is opt-in, implement it on the `ActiveModel` and call the hooks from its
+/// `ActiveModelBehavior`:
+/// ```rust,ignore
+/// impl Auditable for ActiveModel {
+///     fn redacted_columns() -> &'static [&'static str] {
+///         &["password"]
+///     }
+/// }
+///
+/// #[async_trait::async_trait]
+/// impl ActiveModelBehavior for ActiveModel {
+///     async fn before_save<C: ConnectionTrait>(self, db: &C, insert: bool) -> Result<Self, DbErr> {
+///         audit::before_save(&self, db, insert).await?;
+///         Ok(self)
+///     }
+///
+///     async fn after_save<C: ConnectionTrait>(model: Model, db: &C, insert: bool) -> Result<Model, DbErr> {
+///         audit::after_save::<Self, _>(&model, db, insert).await?;
+///         Ok(model)
+///     }
+///
+///     async fn after_delete<C: ConnectionTrait>(self, db: &C) -> Result<Self, DbErr> {
+///         audit::after_delete(&self, db).await?;
+///         Ok(self)
+///     }
+/// }
+/// ```
+pub trait Auditable: ActiveModelTrait + Send + Sync {
+    /// Name of the entity in the audit log, its table by default
+    fn audit_entity() -> String {
+        Self::Entity::default().table_name().to_string()
+    }
+
+    /// Columns whose values are recorded as `***`, e.g. password hashes
+    fn redacted_columns() -> &'static [&'static str] {
+        &[]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        }
+    }
+}
+
+/// Run `future` with `actor` recorded as the author of the changes it audits
+pub async fn with_actor<F>(actor: Option<String>, future: F) -> F::Output
+where
+    F: Future,
+{
+    ACTOR.scope(actor, future).await
+}
+
+/// Author of the changes audited by the current task, if any
+pub fn current_actor() -> Option<String> {
+    ACTOR.try_with(Clone::clone).ok().flatten()
+}
+
+/// Records the id of the authenticated `R` as the actor of the request's changes
+///
+/// Layer it inside the auth middleware, which inserts the user it reads:
+/// ```rust,ignore
+/// routes! {
+///     "/" => [UserController] with [auth, axum::middleware::from_fn(audit_actor::<BasicUser>)]
+/// }
+/// ```
+pub async fn audit_actor<R>(request: Request, next: Next) -> Response
+where
+    R: AuthenticatableUser + Send + Sync + Clone + 'static,
+    R::Id: Display,
+{
+    let actor = request.extensions().get::<R>().map(|user| user.get_id().to_string());
+
+    with_actor(actor, next.run(request)).await
+}
+
+/// Hook for `ActiveModelBehavior::before_save`, records the columns an update sets
+///
+/// Inserts are recorded by [`after_save`] once their id is known.
+pub async fn before_save<A, C>(model: &A, db: &C, insert: bool) -> Result<(), DbErr>
+where
+    A: Auditable,
+    C: ConnectionTrait,
+{
+    if insert {
+        return Ok(());
+    }
+
+    let mut changes = serde_json::Map::new();
+    for column in <A::Entity as EntityTrait>::Column::iter() {
+        if let ActiveValue::Set(value) = model.get(column) {
+            changes.insert(column_name(&column), recorded_value::<A>(&column, &value));
+        }
+    }
+
+    let id = primary_key::<A::Entity, _>(|column| model.get(column).into_value());
+
+    record::<A, C>(db, AuditAction::Update, id, changes).await
+}
+
+/// Hook for `ActiveModelBehavior::after_save`, records every column of an inserted row
+pub async fn after_save<A, C>(model: &<A::Entity as EntityTrait>::Model, db: &C, insert: bool) -> Result<(), DbErr>
+where
+    A: Auditable,
+    C: ConnectionTrait,
+{
+    if !insert {
+        return Ok(());
+    }
+
+    let changes = <A::Entity as EntityTrait>::Column::iter()
+        .map(|column| (column_name(&column), recorded_value::<A>(&column, &model.get(column))))
+        .collect();
+
+    let id = primary_key::<A::Entity, _>(|column| Some(model.get(column)));
+
+    record::<A, C>(db, AuditAction::Create, id, changes).await
+}
+
+/// Hook for `ActiveModelBehavior::after_delete`
+pub async fn after_delete<A, C>(model: &A, db: &C) -> Result<(), DbErr>
+where
+    A: Auditable,
+    C: ConnectionTrait,
+{
+    let id = primary_key::<A::Entity, _>(|column| model.get(column).into_value());
+
+    record::<A, C>(db, AuditAction::Delete, id, serde_json::Map::new()).await
+}
+
+async fn record<A, C>(
+    db: &C,
+    action: AuditAction,
+    id: String,
+    changes: serde_json::Map<String, serde_json::Value>,
+) -> Result<(), DbErr>
+where
+    A: Auditable,
+    C: ConnectionTrait,
+{
+    let insert = Query::insert()
+        .into_table(Alias::new(AUDIT_TABLE))
+        .columns(["entity", "entity_id", "action", "actor", "changes"].map(Alias::new))
+        .values_panic([
+            SimpleExpr::from(A::audit_entity()),
+            id.into(),
+            action.as_str().into(),
+            current_actor().into(),
+            serde_json::Value::Object(changes).into(),
+        ])
+        .to_owned();
+
+    db.execute(&insert).await?;
+
+    Ok(())
+}
+
+/// Primary key of the row, the values of a composite key joined with `,`
+fn primary_key<E, F>(value: F) -> String
+where
+    E: EntityTrait,
+    F: Fn(E::Column) -> Option<Value>,
+{
+    E::PrimaryKey::iter()
+        .map(|key| match value(key.into_column()) {
+            Some(value) => match json_value(&value) {
+                serde_json::Value::String(value) => value,
+                value => value.to_string(),
+            },
+            None => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn recorded_value<A>(column: &<A::Entity as EntityTrait>::Column, value: &Value) -> serde_json::Value
+where
+    A: Auditable,
+{
+    if A::redacted_columns().contains(&column_name(column).as_str()) {
+        return serde_json::Value::String("***".to_string());
+    }
+
+    json_value(value)
+}
+
+fn json_value(value: &Value) -> serde_json::Value {
+    match (value, sea_value_to_json_value(value)) {
+        (Value::String(_), json) => json,
+        // dates and times come back as quoted SQL literals
+        (_, serde_json::Value::String(literal)) => match literal.strip_prefix('\'').and_then(|l| l.strip_suffix('\'')) {
+            Some(unquoted) => serde_json::Value::String(unquoted.to_string()),
+            None => serde_json::Value::String(literal),
+        },
+        (_, json) => json,
+    }
+}
+
+fn column_name<C>(column: &C) -> String
+where
+    C: sea_orm::IdenStatic,
+{
+    column.as_str().to_string()
+}
@@ -0,0 +1,126 @@
+use std::{
+    any::Any,
+    backtrace::{Backtrace, BacktraceStatus},
+    cell::{Cell, RefCell},
+    panic::{AssertUnwindSafe, PanicHookInfo},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    Json,
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures_util::FutureExt;
+
+use crate::response::BaseErrorResponse;
+
+thread_local! {
+    /// Details the hook records for [`catch_panic`], which only sees the payload
+    static LAST_PANIC: RefCell<Option<PanicDetails>> = const { RefCell::new(None) };
+    /// Depth of [`catch_panic`] futures being polled, their panics are logged by them
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+}
+
+struct PanicDetails {
+    location: String,
+    backtrace: Option<String>,
+}
+
+/// Install the panic hook recording the location and backtrace [`catch_panic`] logs
+///
+/// The backtrace is only captured when `RUST_BACKTRACE` (or `RUST_LIB_BACKTRACE`) is set.
+/// Panics outside of [`catch_panic`] are still reported by the previous hook.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info: &PanicHookInfo<'_>| {
+        if CATCHING.get() == 0 {
+            return previous(info);
+        }
+
+        let backtrace = Backtrace::capture();
+        let details = PanicDetails {
+            location: info.location().map_or_else(|| "unknown".to_string(), ToString::to_string),
+            backtrace: (backtrace.status() == BacktraceStatus::Captured).then(|| backtrace.to_string()),
+        };
+
+        LAST_PANIC.set(Some(details));
+    }));
+}
+
+/// Turns a panic of the inner service into a JSON `500 Internal Server Error`
+///
+/// The panic is logged as one `error` event with the `panic.message` and `panic.location`
+/// fields, plus `panic.backtrace` when one was captured, so it stays a single entry in
+/// JSON logs. The location and backtrace need [`install_panic_hook`].
+///
+/// Usage:
+/// ```rust,ignore
+/// router.layer(axum::middleware::from_fn(catch_panic))
+/// ```
+pub async fn catch_panic(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+
+    let panic = match Catching(Box::pin(AssertUnwindSafe(next.run(request)).catch_unwind())).await {
+        Ok(response) => return response,
+        Err(panic) => panic,
+    };
+
+    let details = LAST_PANIC.take();
+
+    tracing::error!(
+        panic.message = panic_message(&*panic),
+        panic.location = details.as_ref().map_or("unknown", |details| details.location.as_str()),
+        panic.backtrace = details.as_ref().and_then(|details| details.backtrace.as_deref()),
+        "{} {} panicked",
+        method,
+        path
+    );
+
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(BaseErrorResponse::<()>::new("internal server error", None)),
+    )
+        .into_response()
+}
+
+/// Polls `F` with [`CATCHING`] raised, so the hook records its panics instead of printing them
+struct Catching<F>(Pin<Box<F>>);
+
+impl<F> Future for Catching<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        CATCHING.set(CATCHING.get() + 1);
+        let _lowered = Lowered;
+
+        self.0.as_mut().poll(cx)
+    }
+}
+
+/// Lowers [`CATCHING`] once the poll returns, or unwinds
+struct Lowered;
+
+impl Drop for Lowered {
+    fn drop(&mut self) {
+        CATCHING.set(CATCHING.get() - 1);
+    }
+}
+
+pub(crate) fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic payload"
+    }
+}
@@ -0,0 +1,475 @@
+use std::{
+    io::Write,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Json, Router,
+    body::{Bytes, HttpBody},
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use flate2::{Compression, write::GzEncoder};
+
+use crate::config::Profile;
+
+/// Optional feature module that can add routes or layers to the app router
+///
+/// Usage:
+/// ```rust,ignore
+/// struct AdminPlugin;
+///
+/// impl Plugin for AdminPlugin {
+///     fn name(&self) -> &'static str {
+///         "admin"
+///     }
+///
+///     fn mount(&self, router: Router) -> Router {
+///         router.nest("/admin", AdminController::router())
+///     }
+/// }
+/// ```
+pub trait Plugin: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Disabled plugins are skipped by [`PluginRegistry::mount`]
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    fn mount(&self, router: Router) -> Router;
+}
+
+/// Ordered list of plugins folded over the base router
+///
+/// Plugins are mounted in registration order. Routes added by a plugin are not
+/// wrapped by layers that were applied to the base router before mounting.
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Box<dyn Plugin>>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(mut self, plugin: impl Plugin + 'static) -> Self {
+        self.plugins.push(Box::new(plugin));
+        self
+    }
+
+    /// Register `plugin` only when `condition` holds, e.g. depending on the profile
+    pub fn register_if(self, condition: bool, plugin: impl Plugin + 'static) -> Self {
+        if condition {
+            self.register(plugin)
+        } else {
+            self
+        }
+    }
+
+    pub fn mount(&self, router: Router) -> Router {
+        self.plugins
+            .iter()
+            .filter(|plugin| plugin.enabled())
+            .fold(router, |router, plugin| {
+                tracing::debug!("mounting plugin `{}`", plugin.name());
+
+                plugin.mount(router)
+            })
+    }
+}
+
+/// `GET /health` answering `{"status": "ok"}`, plus `"profile"` when one is set
+#[derive(Default)]
+pub struct HealthPlugin {
+    profile: Option<Profile>,
+}
+
+impl HealthPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+}
+
+impl Plugin for HealthPlugin {
+    fn name(&self) -> &'static str {
+        "health"
+    }
+
+    fn mount(&self, router: Router) -> Router {
+        let body = match self.profile {
+            Some(profile) => serde_json::json!({ "status": "ok", "profile": profile }),
+            None => serde_json::json!({ "status": "ok" }),
+        };
+
+        router.route("/health", get(move || async move { Json(body) }))
+    }
+}
+
+/// Serialization of the OpenAPI document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl DocFormat {
+    /// First supported media type of the `Accept` header, JSON when there is none
+    pub fn from_accept(headers: &HeaderMap) -> Self {
+        headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .find_map(|media| match media.split(';').next().unwrap_or("").trim() {
+                "application/json" => Some(DocFormat::Json),
+                "application/yaml" | "text/yaml" => Some(DocFormat::Yaml),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            DocFormat::Json => "application/json",
+            DocFormat::Yaml => "application/yaml",
+        }
+    }
+
+    pub fn render(&self, spec: &utoipa::openapi::OpenApi) -> anyhow::Result<String> {
+        Ok(match self {
+            DocFormat::Json => spec.to_pretty_json()?,
+            DocFormat::Yaml => spec.to_yaml()?,
+        })
+    }
+}
+
+/// Serves an OpenAPI document at `/openapi.json` and `/openapi` by default
+///
+/// The format follows the `Accept` header: `application/yaml` or `text/yaml` get YAML,
+/// anything else gets JSON. Both are rendered and gzipped once when mounted, clients
+/// sending `Accept-Encoding: gzip` get the compressed body.
+pub struct DocsPlugin {
+    spec: utoipa::openapi::OpenApi,
+    paths: Vec<String>,
+}
+
+impl DocsPlugin {
+    pub fn new(spec: utoipa::openapi::OpenApi) -> Self {
+        Self {
+            spec,
+            paths: vec!["/openapi.json".to_string(), "/openapi".to_string()],
+        }
+    }
+
+    /// Serve the document at `path` only, instead of the default paths
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.paths = vec![path.into()];
+        self
+    }
+}
+
+impl Plugin for DocsPlugin {
+    fn name(&self) -> &'static str {
+        "docs"
+    }
+
+    fn mount(&self, router: Router) -> Router {
+        let json = RenderedDoc::new(&self.spec, DocFormat::Json);
+        let yaml = RenderedDoc::new(&self.spec, DocFormat::Yaml);
+        let rendered = Arc::new((json, yaml));
+
+        let handler = get(move |headers: HeaderMap| async move {
+            let doc = match DocFormat::from_accept(&headers) {
+                DocFormat::Json => &rendered.0,
+                DocFormat::Yaml => &rendered.1,
+            };
+
+            doc.respond(accepts_gzip(&headers))
+        });
+
+        self.paths
+            .iter()
+            .fold(router, |router, path| router.route(path, handler.clone()))
+    }
+}
+
+/// Document body in one format, with its gzipped copy
+struct RenderedDoc {
+    format: DocFormat,
+    body: Option<Bytes>,
+    gzip: Option<Bytes>,
+}
+
+impl RenderedDoc {
+    fn new(spec: &utoipa::openapi::OpenApi, format: DocFormat) -> Self {
+        let body = format
+            .render(spec)
+            .inspect_err(|err| tracing::error!("cannot render the OpenAPI document: {:?}", err))
+            .ok()
+            .map(Bytes::from);
+
+        let gzip = body.as_ref().and_then(|body| {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+
+            encoder
+                .write_all(body)
+                .and_then(|_| encoder.finish())
+                .inspect_err(|err| tracing::error!("cannot gzip the OpenAPI document: {:?}", err))
+                .ok()
+                .map(Bytes::from)
+        });
+
+        Self { format, body, gzip }
+    }
+
+    fn respond(&self, gzip: bool) -> Response {
+        let content_type = (header::CONTENT_TYPE, self.format.content_type());
+        let vary = (header::VARY, "accept, accept-encoding");
+
+        match (&self.body, &self.gzip) {
+            (Some(_), Some(compressed)) if gzip => (
+                [content_type, vary, (header::CONTENT_ENCODING, "gzip")],
+                compressed.clone(),
+            )
+                .into_response(),
+            (Some(body), _) => ([content_type, vary], body.clone()).into_response(),
+            (None, _) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    }
+}
+
+/// Whether `Accept-Encoding` allows gzip, ignoring `q=0` entries
+fn accepts_gzip(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|coding| {
+            let mut params = coding.split(';').map(str::trim);
+            let name = params.next().unwrap_or("");
+            let refused = params.any(|param| {
+                param
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q == 0.0)
+            });
+
+            (name.eq_ignore_ascii_case("gzip") || name == "*") && !refused
+        })
+}
+
+/// `GET /_routes` listing every controller route as JSON
+///
+/// Only enabled in debug builds, use [`RoutesPlugin::always`] to expose it in release.
+#[derive(Default)]
+pub struct RoutesPlugin {
+    always: bool,
+}
+
+impl RoutesPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn always(mut self) -> Self {
+        self.always = true;
+        self
+    }
+}
+
+impl Plugin for RoutesPlugin {
+    fn name(&self) -> &'static str {
+        "routes"
+    }
+
+    fn enabled(&self) -> bool {
+        self.always || cfg!(debug_assertions)
+    }
+
+    fn mount(&self, router: Router) -> Router {
+        router.route(
+            "/_routes",
+            get(|| async { Json(crate::routing::registered_routes()) }),
+        )
+    }
+}
+
+/// Counts requests and responses and exposes them at `GET /metrics`
+///
+/// The counters are rendered in the Prometheus text format. Mount it last so
+/// every other route is counted. Requests slower than [`MetricsPlugin::slow_request`]
+/// are logged as warnings with their route and elapsed time.
+///
+/// To expose the counters on another port, e.g. one that isn't public:
+/// ```rust,ignore
+/// let metrics = MetricsPlugin::new().detached();
+/// let admin = metrics.endpoint();
+///
+/// let app = PluginRegistry::new().register(metrics).mount(app);
+/// ```
+#[derive(Default, Clone)]
+pub struct MetricsPlugin {
+    metrics: Arc<Metrics>,
+    detached: bool,
+}
+
+#[derive(Default)]
+struct Metrics {
+    requests: AtomicU64,
+    in_flight: AtomicU64,
+    // 1xx to 5xx
+    responses: [AtomicU64; 5],
+    // only bodies of a known length are counted
+    request_body_bytes: AtomicU64,
+    response_body_bytes: AtomicU64,
+    slow_requests: AtomicU64,
+    slow_request: Option<Duration>,
+}
+
+impl MetricsPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Warn about requests taking longer than `threshold`
+    pub fn slow_request(self, threshold: Duration) -> Self {
+        Self {
+            metrics: Arc::new(Metrics {
+                slow_request: Some(threshold),
+                ..Default::default()
+            }),
+            ..self
+        }
+    }
+
+    /// Only count the requests, `GET /metrics` is served by [`MetricsPlugin::endpoint`]
+    pub fn detached(mut self) -> Self {
+        self.detached = true;
+        self
+    }
+
+    /// Router serving `GET /metrics` alone, with the counters of this plugin
+    pub fn endpoint(&self) -> Router {
+        Router::new().route("/metrics", get(render_metrics).with_state(self.metrics.clone()))
+    }
+}
+
+impl Plugin for MetricsPlugin {
+    fn name(&self) -> &'static str {
+        "metrics"
+    }
+
+    fn mount(&self, router: Router) -> Router {
+        let router = if self.detached {
+            router
+        } else {
+            router.merge(self.endpoint())
+        };
+
+        router.layer(axum::middleware::from_fn_with_state(
+            self.metrics.clone(),
+            track_metrics,
+        ))
+    }
+}
+
+async fn track_metrics(State(metrics): State<Arc<Metrics>>, request: Request, next: Next) -> Response {
+    metrics.requests.fetch_add(1, Ordering::Relaxed);
+    metrics.in_flight.fetch_add(1, Ordering::Relaxed);
+
+    if let Some(bytes) = request.body().size_hint().exact() {
+        metrics.request_body_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    let elapsed = started.elapsed();
+
+    metrics.in_flight.fetch_sub(1, Ordering::Relaxed);
+
+    let class = (response.status().as_u16() / 100).clamp(1, 5) as usize;
+    metrics.responses[class - 1].fetch_add(1, Ordering::Relaxed);
+
+    if let Some(bytes) = response.body().size_hint().exact() {
+        metrics.response_body_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    if metrics.slow_request.is_some_and(|threshold| elapsed > threshold) {
+        metrics.slow_requests.fetch_add(1, Ordering::Relaxed);
+
+        tracing::warn!("slow request {} {} took {} ms", method, route, elapsed.as_millis());
+    }
+
+    response
+}
+
+async fn render_metrics(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+    let mut body = String::new();
+
+    body.push_str("# TYPE http_requests_total counter\n");
+    body.push_str(&format!(
+        "http_requests_total {}\n",
+        metrics.requests.load(Ordering::Relaxed)
+    ));
+
+    body.push_str("# TYPE http_requests_in_flight gauge\n");
+    body.push_str(&format!(
+        "http_requests_in_flight {}\n",
+        metrics.in_flight.load(Ordering::Relaxed)
+    ));
+
+    body.push_str("# TYPE http_request_body_bytes_total counter\n");
+    body.push_str(&format!(
+        "http_request_body_bytes_total {}\n",
+        metrics.request_body_bytes.load(Ordering::Relaxed)
+    ));
+
+    body.push_str("# TYPE http_response_body_bytes_total counter\n");
+    body.push_str(&format!(
+        "http_response_body_bytes_total {}\n",
+        metrics.response_body_bytes.load(Ordering::Relaxed)
+    ));
+
+    body.push_str("# TYPE http_slow_requests_total counter\n");
+    body.push_str(&format!(
+        "http_slow_requests_total {}\n",
+        metrics.slow_requests.load(Ordering::Relaxed)
+    ));
+
+    body.push_str("# TYPE http_responses_total counter\n");
+    for (index, count) in metrics.responses.iter().enumerate() {
+        body.push_str(&format!(
+            "http_responses_total{{class=\"{}xx\"}} {}\n",
+            index + 1,
+            count.load(Ordering::Relaxed)
+        ));
+    }
+
+    (
+        [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        body,
+    )
+}
@@ -0,0 +1,94 @@
+use std::marker::PhantomData;
+
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel,
+    PrimaryKeyTrait,
+};
+
+use crate::error::AppError;
+
+/// Thin data access layer over a SeaORM entity
+///
+/// Usage:
+/// ```rust,ignore
+/// use argon_core::repository::Repository;
+/// use argon_core::response::Deleted;
+///
+/// let users = Repository::<user::Entity>::new(db);
+///
+/// // 204 when the row existed, 404 otherwise
+/// Deleted::or_not_found(users.delete(id).await?)
+/// ```
+#[derive(Debug)]
+pub struct Repository<E> {
+    db: DatabaseConnection,
+    entity: PhantomData<E>,
+}
+
+impl<E> Repository<E>
+where
+    E: EntityTrait,
+{
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            entity: PhantomData,
+        }
+    }
+
+    pub fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    pub async fn find_by_id<T>(&self, id: T) -> Result<Option<E::Model>, DbErr>
+    where
+        T: Into<<E::PrimaryKey as PrimaryKeyTrait>::ValueType>,
+    {
+        E::find_by_id(id).one(&self.db).await
+    }
+
+    /// Insert a row, a unique constraint violation is an [`AppError::Conflict`]
+    pub async fn create<A>(&self, model: A) -> Result<E::Model, AppError>
+    where
+        A: ActiveModelTrait<Entity = E> + ActiveModelBehavior + Send,
+        E::Model: IntoActiveModel<A>,
+    {
+        model
+            .insert(&self.db)
+            .await
+            .map_err(|err| AppError::from_db(err, E::default().table_name()))
+    }
+
+    /// Update a row, a unique constraint violation is an [`AppError::Conflict`]
+    pub async fn update<A>(&self, model: A) -> Result<E::Model, AppError>
+    where
+        A: ActiveModelTrait<Entity = E> + ActiveModelBehavior + Send,
+        E::Model: IntoActiveModel<A>,
+    {
+        model
+            .update(&self.db)
+            .await
+            .map_err(|err| AppError::from_db(err, E::default().table_name()))
+    }
+
+    /// Delete a row by primary key
+    ///
+    /// Returns `false` when no row matched, so handlers can answer with `404`.
+    pub async fn delete<T>(&self, id: T) -> Result<bool, DbErr>
+    where
+        T: Into<<E::PrimaryKey as PrimaryKeyTrait>::ValueType>,
+    {
+        let result = E::delete_by_id(id).exec(&self.db).await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
+
+impl<E> Clone for Repository<E> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            entity: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+/// Breakers of [`with_circuit_breaker`] keyed by name
+static BREAKERS: LazyLock<Mutex<HashMap<String, Arc<CircuitBreaker>>>> = LazyLock::new(Mutex::default);
+
+/// How often and how patiently a failed call is tried again
+///
+/// The delay doubles after every failure, starting at `base_delay` and capped at `max_delay`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32, base_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            base_delay,
+            ..Self::default()
+        }
+    }
+
+    /// A single attempt
+    pub fn none() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Wait before the retry following the `retry`-th failure, counting from 0
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay)
+    }
+
+    /// Run `op` until it succeeds or the retries are spent, returning the last error
+    pub async fn retry<F, Fut, T, E>(&self, mut op: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut retry = 0;
+
+        loop {
+            match op().await {
+                Ok(value) => return Ok(value),
+                Err(err) if retry >= self.max_retries => return Err(err),
+                Err(_) => {
+                    tokio::time::sleep(self.delay(retry)).await;
+                    retry += 1;
+                }
+            }
+        }
+    }
+}
+
+/// State of a [`CircuitBreaker`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through, counting the consecutive failures
+    Closed { failures: u32 },
+    /// Calls are refused until the cooldown is over
+    Open { until: Instant },
+    /// The cooldown is over and a single trial call is in flight
+    HalfOpen,
+}
+
+/// Error of a call made through a [`CircuitBreaker`]
+#[derive(Debug)]
+pub enum CircuitError<E> {
+    /// The breaker is open, the call wasn't made
+    Open,
+    Failed(E),
+}
+
+impl<E> std::fmt::Display for CircuitError<E>
+where
+    E: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitError::Open => write!(f, "the circuit breaker is open"),
+            CircuitError::Failed(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl<E> std::error::Error for CircuitError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CircuitError::Open => None,
+            CircuitError::Failed(err) => Some(err),
+        }
+    }
+}
+
+/// Stops calling a failing dependency for a while instead of piling up timeouts
+///
+/// After `failure_threshold` consecutive failures the breaker opens and refuses calls
+/// for `cooldown`. The next call is then let through as a trial, closing the breaker
+/// on success and opening it again on failure. Every call is retried per its
+/// [`RetryPolicy`] while the breaker stays closed.
+///
+/// Usage:
+/// ```rust,ignore
+/// let breaker = CircuitBreaker::new()
+///     .failure_threshold(5)
+///     .cooldown(Duration::from_secs(30))
+///     .retry(RetryPolicy::new(2, Duration::from_millis(200)));
+///
+/// let rates = breaker.call(|| client.fetch_rates()).await?;
+/// ```
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    retry: RetryPolicy,
+    state: Mutex<CircuitState>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+            retry: RetryPolicy::default(),
+            state: Mutex::new(CircuitState::Closed { failures: 0 }),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn state(&self) -> CircuitState {
+        *self.lock()
+    }
+
+    /// Run `op` through the breaker, retrying failures while it stays closed
+    pub async fn call<F, Fut, T, E>(&self, mut op: F) -> Result<T, CircuitError<E>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut retry = 0;
+
+        loop {
+            self.acquire()?;
+
+            match op().await {
+                Ok(value) => {
+                    self.record_success();
+
+                    return Ok(value);
+                }
+                Err(err) => {
+                    let open = self.record_failure();
+
+                    if open || retry >= self.retry.max_retries {
+                        return Err(CircuitError::Failed(err));
+                    }
+                }
+            }
+
+            tokio::time::sleep(self.retry.delay(retry)).await;
+            retry += 1;
+        }
+    }
+
+    // whether a call may be made now, moving an expired open breaker to half-open
+    fn acquire<E>(&self) -> Result<(), CircuitError<E>> {
+        let mut state = self.lock();
+
+        match *state {
+            CircuitState::Closed { .. } => Ok(()),
+            CircuitState::Open { until } if Instant::now() >= until => {
+                *state = CircuitState::HalfOpen;
+
+                Ok(())
+            }
+            CircuitState::Open { .. } | CircuitState::HalfOpen => Err(CircuitError::Open),
+        }
+    }
+
+    fn record_success(&self) {
+        *self.lock() = CircuitState::Closed { failures: 0 };
+    }
+
+    /// Count a failure, `true` when it opened the breaker
+    fn record_failure(&self) -> bool {
+        let mut state = self.lock();
+
+        let failures = match *state {
+            CircuitState::Closed { failures } => failures + 1,
+            // the trial call failed
+            CircuitState::HalfOpen | CircuitState::Open { .. } => self.failure_threshold,
+        };
+
+        if failures >= self.failure_threshold {
+            tracing::warn!("opening the circuit breaker for {:?} after {} failures", self.cooldown, failures);
+
+            *state = CircuitState::Open {
+                until: Instant::now() + self.cooldown,
+            };
+
+            return true;
+        }
+
+        *state = CircuitState::Closed { failures };
+
+        false
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CircuitState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Configure the breaker used by [`with_circuit_breaker`] for `name`, replacing its state
+///
+/// Usage:
+/// ```rust,ignore
+/// register_circuit_breaker("payments", CircuitBreaker::new().failure_threshold(3));
+/// ```
+pub fn register_circuit_breaker(name: impl Into<String>, breaker: CircuitBreaker) -> Arc<CircuitBreaker> {
+    let breaker = Arc::new(breaker);
+
+    breakers().insert(name.into(), breaker.clone());
+
+    breaker
+}
+
+/// The breaker registered for `name`, a default one is created on first use
+pub fn circuit_breaker(name: &str) -> Arc<CircuitBreaker> {
+    breakers()
+        .entry(name.to_string())
+        .or_insert_with(|| Arc::new(CircuitBreaker::default()))
+        .clone()
+}
+
+/// Run `op` through the breaker named `name`, shared by every caller using that name
+///
+/// Usage:
+/// ```rust,ignore
+/// let charge = with_circuit_breaker("payments", || payments.charge(&order)).await?;
+/// ```
+pub async fn with_circuit_breaker<F, Fut, T, E>(name: &str, op: F) -> Result<T, CircuitError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let breaker = circuit_breaker(name);
+
+    breaker.call(op).await
+}
+
+fn breakers() -> std::sync::MutexGuard<'static, HashMap<String, Arc<CircuitBreaker>>> {
+    BREAKERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
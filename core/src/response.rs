@@ -1,3 +1,9 @@
+mod envelope;
+mod file;
+
+pub use envelope::{ENVELOPE_MAX_BYTES, RawResponse, envelope};
+pub use file::FileDownload;
+
 #[derive(serde::Serialize, utoipa::ToSchema, Debug, Clone)]
 pub struct BaseErrorResponse<T>
     where T: serde::Serialize + utoipa::ToSchema
@@ -16,4 +22,431 @@ impl<T> BaseErrorResponse<T>
             detail: detail.into()
         }
     }
+
+    /// Build the response with the message registered for `code` in the client's language
+    ///
+    /// See [`crate::i18n`] for registering messages.
+    pub fn localized(code: &str, languages: &crate::i18n::AcceptLanguage, detail: impl Into<Option<T>>) -> Self {
+        Self::new(crate::i18n::translate(code, languages), detail)
+    }
+}
+
+/// Error body without detail, serialized like `BaseErrorResponse::<()>`
+///
+/// Documents the responses of `#[utoipa_errors(...)]`.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ErrorResponse {
+    message: String,
+    detail: Option<()>,
+}
+
+impl ErrorResponse {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            detail: None,
+        }
+    }
+}
+
+impl utoipa::PartialSchema for ErrorResponse {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .property(
+                "message",
+                utoipa::openapi::ObjectBuilder::new().schema_type(utoipa::openapi::schema::Type::String),
+            )
+            .required("message")
+            .property(
+                "detail",
+                utoipa::openapi::ObjectBuilder::new().schema_type(utoipa::openapi::schema::Type::Null),
+            )
+            .into()
+    }
+}
+
+impl utoipa::ToSchema for ErrorResponse {}
+
+/// Field name to its error messages, the `detail` of `422` responses
+///
+/// Sorted by field name, so the body is stable. Answer it as a [`ValidationErrorResponse`].
+#[derive(serde::Serialize, utoipa::ToSchema, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldErrors(pub std::collections::BTreeMap<String, Vec<String>>);
+
+impl FieldErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an error message to `field`
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.0.entry(field.into()).or_default().push(message.into());
+    }
+
+    /// Chained form of [`FieldErrors::add`]
+    pub fn field(mut self, field: impl Into<String>, message: impl Into<String>) -> Self {
+        self.add(field, message);
+        self
+    }
+
+    pub fn get(&self, field: &str) -> Option<&[String]> {
+        self.0.get(field).map(Vec::as_slice)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// `422 Unprocessable Entity` body listing the messages of every invalid field
+///
+/// Serialized like `BaseErrorResponse<FieldErrors>`, e.g.
+/// `{"message": "validation failed", "detail": {"title": ["must not be blank"]}}`.
+///
+/// Returned by [`crate::extract::Valid`], and documented automatically by `#[controller]`
+/// on handlers taking a `Valid<T>`.
+#[derive(serde::Serialize, utoipa::ToSchema, Debug, Clone, Default)]
+pub struct ValidationErrorResponse {
+    message: String,
+    detail: FieldErrors,
+}
+
+impl ValidationErrorResponse {
+    pub fn new() -> Self {
+        Self::from(FieldErrors::new())
+    }
+
+    /// Add an error message to `field`
+    pub fn field(mut self, field: impl Into<String>, message: impl Into<String>) -> Self {
+        self.detail.add(field, message);
+        self
+    }
+
+    /// The messages of every invalid field
+    pub fn fields(&self) -> &FieldErrors {
+        &self.detail
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.detail.is_empty()
+    }
+
+    /// `Ok` when no field failed, so `validate` can end with `errors.into_result()`
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl From<FieldErrors> for ValidationErrorResponse {
+    fn from(fields: FieldErrors) -> Self {
+        Self {
+            message: "validation failed".to_string(),
+            detail: fields,
+        }
+    }
+}
+
+impl axum::response::IntoResponse for ValidationErrorResponse {
+    fn into_response(self) -> axum::response::Response {
+        (axum::http::StatusCode::UNPROCESSABLE_ENTITY, axum::Json(self)).into_response()
+    }
+}
+
+/// Raw bytes answered with their content type, e.g. an image or a PDF
+///
+/// Documented as a binary string:
+/// ```rust,ignore
+/// #[get("/users/{id}/avatar")]
+/// #[utoipa_response(status = 200, body = Binary, content_type = "image/png")]
+/// async fn avatar() -> Binary {
+///     Binary::new("image/png", load_avatar().await)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Binary {
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+impl Binary {
+    pub fn new(content_type: impl Into<String>, bytes: impl Into<Vec<u8>>) -> Self {
+        Self {
+            content_type: content_type.into(),
+            bytes: bytes.into(),
+        }
+    }
+}
+
+impl axum::response::IntoResponse for Binary {
+    fn into_response(self) -> axum::response::Response {
+        match axum::http::HeaderValue::from_str(&self.content_type) {
+            Ok(content_type) => ([(axum::http::header::CONTENT_TYPE, content_type)], self.bytes).into_response(),
+            Err(_) => {
+                tracing::error!("invalid content type `{}` for a binary response", self.content_type);
+
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+impl utoipa::PartialSchema for Binary {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .format(Some(utoipa::openapi::SchemaFormat::KnownFormat(
+                utoipa::openapi::KnownFormat::Binary,
+            )))
+            .into()
+    }
+}
+
+impl utoipa::ToSchema for Binary {}
+
+/// `204 No Content` response for a successful delete
+///
+/// Document it on a handler with `#[utoipa_response(response = argon_core::response::Deleted)]`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Deleted;
+
+impl Deleted {
+    /// `Deleted` when a row was removed, `404 Not Found` when nothing matched
+    pub fn or_not_found(deleted: bool) -> Result<Self, axum::http::StatusCode> {
+        if deleted {
+            Ok(Self)
+        } else {
+            Err(axum::http::StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+impl axum::response::IntoResponse for Deleted {
+    fn into_response(self) -> axum::response::Response {
+        axum::http::StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+impl utoipa::IntoResponses for Deleted {
+    fn responses() -> std::collections::BTreeMap<String, utoipa::openapi::RefOr<utoipa::openapi::response::Response>> {
+        utoipa::openapi::response::ResponsesBuilder::new()
+            .response("204", utoipa::openapi::response::Response::new("Record deleted"))
+            .build()
+            .responses
+    }
+}
+
+/// Redirect to another url through the `Location` header
+///
+/// Document it on a handler with `#[utoipa_response(response = argon_core::response::Redirect)]`:
+/// ```rust,ignore
+/// #[post("/posts")]
+/// async fn create_post(...) -> Result<Redirect, AppError> {
+///     let post = repository.create(model).await?;
+///
+///     Ok(Redirect::see_other(format!("/posts/{}", post.id)))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Redirect {
+    pub status: axum::http::StatusCode,
+    pub location: String,
+}
+
+impl Redirect {
+    /// `307 Temporary Redirect`, the request is repeated with the same method and body
+    pub fn temporary(url: impl Into<String>) -> Self {
+        Self::new(axum::http::StatusCode::TEMPORARY_REDIRECT, url)
+    }
+
+    /// `308 Permanent Redirect`, the request is repeated with the same method and body
+    pub fn permanent(url: impl Into<String>) -> Self {
+        Self::new(axum::http::StatusCode::PERMANENT_REDIRECT, url)
+    }
+
+    /// `303 See Other`, the client follows with a `GET`, e.g. after a form post
+    pub fn see_other(url: impl Into<String>) -> Self {
+        Self::new(axum::http::StatusCode::SEE_OTHER, url)
+    }
+
+    fn new(status: axum::http::StatusCode, url: impl Into<String>) -> Self {
+        Self {
+            status,
+            location: url.into(),
+        }
+    }
+}
+
+impl axum::response::IntoResponse for Redirect {
+    fn into_response(self) -> axum::response::Response {
+        match axum::http::HeaderValue::from_str(&self.location) {
+            Ok(location) => (self.status, [(axum::http::header::LOCATION, location)]).into_response(),
+            Err(_) => {
+                tracing::error!("invalid redirect location `{}`", self.location);
+
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+impl utoipa::IntoResponses for Redirect {
+    fn responses() -> std::collections::BTreeMap<String, utoipa::openapi::RefOr<utoipa::openapi::response::Response>> {
+        let location = utoipa::openapi::header::HeaderBuilder::new()
+            .schema(utoipa::openapi::ObjectBuilder::new().schema_type(utoipa::openapi::schema::Type::String))
+            .description(Some("Url to follow"))
+            .build();
+
+        utoipa::openapi::response::ResponsesBuilder::new()
+            .response(
+                "3XX",
+                utoipa::openapi::response::ResponseBuilder::new()
+                    .description("Redirect")
+                    .header("Location", location),
+            )
+            .build()
+            .responses
+    }
+}
+
+/// JSON body answered with a chosen success status
+///
+/// Documented exactly like `T`, declare the status on the handler:
+/// ```rust,ignore
+/// #[post("/users")]
+/// #[utoipa_response(status = 201, body = UserResponse)]
+/// async fn create_user(...) -> Result<Success<UserResponse>, AppError> {
+///     let user = repository.create(model).await?;
+///
+///     Ok(Success::created(UserResponse::from_model(user)))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Success<T> {
+    pub status: axum::http::StatusCode,
+    pub body: T,
+}
+
+impl<T> Success<T> {
+    pub fn new(status: axum::http::StatusCode, body: T) -> Self {
+        Self { status, body }
+    }
+
+    /// `200 OK`
+    pub fn ok(body: T) -> Self {
+        Self::new(axum::http::StatusCode::OK, body)
+    }
+
+    /// `201 Created`
+    pub fn created(body: T) -> Self {
+        Self::new(axum::http::StatusCode::CREATED, body)
+    }
+
+    /// `202 Accepted`
+    pub fn accepted(body: T) -> Self {
+        Self::new(axum::http::StatusCode::ACCEPTED, body)
+    }
+}
+
+impl<T> axum::response::IntoResponse for Success<T>
+    where T: serde::Serialize
+{
+    fn into_response(self) -> axum::response::Response {
+        (self.status, axum::Json(self.body)).into_response()
+    }
+}
+
+impl<T> utoipa::PartialSchema for Success<T>
+    where T: utoipa::ToSchema
+{
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        T::schema()
+    }
+}
+
+impl<T> utoipa::ToSchema for Success<T>
+    where T: utoipa::ToSchema
+{
+    fn name() -> std::borrow::Cow<'static, str> {
+        T::name()
+    }
+
+    fn schemas(schemas: &mut Vec<(String, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>)>) {
+        T::schemas(schemas)
+    }
+}
+
+/// Build a response DTO from an entity `Model`
+///
+/// Implement it by hand or derive it with `#[derive(argon_macros::FromModel)]`:
+/// ```rust,ignore
+/// #[derive(serde::Serialize, utoipa::ToSchema, argon_macros::FromModel)]
+/// #[from_model(crate::app::model::user::Model)]
+/// pub struct UserResponse {
+///     pub id: i32,
+///     pub username: String,
+///     // `password` is left out so it never reaches the client
+/// }
+///
+/// let response = UserResponse::from_model(user);
+/// ```
+pub trait FromModel<M>: Sized {
+    fn from_model(model: M) -> Self;
+
+    fn from_models(models: Vec<M>) -> Vec<Self> {
+        models.into_iter().map(Self::from_model).collect()
+    }
+}
+
+/// A page of items returned as JSON
+#[derive(serde::Serialize, utoipa::ToSchema, Debug, Clone)]
+pub struct Paginated<T>
+    where T: serde::Serialize + utoipa::ToSchema
+{
+    pub items: Vec<T>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+}
+
+impl<T> Paginated<T>
+    where T: serde::Serialize + utoipa::ToSchema
+{
+    pub fn new(items: Vec<T>, total: u64, page: u64, per_page: u64) -> Self {
+        Self {
+            items,
+            total,
+            page,
+            per_page,
+        }
+    }
+
+    /// Map a page of entity models into response DTOs
+    pub fn from_models<M>(models: Vec<M>, total: u64, page: u64, per_page: u64) -> Self
+        where T: FromModel<M>
+    {
+        Self::new(T::from_models(models), total, page, per_page)
+    }
+}
+
+/// Treat a whole result set as a single page
+impl<T, M> From<Vec<M>> for Paginated<T>
+    where T: serde::Serialize + utoipa::ToSchema + FromModel<M>
+{
+    fn from(models: Vec<M>) -> Self {
+        let total = models.len() as u64;
+
+        Self::from_models(models, total, 1, total)
+    }
+}
+
+impl<T> axum::response::IntoResponse for Paginated<T>
+    where T: serde::Serialize + utoipa::ToSchema
+{
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
 }
@@ -0,0 +1,83 @@
+use axum::{
+    body::{Body, HttpBody},
+    extract::Request,
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+
+/// Largest body [`envelope`] buffers to wrap, bigger or unsized ones are passed through
+pub const ENVELOPE_MAX_BYTES: usize = 2 * 1024 * 1024;
+
+/// Response extension asking [`envelope`] and similar layers to leave the response as is
+///
+/// `#[raw_response]` handlers of a `#[controller]` get it on every response, a handler can
+/// also insert it by hand.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RawResponse;
+
+/// Middleware wrapping successful JSON responses as `{ "data": ..., "meta": { ... } }`
+///
+/// Only `2xx` responses with a JSON `Content-Type` are wrapped, so errors keep the
+/// `BaseErrorResponse` shape, and responses tagged with [`RawResponse`] are skipped.
+/// Streamed bodies, and ones over [`ENVELOPE_MAX_BYTES`], are left unwrapped too.
+/// It is opt-in, layer it on the routers that want it.
+///
+/// Usage:
+/// ```rust,ignore
+/// let router = Router::new()
+///     .merge(UserController::router())
+///     .layer(axum::middleware::from_fn(argon_core::response::envelope));
+/// ```
+pub async fn envelope(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+
+    if !response.status().is_success() || !is_json(&response) || response.extensions().get::<RawResponse>().is_some() {
+        return response;
+    }
+
+    // only bodies of a known size are buffered, a stream could be endless
+    let size = response.body().size_hint().exact();
+    if size.is_none_or(|size| size > ENVELOPE_MAX_BYTES as u64) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    let bytes = match axum::body::to_bytes(body, ENVELOPE_MAX_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            tracing::error!("cannot read the response body to wrap it: {:?}", err);
+
+            return axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let data: serde_json::Value = match serde_json::from_slice(&bytes) {
+        Ok(data) => data,
+        // not valid JSON after all, pass it through untouched
+        Err(_) => return Response::from_parts(parts, Body::from(bytes)),
+    };
+
+    let wrapped = serde_json::json!({
+        "data": data,
+        "meta": {
+            "timestamp": Utc::now().to_rfc3339(),
+        },
+    });
+
+    let body = serde_json::to_vec(&wrapped).expect("a json value always serializes");
+
+    parts.headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
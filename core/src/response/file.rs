@@ -0,0 +1,203 @@
+use std::{io::SeekFrom, path::Path};
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use futures_util::{StreamExt, TryStreamExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+/// Streams a file or reader to the client as a download
+///
+/// Files opened with [`FileDownload::from_path`] advertise `Accept-Ranges: bytes` and
+/// answer a single `Range` request with `206 Partial Content`, so downloads can resume.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[get("/exports/{name}")]
+/// async fn export(Path(name): Path<String>, headers: HeaderMap) -> Result<FileDownload, StatusCode> {
+///     let download = FileDownload::from_path(format!("exports/{}", name))
+///         .await
+///         .map_err(|_| StatusCode::NOT_FOUND)?;
+///
+///     Ok(download.with_range(&headers))
+/// }
+/// ```
+pub struct FileDownload {
+    source: Source,
+    filename: Option<String>,
+    content_type: Option<String>,
+    range: Option<HeaderValue>,
+}
+
+enum Source {
+    File { file: tokio::fs::File, len: u64 },
+    Reader(Box<dyn AsyncRead + Send + Unpin>),
+}
+
+impl FileDownload {
+    /// Open a file, using its name for `Content-Disposition` and to guess the `Content-Type`
+    pub async fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let file = tokio::fs::File::open(path).await?;
+        let len = file.metadata().await?.len();
+
+        Ok(Self {
+            source: Source::File { file, len },
+            filename: path.file_name().map(|name| name.to_string_lossy().into_owned()),
+            content_type: None,
+            range: None,
+        })
+    }
+
+    /// Stream from any reader; range requests are not supported for readers
+    pub fn from_reader(reader: impl AsyncRead + Send + Unpin + 'static) -> Self {
+        Self {
+            source: Source::Reader(Box::new(reader)),
+            filename: None,
+            content_type: None,
+            range: None,
+        }
+    }
+
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// Override the `Content-Type` guessed from the file name
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Honor the `Range` header of the request
+    pub fn with_range(mut self, headers: &HeaderMap) -> Self {
+        self.range = headers.get(header::RANGE).cloned();
+        self
+    }
+}
+
+impl IntoResponse for FileDownload {
+    fn into_response(self) -> Response {
+        let content_type = self
+            .content_type
+            .or_else(|| {
+                let filename = self.filename.as_deref()?;
+
+                mime_guess::from_path(filename).first().map(|mime| mime.to_string())
+            })
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        let mut headers = HeaderMap::new();
+
+        if let Ok(value) = HeaderValue::from_str(&content_type) {
+            headers.insert(header::CONTENT_TYPE, value);
+        }
+
+        if let Some(filename) = &self.filename {
+            let disposition = format!("attachment; filename=\"{}\"", filename.replace(['"', '\\'], "_"));
+
+            if let Ok(value) = HeaderValue::from_str(&disposition) {
+                headers.insert(header::CONTENT_DISPOSITION, value);
+            }
+        }
+
+        let (mut file, len) = match self.source {
+            Source::File { file, len } => (file, len),
+            Source::Reader(reader) => {
+                return (headers, Body::from_stream(ReaderStream::new(reader))).into_response();
+            }
+        };
+
+        headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+        let range = match self.range.as_ref().and_then(|value| value.to_str().ok()) {
+            Some(value) => match parse_range(value, len) {
+                RangeRequest::Satisfiable(start, end) => Some((start, end)),
+                RangeRequest::Unsupported => None,
+                RangeRequest::Unsatisfiable => {
+                    headers.insert(
+                        header::CONTENT_RANGE,
+                        HeaderValue::from_str(&format!("bytes */{}", len)).unwrap(),
+                    );
+
+                    return (StatusCode::RANGE_NOT_SATISFIABLE, headers).into_response();
+                }
+            },
+            None => None,
+        };
+
+        let Some((start, end)) = range else {
+            headers.insert(header::CONTENT_LENGTH, HeaderValue::from(len));
+
+            return (headers, Body::from_stream(ReaderStream::new(file))).into_response();
+        };
+
+        let length = end - start + 1;
+
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from(length));
+        headers.insert(
+            header::CONTENT_RANGE,
+            HeaderValue::from_str(&format!("bytes {}-{}/{}", start, end, len)).unwrap(),
+        );
+
+        let stream = futures_util::stream::once(async move {
+            file.seek(SeekFrom::Start(start)).await?;
+
+            Ok::<_, std::io::Error>(ReaderStream::new(file.take(length)))
+        })
+        .try_flatten()
+        .boxed();
+
+        (StatusCode::PARTIAL_CONTENT, headers, Body::from_stream(stream)).into_response()
+    }
+}
+
+enum RangeRequest {
+    /// Inclusive byte range
+    Satisfiable(u64, u64),
+    Unsatisfiable,
+    /// Malformed or multi-range requests are answered with the full body
+    Unsupported,
+}
+
+/// Parse a single `bytes=start-end`, `bytes=start-` or `bytes=-suffix` range
+fn parse_range(value: &str, len: u64) -> RangeRequest {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return RangeRequest::Unsupported;
+    };
+
+    if spec.contains(',') {
+        return RangeRequest::Unsupported;
+    }
+
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return RangeRequest::Unsupported;
+    };
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return RangeRequest::Unsupported,
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return RangeRequest::Unsatisfiable,
+            Ok(suffix) => (len.saturating_sub(suffix), len.saturating_sub(1)),
+            Err(_) => return RangeRequest::Unsupported,
+        },
+        (start, "") => match start.parse::<u64>() {
+            Ok(start) => (start, len.saturating_sub(1)),
+            Err(_) => return RangeRequest::Unsupported,
+        },
+        (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+            (Ok(start), Ok(end)) if start <= end => (start, end.min(len.saturating_sub(1))),
+            _ => return RangeRequest::Unsupported,
+        },
+    };
+
+    if len == 0 || start >= len {
+        return RangeRequest::Unsatisfiable;
+    }
+
+    RangeRequest::Satisfiable(start, end)
+}
@@ -0,0 +1,273 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::{
+    Router,
+    extract::{MatchedPath, Request, State},
+    http::{HeaderValue, StatusCode, Uri, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tower::ServiceExt;
+
+/// Header read by [`Versioned::into_header_router`] to pick a version
+pub const VERSION_HEADER: &str = "Accept-Version";
+
+/// Builder for serving several versions of an API side by side
+///
+/// Every version is a plain `Router` (usually built from one or more controllers).
+/// The builder can either mount each version under its own `/v{n}` prefix or serve
+/// all of them from the same paths and dispatch on the `Accept-Version` header.
+///
+/// Usage:
+/// ```rust,ignore
+/// use argon_core::routing::Versioned;
+///
+/// // GET /v1/users and GET /v2/users
+/// let router = Versioned::new()
+///     .version(1, UserControllerV1::router())
+///     .version(2, UserControllerV2::router())
+///     .into_path_router();
+///
+/// // GET /users with `Accept-Version: 1` or `Accept-Version: v2`
+/// let router = Versioned::new()
+///     .version(1, UserControllerV1::router())
+///     .version(2, UserControllerV2::router())
+///     .default_version(2)
+///     .into_header_router();
+/// ```
+///
+/// # OpenAPI
+///
+/// With path versioning every version owns distinct paths, so nest each version's
+/// generated `...Api` struct under the same prefix in the main doc:
+///
+/// ```rust,ignore
+/// #[derive(OpenApi)]
+/// #[openapi(nest(
+///     (path = "/v1", api = UserControllerV1Api),
+///     (path = "/v2", api = UserControllerV2Api),
+/// ))]
+/// pub struct MainApiDoc;
+/// ```
+///
+/// With header versioning the paths are shared between versions and an OpenAPI
+/// document can only describe one operation per method and path. Generate one
+/// document per version instead and set `info(version = "...")` on each.
+#[derive(Clone, Default)]
+pub struct Versioned {
+    versions: Vec<(u32, Router)>,
+    default: Option<u32>,
+}
+
+impl Versioned {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the router serving `version`
+    ///
+    /// Registering the same version twice replaces the previous router.
+    pub fn version(mut self, version: u32, router: Router) -> Self {
+        self.versions.retain(|(existing, _)| *existing != version);
+        self.versions.push((version, router));
+        self
+    }
+
+    /// Version used by the header router when the request has no `Accept-Version`
+    ///
+    /// Defaults to the highest registered version.
+    pub fn default_version(mut self, version: u32) -> Self {
+        self.default = Some(version);
+        self
+    }
+
+    /// Mount every version under a `/v{n}` prefix
+    pub fn into_path_router(self) -> Router {
+        self.versions
+            .into_iter()
+            .fold(Router::new(), |router, (version, versioned)| {
+                router.nest(&format!("/v{}", version), versioned)
+            })
+    }
+
+    /// Serve every version from the same paths, dispatching on `Accept-Version`
+    ///
+    /// Unknown or malformed versions are rejected with `400 Bad Request`.
+    pub fn into_header_router(self) -> Router {
+        let default = self
+            .default
+            .or_else(|| self.versions.iter().map(|(version, _)| *version).max());
+        let versions = Arc::new(self.versions);
+
+        Router::new().fallback(move |request: Request| {
+            let versions = versions.clone();
+
+            async move { dispatch(&versions, default, request).await }
+        })
+    }
+}
+
+async fn dispatch(versions: &[(u32, Router)], default: Option<u32>, request: Request) -> Response {
+    let requested = match request.headers().get(VERSION_HEADER) {
+        Some(value) => parse_version(value),
+        None => default,
+    };
+
+    let Some(requested) = requested else {
+        tracing::debug!("cannot resolve a version from `{}`", VERSION_HEADER);
+
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    let Some((_, router)) = versions.iter().find(|(version, _)| *version == requested) else {
+        tracing::debug!("requested api version `{}` is not registered", requested);
+
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    router
+        .clone()
+        .oneshot(request)
+        .await
+        .unwrap_or_else(|err| match err {})
+}
+
+/// Parse `1` or `v1` into a version number
+fn parse_version(value: &HeaderValue) -> Option<u32> {
+    let value = value.to_str().ok()?.trim();
+    let value = value
+        .strip_prefix('v')
+        .or_else(|| value.strip_prefix('V'))
+        .unwrap_or(value);
+
+    value.parse().ok()
+}
+
+/// A route registered by a `#[controller]`
+///
+/// `path` is the path declared on the handler, before any `nest` prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct RouteInfo {
+    pub method: &'static str,
+    pub path: &'static str,
+    pub controller: &'static str,
+}
+
+inventory::collect!(RouteInfo);
+
+/// Every route of every `#[controller]` linked into the binary, sorted by path
+pub fn registered_routes() -> Vec<RouteInfo> {
+    let mut routes: Vec<RouteInfo> = inventory::iter::<RouteInfo>.into_iter().copied().collect();
+
+    routes.sort_by(|a, b| (a.path, a.method).cmp(&(b.path, b.method)));
+
+    routes
+}
+
+/// What to do with a request whose path only lacks or has an extra trailing slash
+///
+/// Only requests that matched no route are redirected, so routes declared with both
+/// forms and `{*wildcard}` routes are served as they are.
+///
+/// Usage:
+/// ```rust,ignore
+/// router.layer(axum::middleware::from_fn_with_state(
+///     TrailingSlash::RedirectToNoSlash,
+///     trailing_slash,
+/// ))
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingSlash {
+    /// `/users` and `/users/` are different paths
+    #[default]
+    Strict,
+    /// `/users/` is redirected to `/users`
+    RedirectToNoSlash,
+    /// `/users` is redirected to `/users/`
+    RedirectToSlash,
+}
+
+impl TrailingSlash {
+    /// Path the request is redirected to, if any
+    fn canonical(&self, path: &str) -> Option<String> {
+        match self {
+            TrailingSlash::Strict => None,
+            TrailingSlash::RedirectToNoSlash if path.len() > 1 && path.ends_with('/') => {
+                Some(path.trim_end_matches('/').to_string()).filter(|path| !path.is_empty())
+            }
+            TrailingSlash::RedirectToSlash if !path.ends_with('/') => Some(format!("{}/", path)),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for TrailingSlash {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "strict" => Ok(TrailingSlash::Strict),
+            "no_slash" => Ok(TrailingSlash::RedirectToNoSlash),
+            "slash" => Ok(TrailingSlash::RedirectToSlash),
+            other => Err(anyhow::anyhow!(
+                "unknown trailing slash mode `{}`, expected `strict`, `no_slash` or `slash`",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for TrailingSlash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match self {
+            TrailingSlash::Strict => "strict",
+            TrailingSlash::RedirectToNoSlash => "no_slash",
+            TrailingSlash::RedirectToSlash => "slash",
+        };
+
+        write!(f, "{}", mode)
+    }
+}
+
+/// Answers `308 Permanent Redirect` to the canonical path, keeping the method and query
+///
+/// Layer it with `Router::layer`, not `route_layer`, so it also sees unmatched requests.
+pub async fn trailing_slash(State(mode): State<TrailingSlash>, request: Request, next: Next) -> Response {
+    if request.extensions().get::<MatchedPath>().is_some() {
+        return next.run(request).await;
+    }
+
+    let Some(path) = mode.canonical(request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let location = match request.uri().query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path,
+    };
+
+    // a path that doesn't form a valid uri is left to the router
+    if location.parse::<Uri>().is_err() {
+        return next.run(request).await;
+    }
+
+    tracing::debug!("redirecting `{}` to `{}`", request.uri(), location);
+
+    (StatusCode::PERMANENT_REDIRECT, [(header::LOCATION, location)]).into_response()
+}
+
+/// `204 No Content` answer to an `OPTIONS` request, generated by `#[controller(preflight)]`
+///
+/// `allow` lists the methods of the path, sent as `Allow` and `Access-Control-Allow-Methods`.
+/// No origin is allowed here, which origins may call the API is left to the CORS layer, and
+/// that layer answers preflights itself when one wraps the router.
+pub fn preflight(allow: &'static str) -> Response {
+    (
+        StatusCode::NO_CONTENT,
+        [
+            (header::ALLOW, allow),
+            (header::ACCESS_CONTROL_ALLOW_METHODS, allow),
+        ],
+    )
+        .into_response()
+}
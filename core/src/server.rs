@@ -0,0 +1,198 @@
+use std::{fmt::Debug, future::Future, pin::Pin, time::Duration};
+
+use axum::{Router, body::Body, extract::ConnectInfo, http::Request, serve::Listener};
+use hyper::{body::Incoming, server::conn::http1};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo, TokioTimer},
+    server::conn::auto::Builder,
+};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tower::ServiceExt;
+
+/// Connection settings of [`serve`]
+///
+/// HTTP/2 is served in cleartext (h2c) next to HTTP/1, detected from the connection
+/// preface. `keep_alive_timeout` closes HTTP/1 connections idle for that long and
+/// pings idle HTTP/2 connections at that interval, dropping peers that don't answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerOptions {
+    pub keep_alive: bool,
+    pub keep_alive_timeout: Duration,
+    pub http2: bool,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            keep_alive: true,
+            keep_alive_timeout: Duration::from_secs(75),
+            http2: true,
+        }
+    }
+}
+
+impl ServerOptions {
+    /// Connection builder with the options applied
+    pub fn builder(&self) -> Builder<TokioExecutor> {
+        let mut builder = Builder::new(TokioExecutor::new());
+
+        builder
+            .http1()
+            .timer(TokioTimer::new())
+            .keep_alive(self.keep_alive)
+            .header_read_timeout(self.keep_alive_timeout);
+
+        builder
+            .http2()
+            .timer(TokioTimer::new())
+            .keep_alive_interval(self.keep_alive.then_some(self.keep_alive_timeout));
+
+        if !self.http2 {
+            builder = builder.http1_only();
+        }
+
+        builder
+    }
+
+    /// HTTP/1 connection builder with the options applied, used when HTTP/2 is disabled
+    ///
+    /// The auto builder ignores `http1_only` once upgrades are enabled, so HTTP/2 would
+    /// still be served.
+    pub fn http1_builder(&self) -> http1::Builder {
+        let mut builder = http1::Builder::new();
+
+        builder
+            .timer(TokioTimer::new())
+            .keep_alive(self.keep_alive)
+            .header_read_timeout(self.keep_alive_timeout);
+
+        builder
+    }
+}
+
+/// Serve `app` until `shutdown` resolves, then wait for the open connections to finish
+///
+/// Handlers can extract `ConnectInfo<L::Addr>` like with `axum::serve` and
+/// `into_make_service_with_connect_info`, that is `ConnectInfo<SocketAddr>` on a
+/// `TcpListener`. Failed accepts are logged and retried by the listener.
+pub async fn serve<L, F>(mut listener: L, app: Router, options: ServerOptions, shutdown: F) -> std::io::Result<()>
+where
+    L: Listener,
+    L::Addr: Clone + Debug + Sync + 'static,
+    F: Future<Output = ()>,
+{
+    let builder = options.builder();
+    let http1 = options.http1_builder();
+    let stop = CancellationToken::new();
+    let connections = TaskTracker::new();
+    let mut shutdown = std::pin::pin!(shutdown);
+
+    loop {
+        let (stream, remote) = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = &mut shutdown => break,
+        };
+
+        let app = app.clone();
+        let peer = remote.clone();
+        let service = hyper::service::service_fn(move |mut request: Request<Incoming>| {
+            request.extensions_mut().insert(ConnectInfo(peer.clone()));
+
+            app.clone().oneshot(request.map(Body::new))
+        });
+
+        let io = TokioIo::new(stream);
+        let stop = stop.clone();
+
+        if options.http2 {
+            let connection = builder.serve_connection_with_upgrades(io, service).into_owned();
+            connections.spawn(drive(connection, |connection| connection.graceful_shutdown(), stop, remote));
+        } else {
+            let connection = http1.serve_connection(io, service).with_upgrades();
+            connections.spawn(drive(connection, |connection| connection.graceful_shutdown(), stop, remote));
+        }
+    }
+
+    // stop accepting before waiting on the open connections
+    drop(listener);
+    stop.cancel();
+    connections.close();
+    connections.wait().await;
+
+    Ok(())
+}
+
+/// Serve `connection` until it closes, shutting it down gracefully once `stop` is cancelled
+async fn drive<C, E>(connection: C, graceful_shutdown: fn(Pin<&mut C>), stop: CancellationToken, remote: impl Debug)
+where
+    C: Future<Output = Result<(), E>>,
+    E: Debug,
+{
+    let mut connection = std::pin::pin!(connection);
+
+    let result = tokio::select! {
+        result = connection.as_mut() => result,
+        _ = stop.cancelled() => {
+            // finish the in-flight requests, then close
+            graceful_shutdown(connection.as_mut());
+            connection.await
+        }
+    };
+
+    if let Err(err) = result {
+        tracing::debug!("connection from {:?} failed: {:?}", remote, err);
+    }
+}
+
+/// [`serve`] several listeners at once, e.g. the API and an admin port, until `shutdown`
+/// resolves, then wait for the open connections of all of them to finish
+///
+/// Usage:
+/// ```rust,ignore
+/// serve_many(vec![(api_listener, app), (admin_listener, admin)], ServerOptions::default(), ctrl_c()).await?;
+/// ```
+pub async fn serve_many<L, F>(servers: Vec<(L, Router)>, options: ServerOptions, shutdown: F) -> std::io::Result<()>
+where
+    L: Listener,
+    L::Addr: Clone + Debug + Sync + 'static,
+    F: Future<Output = ()>,
+{
+    let stop = CancellationToken::new();
+
+    let served = futures_util::future::try_join_all(
+        servers
+            .into_iter()
+            .map(|(listener, app)| serve(listener, app, options, stop.clone().cancelled_owned())),
+    );
+    let mut served = std::pin::pin!(served);
+
+    tokio::select! {
+        result = &mut served => return result.map(drop),
+        _ = shutdown => {}
+    }
+
+    // every server stops accepting, then they drain together
+    stop.cancel();
+
+    served.await.map(drop)
+}
+
+/// Bind a Unix domain socket at `path`, replacing the socket a previous run left behind
+///
+/// Anything else already at `path` is left alone and fails the bind.
+#[cfg(unix)]
+pub fn bind_unix(path: &std::path::Path) -> std::io::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            tracing::debug!("removing the stale socket {}", path.display());
+            std::fs::remove_file(path)?;
+        }
+        Ok(_) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+
+    tokio::net::UnixListener::bind(path)
+}
@@ -0,0 +1,187 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, Method, StatusCode, header},
+    middleware::Next,
+    response::Response,
+};
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+
+type InFlight = Shared<BoxFuture<'static, Option<Arc<BufferedResponse>>>>;
+
+/// Largest response [`SingleFlight`] shares by default, see [`SingleFlight::max_body_bytes`]
+pub const DEFAULT_MAX_SHARED_BODY: usize = 1024 * 1024;
+
+/// Coalesces concurrent identical `GET` requests, the handler runs once and every
+/// caller gets a copy of its response
+///
+/// Requests are identical when they share the path, the query and the headers the
+/// response varies on: `Authorization`, `Cookie`, `X-Tenant-Id`, `Accept-Encoding`,
+/// `Accept-Language` and `Accept-Version` by default, see [`SingleFlight::vary`]. So users
+/// never receive each other's responses, nor one in another encoding or language.
+/// Nothing is kept once the response is out, it is not a cache.
+///
+/// Shared responses are buffered in memory up to [`SingleFlight::max_body_bytes`], past
+/// it the response isn't shared and each caller runs the handler on its own.
+///
+/// Usage:
+/// ```rust,ignore
+/// router.route(
+///     "/reports/summary",
+///     get(summary).layer(axum::middleware::from_fn_with_state(SingleFlight::new(), single_flight)),
+/// )
+/// ```
+#[derive(Clone)]
+pub struct SingleFlight {
+    in_flight: Arc<Mutex<HashMap<String, InFlight>>>,
+    vary: Arc<Vec<HeaderName>>,
+    max_body_bytes: usize,
+}
+
+impl Default for SingleFlight {
+    fn default() -> Self {
+        Self {
+            in_flight: Arc::default(),
+            vary: Arc::new(vec![
+                header::AUTHORIZATION,
+                header::COOKIE,
+                HeaderName::from_static("x-tenant-id"),
+                header::ACCEPT_ENCODING,
+                header::ACCEPT_LANGUAGE,
+                HeaderName::from_static("accept-version"),
+            ]),
+            max_body_bytes: DEFAULT_MAX_SHARED_BODY,
+        }
+    }
+}
+
+impl SingleFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tell apart the requests differing in `header`, on top of the defaults
+    pub fn vary(mut self, header: HeaderName) -> Self {
+        Arc::make_mut(&mut self.vary).push(header);
+        self
+    }
+
+    /// Share responses up to `max_body_bytes`, [`DEFAULT_MAX_SHARED_BODY`] by default
+    pub fn max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct BufferedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl BufferedResponse {
+    fn to_response(&self) -> Response {
+        let mut response = Response::new(Body::from(self.body.clone()));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+
+        response
+    }
+}
+
+pub async fn single_flight(State(single_flight): State<SingleFlight>, request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+
+    let key = single_flight.request_key(&request);
+    // to run the request again when the response can't be shared
+    let (parts, body) = request.into_parts();
+
+    let in_flight = {
+        let mut in_flight = single_flight
+            .in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match in_flight.get(&key) {
+            Some(shared) => {
+                tracing::debug!("joining the in-flight request `{}`", key);
+
+                shared.clone()
+            }
+            None => {
+                let request = Request::from_parts(parts.clone(), body);
+                let shared = run(single_flight.clone(), key.clone(), request, next.clone())
+                    .boxed()
+                    .shared();
+                in_flight.insert(key, shared.clone());
+
+                shared
+            }
+        }
+    };
+
+    match in_flight.await {
+        Some(response) => response.to_response(),
+        // the body of a `GET` isn't sent again, it is not part of the key either
+        None => next.run(Request::from_parts(parts, Body::empty())).await,
+    }
+}
+
+// owned by every caller, so the handler keeps running if the first one goes away
+async fn run(single_flight: SingleFlight, key: String, request: Request, next: Next) -> Option<Arc<BufferedResponse>> {
+    let (parts, body) = next.run(request).await.into_parts();
+
+    let buffered = match axum::body::to_bytes(body, single_flight.max_body_bytes).await {
+        Ok(body) => Some(Arc::new(BufferedResponse {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+        })),
+        Err(err) => {
+            tracing::warn!(
+                "cannot share the response of `{}`, running it for each caller: {:?}",
+                key,
+                err
+            );
+
+            None
+        }
+    };
+
+    single_flight
+        .in_flight
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .remove(&key);
+
+    buffered
+}
+
+impl SingleFlight {
+    fn request_key(&self, request: &Request) -> String {
+        let headers = self.vary.iter().map(|name| {
+            let values = request
+                .headers()
+                .get_all(name)
+                .iter()
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+                .collect::<Vec<_>>()
+                .join(";");
+
+            format!("{}: {}", name, values)
+        });
+
+        std::iter::once(format!("{} {}", request.method(), request.uri()))
+            .chain(headers)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
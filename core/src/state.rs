@@ -0,0 +1,111 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::Arc,
+};
+
+use axum::{extract::FromRequestParts, http::StatusCode, http::request::Parts};
+use sea_orm::DatabaseConnection;
+
+/// Shared services of the application, layered once as an `Extension`
+///
+/// Holds the database connection plus any number of services keyed by type
+/// (config, authenticator, clients...). Handlers pull pieces out with the
+/// [`Db`] and [`Service`] extractors instead of juggling many extensions.
+///
+/// Usage:
+/// ```rust,ignore
+/// let state = AppState::new(db)
+///     .with(AppConfig::get().await)
+///     .with(BasicAuthenticator::new(db.clone()));
+///
+/// let app = routes().layer(Extension(state));
+///
+/// async fn handler(Db(db): Db, Service(config): Service<AppConfig>) { ... }
+/// ```
+#[derive(Clone)]
+pub struct AppState {
+    db: DatabaseConnection,
+    // behind an Arc since the Extension layer clones the state for every request
+    services: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl AppState {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            services: Arc::default(),
+        }
+    }
+
+    /// Add a service, replacing any previous service of the same type
+    pub fn with<T>(mut self, service: T) -> Self
+    where
+        T: Send + Sync + 'static,
+    {
+        Arc::make_mut(&mut self.services).insert(TypeId::of::<T>(), Arc::new(service));
+        self
+    }
+
+    pub fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.services
+            .get(&TypeId::of::<T>())
+            .and_then(|service| service.downcast_ref::<T>())
+    }
+}
+
+fn app_state(parts: &Parts) -> Result<&AppState, StatusCode> {
+    parts.extensions.get::<AppState>().ok_or_else(|| {
+        tracing::error!("no AppState Extension available");
+
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// Extracts the database connection from [`AppState`]
+///
+/// Behind [`crate::db::tenant_middleware`] it is the connection of the request's tenant.
+pub struct Db(pub DatabaseConnection);
+
+impl<S> FromRequestParts<S> for Db
+where
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(tenant) = parts.extensions.get::<crate::db::Tenant>() {
+            return Ok(Db(tenant.db().clone()));
+        }
+
+        Ok(Db(app_state(parts)?.db().clone()))
+    }
+}
+
+/// Extracts a clone of the `T` service registered in [`AppState`]
+pub struct Service<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for Service<T>
+where
+    S: Send + Sync,
+    T: Clone + Send + Sync + 'static,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(service) = app_state(parts)?.get::<T>() else {
+            tracing::error!("no `{}` service registered in AppState", std::any::type_name::<T>());
+
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        };
+
+        Ok(Service(service.clone()))
+    }
+}
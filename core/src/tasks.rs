@@ -0,0 +1,63 @@
+use std::{future::Future, panic::AssertUnwindSafe, sync::LazyLock, time::Duration};
+
+use futures_util::FutureExt;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tracing::Instrument;
+
+use crate::panic::panic_message;
+
+static TASKS: LazyLock<TaskTracker> = LazyLock::new(TaskTracker::new);
+static SHUTDOWN: LazyLock<CancellationToken> = LazyLock::new(CancellationToken::new);
+
+/// Spawn a fire-and-forget background task
+///
+/// The task runs inside a `task` tracing span named after `name`. A panic inside
+/// the task is caught and logged instead of being reported by the runtime, and the
+/// task is tracked so [`drain`] can wait for it on shutdown.
+///
+/// Usage:
+/// ```rust,ignore
+/// argon_core::tasks::spawn_task("welcome_email", async move {
+///     mailer.send_welcome(&user).await;
+/// });
+/// ```
+pub fn spawn_task<F>(name: &'static str, future: F) -> tokio::task::JoinHandle<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let span = tracing::info_span!("task", name);
+
+    TASKS.spawn(
+        async move {
+            if let Err(panic) = AssertUnwindSafe(future).catch_unwind().await {
+                tracing::error!("background task `{}` panicked: {}", name, panic_message(&*panic));
+            }
+        }
+        .instrument(span),
+    )
+}
+
+/// Token cancelled once shutdown starts
+///
+/// Long running tasks can `select!` on `shutdown_token().cancelled()` to stop early.
+pub fn shutdown_token() -> CancellationToken {
+    SHUTDOWN.clone()
+}
+
+/// Stop accepting new work and wait for spawned tasks to finish
+///
+/// Returns `false` when tasks were still running after `timeout`.
+pub async fn drain(timeout: Duration) -> bool {
+    SHUTDOWN.cancel();
+    TASKS.close();
+
+    tracing::info!("waiting for {} background task(s) to finish", TASKS.len());
+
+    if tokio::time::timeout(timeout, TASKS.wait()).await.is_err() {
+        tracing::warn!("{} background task(s) still running after {:?}", TASKS.len(), timeout);
+
+        return false;
+    }
+
+    true
+}
@@ -0,0 +1,170 @@
+mod spec;
+
+pub use spec::SpecValidator;
+
+use axum::{
+    Extension, Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+    response::Response,
+};
+use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase};
+use tower::ServiceExt;
+
+use crate::{auth::AuthenticatableUser, controller::Controller, db::UserStore, state::AppState};
+
+/// Drives a controller router in tests, without a server or a database
+///
+/// Extensions are layered in the order they are added, so stub authenticators,
+/// users or a mock [`AppState`] are visible to every handler.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[tokio::test]
+/// async fn index_answers() {
+///     let app = TestApp::controller::<TestController>().with_state(mock_state(
+///         MockDatabase::new(DatabaseBackend::Postgres).append_query_results([[user]]),
+///     ));
+///
+///     let (status, body) = app.get_json("/hello/1").await;
+///
+///     assert_eq!(status, StatusCode::OK);
+///     assert_eq!(body["message"], "hello");
+/// }
+/// ```
+pub struct TestApp {
+    router: Router,
+}
+
+impl TestApp {
+    pub fn new(router: Router) -> Self {
+        Self { router }
+    }
+
+    pub fn controller<C: Controller>() -> Self {
+        Self::new(C::router())
+    }
+
+    /// Layer `extension`, e.g. a fake authenticator or an authenticated user
+    pub fn with_extension<T>(self, extension: T) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        Self::new(self.router.layer(Extension(extension)))
+    }
+
+    pub fn with_state(self, state: AppState) -> Self {
+        self.with_extension(state)
+    }
+
+    pub async fn request(&self, request: Request<Body>) -> Response {
+        self.router
+            .clone()
+            .oneshot(request)
+            .await
+            .unwrap_or_else(|err| match err {})
+    }
+
+    /// `GET uri`, returning the status and the JSON body (`Null` when empty)
+    pub async fn get_json(&self, uri: &str) -> (StatusCode, serde_json::Value) {
+        self.json(Method::GET, uri, None).await
+    }
+
+    /// `method uri` with `body` serialized as JSON, returning the status and the JSON body
+    pub async fn send_json<T>(&self, method: Method, uri: &str, body: &T) -> (StatusCode, serde_json::Value)
+    where
+        T: serde::Serialize,
+    {
+        let body = serde_json::to_vec(body).expect("the request body should serialize");
+
+        self.json(method, uri, Some(body)).await
+    }
+
+    async fn json(&self, method: Method, uri: &str, body: Option<Vec<u8>>) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder().method(method).uri(uri);
+        if body.is_some() {
+            request = request.header(header::CONTENT_TYPE, "application/json");
+        }
+
+        let request = request
+            .body(body.map(Body::from).unwrap_or_default())
+            .expect("the request should be valid");
+
+        let response = self.request(request).await;
+        let status = response.status();
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("the response body should be readable");
+
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+                panic!("the response body is not JSON ({}): {}", err, String::from_utf8_lossy(&bytes))
+            })
+        };
+
+        (status, json)
+    }
+}
+
+/// Connection answering with the results appended to `mock`
+pub fn mock_db(mock: MockDatabase) -> DatabaseConnection {
+    mock.into_connection()
+}
+
+/// [`AppState`] over a mock connection, register stub services with [`AppState::with`]
+pub fn mock_state(mock: MockDatabase) -> AppState {
+    AppState::new(mock_db(mock))
+}
+
+/// [`AppState`] over a Postgres mock without any prepared result
+pub fn empty_state() -> AppState {
+    mock_state(MockDatabase::new(DatabaseBackend::Postgres))
+}
+
+/// [`UserStore`] over a list of users, for testing authenticators without a database
+///
+/// Usage:
+/// ```rust,ignore
+/// let authenticator = BasicAuthenticator::new(MemoryUserStore::new().user(alice));
+///
+/// assert!(authenticator.attempt("alice".into(), "secret".into()).await.is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MemoryUserStore<U> {
+    users: Vec<U>,
+}
+
+impl<U> Default for MemoryUserStore<U> {
+    fn default() -> Self {
+        Self { users: Vec::new() }
+    }
+}
+
+impl<U> MemoryUserStore<U> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn user(mut self, user: U) -> Self {
+        self.users.push(user);
+        self
+    }
+}
+
+impl<U> UserStore<U> for MemoryUserStore<U>
+where
+    U: AuthenticatableUser + Clone + Send + Sync,
+    U::Username: PartialEq + Sync,
+    U::Id: PartialEq + Sync,
+{
+    async fn find_by_username(&self, username: &U::Username) -> Result<Option<U>, DbErr> {
+        Ok(self.users.iter().find(|user| user.get_username() == *username).cloned())
+    }
+
+    async fn find_by_id(&self, id: &U::Id) -> Result<Option<U>, DbErr> {
+        Ok(self.users.iter().find(|user| user.get_id() == *id).cloned())
+    }
+}
@@ -0,0 +1,249 @@
+use axum::http::{Method, StatusCode};
+use serde_json::Value;
+
+/// Checks JSON bodies against the schemas of an OpenAPI spec, to catch drift between
+/// the handlers and their docs
+///
+/// Objects are checked strictly: a field missing from the documented `properties` is
+/// reported unless the schema allows `additionalProperties`.
+///
+/// Usage:
+/// ```rust,ignore
+/// let spec = SpecValidator::new(&MainApiDoc::openapi());
+///
+/// let (status, body) = app.get_json("/hello/1").await;
+///
+/// spec.assert_response(Method::GET, "/hello/1", status, &body);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpecValidator {
+    spec: Value,
+}
+
+impl SpecValidator {
+    pub fn new(spec: &utoipa::openapi::OpenApi) -> Self {
+        Self {
+            spec: serde_json::to_value(spec).expect("the spec should serialize"),
+        }
+    }
+
+    /// Check a request body against the JSON request body of the operation serving `method path`
+    ///
+    /// `path` is the requested path, e.g. `/users/1` for the `/users/{id}` operation.
+    pub fn validate_request(&self, method: Method, path: &str, body: &Value) -> Result<(), Vec<String>> {
+        let operation = self.operation(&method, path)?;
+
+        let Some(schema) = json_schema(operation.get("requestBody")) else {
+            return Err(vec![format!("{} {} documents no JSON request body", method, path)]);
+        };
+
+        self.check(schema, body)
+    }
+
+    /// Check a response body against the JSON response the operation documents for `status`
+    pub fn validate_response(
+        &self,
+        method: Method,
+        path: &str,
+        status: StatusCode,
+        body: &Value,
+    ) -> Result<(), Vec<String>> {
+        let operation = self.operation(&method, path)?;
+        let responses = operation.get("responses");
+
+        // an exact status wins over a `2XX` range, which wins over `default`
+        let code = status.as_u16().to_string();
+        let range = format!("{}XX", status.as_u16() / 100);
+        let response = [code.as_str(), range.as_str(), "default"]
+            .into_iter()
+            .find_map(|key| responses?.get(key))
+            .map(|response| self.resolve(response));
+
+        let Some(response) = response else {
+            return Err(vec![format!("{} {} documents no {} response", method, path, status)]);
+        };
+
+        match json_schema(Some(response)) {
+            Some(schema) => self.check(schema, body),
+            None if body.is_null() => Ok(()),
+            None => Err(vec![format!("{} {} documents no JSON body for {}", method, path, status)]),
+        }
+    }
+
+    /// Panicking form of [`SpecValidator::validate_response`] for tests
+    pub fn assert_response(&self, method: Method, path: &str, status: StatusCode, body: &Value) {
+        if let Err(errors) = self.validate_response(method.clone(), path, status, body) {
+            panic!(
+                "the {} response of {} {} doesn't match the spec:\n{}",
+                status,
+                method,
+                path,
+                errors.join("\n")
+            );
+        }
+    }
+
+    fn operation(&self, method: &Method, path: &str) -> Result<&Value, Vec<String>> {
+        let paths = self.spec.get("paths").and_then(Value::as_object);
+
+        paths
+            .into_iter()
+            .flatten()
+            .filter(|(template, _)| path_matches(template, path))
+            .find_map(|(_, item)| item.get(method.as_str().to_lowercase()))
+            .ok_or_else(|| vec![format!("the spec has no operation for {} {}", method, path)])
+    }
+
+    fn check(&self, schema: &Value, body: &Value) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        self.check_value(schema, body, "$", &mut errors);
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    fn check_value(&self, schema: &Value, value: &Value, at: &str, errors: &mut Vec<String>) {
+        let schema = self.resolve(schema);
+
+        if let Some(options) = schema.get("allOf").and_then(Value::as_array) {
+            // every part describes some of the fields, check them as one object
+            let merged = self.merge_all_of(options);
+            self.check_value(&merged, value, at, errors);
+            return;
+        }
+
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(options) = schema.get(keyword).and_then(Value::as_array) {
+                let matches = options.iter().any(|option| {
+                    let mut option_errors = Vec::new();
+                    self.check_value(option, value, at, &mut option_errors);
+
+                    option_errors.is_empty()
+                });
+
+                if !matches {
+                    errors.push(format!("{}: matches none of the `{}` schemas", at, keyword));
+                }
+                return;
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+            if !allowed.contains(value) {
+                errors.push(format!("{}: {} is not one of {}", at, value, Value::Array(allowed.clone())));
+            }
+            return;
+        }
+
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(ty)) => vec![ty.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|ty| has_type(value, ty)) {
+            errors.push(format!("{}: expected {}, got {}", at, types.join(" or "), value));
+            return;
+        }
+
+        match value {
+            Value::Object(object) => {
+                let properties = schema.get("properties").and_then(Value::as_object);
+
+                for required in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+                    if let Some(required) = required.as_str()
+                        && !object.contains_key(required)
+                    {
+                        errors.push(format!("{}: missing required field `{}`", at, required));
+                    }
+                }
+
+                for (name, field) in object {
+                    let field_at = format!("{}.{}", at, name);
+
+                    match (properties.and_then(|properties| properties.get(name)), schema.get("additionalProperties")) {
+                        (Some(field_schema), _) => self.check_value(field_schema, field, &field_at, errors),
+                        (None, Some(Value::Bool(true))) => {}
+                        (None, Some(additional)) if additional.is_object() => {
+                            self.check_value(additional, field, &field_at, errors)
+                        }
+                        // an object without documented fields is free-form
+                        (None, None) if properties.is_none() => {}
+                        (None, _) => errors.push(format!("{}: undocumented field", field_at)),
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.check_value(item_schema, item, &format!("{}[{}]", at, index), errors);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn merge_all_of(&self, parts: &[Value]) -> Value {
+        let mut properties = serde_json::Map::new();
+        let mut required = Vec::new();
+
+        for part in parts {
+            let part = self.resolve(part);
+
+            if let Some(part_properties) = part.get("properties").and_then(Value::as_object) {
+                properties.extend(part_properties.clone());
+            }
+            if let Some(part_required) = part.get("required").and_then(Value::as_array) {
+                required.extend(part_required.iter().cloned());
+            }
+        }
+
+        serde_json::json!({ "type": "object", "properties": properties, "required": required })
+    }
+
+    /// Follow `$ref`s to `#/components/...`, an unknown reference is left as is
+    fn resolve<'a>(&'a self, mut schema: &'a Value) -> &'a Value {
+        while let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let Some(target) = reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.spec.pointer(pointer))
+            else {
+                break;
+            };
+
+            schema = target;
+        }
+
+        schema
+    }
+}
+
+/// Schema of the `application/json` content of a request body or a response
+fn json_schema(body: Option<&Value>) -> Option<&Value> {
+    body?.get("content")?.get("application/json")?.get("schema")
+}
+
+fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// Whether `/users/1` is served by the `/users/{id}` template
+fn path_matches(template: &str, path: &str) -> bool {
+    let path = path.split('?').next().unwrap_or(path);
+    let template: Vec<&str> = template.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    template.len() == path.len()
+        && template
+            .iter()
+            .zip(&path)
+            .all(|(expected, actual)| (expected.starts_with('{') && expected.ends_with('}')) || expected == actual)
+}
@@ -0,0 +1,115 @@
+use axum::extract::ws::{Message, WebSocket};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Publishes messages to every subscribed connection
+///
+/// Cheap to clone, register it in `AppState` and take it with the `Service` extractor.
+/// Each subscriber buffers up to `capacity` messages, a subscriber falling further
+/// behind skips the oldest ones and is told how many it missed.
+///
+/// Usage:
+/// ```rust,ignore
+/// let state = AppState::new(db).with(Broadcaster::<ChatMessage>::new(64));
+///
+/// #[get("/chat")]
+/// async fn chat(ws: WebSocketUpgrade, Service(chat): Service<Broadcaster<ChatMessage>>) -> Response {
+///     ws.on_upgrade(move |socket| forward(socket, chat.subscribe()))
+/// }
+///
+/// chat.publish(ChatMessage { ... });
+/// ```
+#[derive(Clone)]
+pub struct Broadcaster<T> {
+    sender: broadcast::Sender<T>,
+}
+
+impl<T> Broadcaster<T>
+where
+    T: Clone + Send + 'static,
+{
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self { sender }
+    }
+
+    /// Send `message` to every subscriber, returning how many there were
+    pub fn publish(&self, message: T) -> usize {
+        // an error only means nobody is subscribed
+        self.sender.send(message).unwrap_or(0)
+    }
+
+    pub fn subscribe(&self) -> Subscription<T> {
+        Subscription {
+            receiver: self.sender.subscribe(),
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+/// What a [`Subscription`] received
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Received<T> {
+    Message(T),
+    /// The subscriber was too slow and this many messages were skipped
+    Lagged(u64),
+}
+
+pub struct Subscription<T> {
+    receiver: broadcast::Receiver<T>,
+}
+
+impl<T> Subscription<T>
+where
+    T: Clone,
+{
+    /// Next message, `None` once every [`Broadcaster`] is dropped
+    pub async fn recv(&mut self) -> Option<Received<T>> {
+        match self.receiver.recv().await {
+            Ok(message) => Some(Received::Message(message)),
+            Err(RecvError::Lagged(skipped)) => Some(Received::Lagged(skipped)),
+            Err(RecvError::Closed) => None,
+        }
+    }
+}
+
+/// Forward a subscription to a socket as JSON text messages until either side closes
+///
+/// Skipped messages are announced with `{"lagged": <count>}`.
+pub async fn forward<T>(mut socket: WebSocket, mut subscription: Subscription<T>)
+where
+    T: Clone + serde::Serialize,
+{
+    loop {
+        let text = tokio::select! {
+            received = subscription.recv() => match received {
+                Some(Received::Message(message)) => match serde_json::to_string(&message) {
+                    Ok(text) => text,
+                    Err(err) => {
+                        tracing::error!("cannot serialize a broadcast message: {:?}", err);
+
+                        continue;
+                    }
+                },
+                Some(Received::Lagged(skipped)) => {
+                    tracing::debug!("websocket subscriber lagged behind by {} messages", skipped);
+
+                    serde_json::json!({ "lagged": skipped }).to_string()
+                }
+                None => break,
+            },
+            incoming = socket.recv() => match incoming {
+                // the client only listens, anything but a close is ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        if socket.send(Message::Text(text.into())).await.is_err() {
+            break;
+        }
+    }
+}
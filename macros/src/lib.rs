@@ -1,12 +1,12 @@
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 use syn::spanned::Spanned;
 use syn::{parse_macro_input, Attribute, Data, DataStruct, DeriveInput, FnArg, Fields, ImplItem, ItemImpl, LitStr, Meta, Type, LitInt};
 
 /// Macro that generates an Axum router from struct methods with route attributes
 ///
 /// Usage:
-/// ```rust
+/// ```rust,ignore
 /// struct MyController;
 ///
 /// #[controller]
@@ -22,8 +22,77 @@ use syn::{parse_macro_input, Attribute, Data, DataStruct, DeriveInput, FnArg, Fi
 ///     }
 /// }
 /// ```
+///
+/// Delete handlers should answer `204 No Content` on success and `404` when
+/// nothing matched, using `argon_core::response::Deleted`:
+/// ```rust,ignore
+/// #[delete("/users/{id}")]
+/// #[utoipa_response(response = argon_core::response::Deleted)]
+/// async fn delete_user(Db(db): Db, Path(id): Path<i32>) -> Result<Deleted, StatusCode> {
+///     let deleted = Repository::<user::Entity>::new(db)
+///         .delete(id)
+///         .await
+///         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+///
+///     Deleted::or_not_found(deleted)
+/// }
+/// ```
+///
+/// A handler can carry several route attributes, it is registered and documented on each:
+/// ```rust,ignore
+/// #[get("/users")]
+/// #[get("/users/")]
+/// async fn list_users(Db(db): Db) -> Json<Vec<User>> { ... }
+/// ```
+///
+/// Besides `router()`, the generated `Controller` impl has `api_doc()` returning the
+/// controller's OpenAPI document, also available as the generated `MyControllerApi` struct.
+///
+/// Handlers returning `impl IntoResponse` must declare their responses, since the
+/// return type tells the documentation nothing:
+/// ```rust,ignore
+/// #[get("/users/{id}")]
+/// #[utoipa_response(status = 200, body = User)]
+/// async fn get_user() -> impl IntoResponse { ... }
+/// ```
+///
+/// Middleware functions for every route of the controller are listed in `middleware(...)`,
+/// each one is applied with `axum::middleware::from_fn` and the first listed runs first:
+/// ```rust,ignore
+/// #[controller(middleware(auth_middleware::<BasicAuthenticator, BasicUser>, rate_limit))]
+/// impl AdminController { ... }
+/// ```
+///
+/// Fallible setup goes in `setup = ...`, a function taking the controller router and
+/// returning `anyhow::Result<Router>`. Build such controllers with `try_router()`,
+/// `router()` panics when the setup fails:
+/// ```rust,ignore
+/// #[controller(setup = Self::load_templates)]
+/// impl PageController { ... }
+///
+/// let router = Router::new().merge(PageController::try_router()?);
+/// ```
+///
+/// Standard errors answered by every route are documented with `errors(...)`, see
+/// `#[utoipa_errors]`:
+/// ```rust,ignore
+/// #[controller(errors(unauthorized, internal))]
+/// impl UserController { ... }
+/// ```
+///
+/// `preflight` answers `OPTIONS` on every path with a `204` listing its methods in `Allow`
+/// and `Access-Control-Allow-Methods`, see `argon_core::routing::preflight`. The `OPTIONS`
+/// routes skip the controller middleware and the `routes!` group layers, so an auth
+/// middleware doesn't turn them away, but layers put around `Controller::mount` still wrap
+/// them. No origin is allowed by the `204`, a CORS layer around the router answers
+/// preflights first and decides which origins may call:
+/// ```rust,ignore
+/// #[controller(preflight)]
+/// impl UserController { ... }
+/// ```
 #[proc_macro_attribute]
-pub fn controller(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn controller(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as ControllerArgs);
     let impl_block = parse_macro_input!(input as ItemImpl);
     let self_ty = &impl_block.self_ty;
     let struct_name = match &**self_ty {
@@ -36,15 +105,39 @@ pub fn controller(_args: TokenStream, input: TokenStream) -> TokenStream {
     };
 
     let mut route_registrations = Vec::new();
+    let mut route_list = Vec::new();
+    // `(method, path)` of the documented operations, in OpenAPI form
+    let mut operation_list = Vec::new();
+    let mut route_methods = Vec::new();
+    let mut route_paths = Vec::new();
+    let controller_name = struct_name.to_string();
+    // registered schemas are keyed by the path, so same-named controllers of other modules
+    // don't share them
+    let controller_key = quote! { concat!(module_path!(), "::", stringify!(#struct_name)) };
     let mut openapi_path_functions = Vec::new();
 
     // Iterate through items in the impl block
     for item in &impl_block.items {
         if let ImplItem::Fn(method) = item {
-            // Check for route attributes
-            if let Some((method_name, path)) = extract_route_attr(&method.attrs) {
+            // A sync handler would otherwise surface as an opaque axum `Handler` trait error
+            if method.sig.asyncness.is_none() && !extract_route_attrs(&method.attrs).is_empty() {
+                return syn::Error::new(
+                    method.sig.ident.span(),
+                    format!("route handler `{}` must be `async`, declare it as `async fn {}`", method.sig.ident, method.sig.ident)
+                )
+                .to_compile_error()
+                .into();
+            }
+
+            // Register the handler on every path of its route attributes
+            for (route_index, (method_name, path)) in extract_route_attrs(&method.attrs).into_iter().enumerate() {
                 let fn_name = &method.sig.ident;
 
+                // axum only panics on a misplaced catch-all once the router is built
+                if let Err(message) = validate_wildcard(&path) {
+                    return syn::Error::new(method.sig.ident.span(), message).to_compile_error().into();
+                }
+
                 // Determine if method takes &self, &mut self, or no self
                 let has_self = method
                     .sig
@@ -66,13 +159,104 @@ pub fn controller(_args: TokenStream, input: TokenStream) -> TokenStream {
 
                 // Generate route registration based on HTTP method
                 let axum_method = format_ident!("{}", method_name);
+                let mut method_router = quote! {
+                    axum::routing::#axum_method(#handler_call)
+                };
+
+                match extract_accepts_attr(&method.attrs) {
+                    Ok(Some(content_types)) => {
+                        method_router = quote! {
+                            #method_router.layer(axum::middleware::from_fn_with_state(
+                                argon_core::limits::AcceptedContentTypes::new([#(#content_types),*]),
+                                argon_core::limits::require_content_type,
+                            ))
+                        };
+                    }
+                    Ok(None) => {}
+                    Err(err) => return err.to_compile_error().into(),
+                }
+
+                match extract_cache_control_attr(&method.attrs) {
+                    Ok(Some(cache_control)) => {
+                        method_router = quote! {
+                            #method_router.layer(axum::middleware::map_response(
+                                |mut response: axum::response::Response| async move {
+                                    if response.status().is_success() {
+                                        response.headers_mut().insert(
+                                            axum::http::header::CACHE_CONTROL,
+                                            axum::http::HeaderValue::from_static(#cache_control),
+                                        );
+                                    }
+
+                                    response
+                                }
+                            ))
+                        };
+                    }
+                    Ok(None) => {}
+                    Err(err) => return err.to_compile_error().into(),
+                }
+
+                let deprecation = match extract_deprecated_route_attr(&method.attrs) {
+                    Ok(deprecation) => deprecation,
+                    Err(err) => return err.to_compile_error().into(),
+                };
+
+                if let Some(sunset) = &deprecation {
+                    let sunset_header = sunset.as_ref().map(|sunset| {
+                        quote! {
+                            headers.insert(
+                                axum::http::HeaderName::from_static("sunset"),
+                                axum::http::HeaderValue::from_static(#sunset),
+                            );
+                        }
+                    });
+
+                    method_router = quote! {
+                        #method_router.layer(axum::middleware::map_response(
+                            |mut response: axum::response::Response| async move {
+                                let headers = response.headers_mut();
+                                headers.insert(
+                                    axum::http::HeaderName::from_static("deprecation"),
+                                    axum::http::HeaderValue::from_static("true"),
+                                );
+                                #sunset_header
+
+                                response
+                            }
+                        ))
+                    };
+                }
+
+                // Mark the response so the envelope and similar layers leave it untouched
+                if has_attr(&method.attrs, "raw_response") {
+                    method_router = quote! {
+                        #method_router.layer(axum::middleware::map_response(
+                            |mut response: axum::response::Response| async move {
+                                response.extensions_mut().insert(argon_core::response::RawResponse);
+
+                                response
+                            }
+                        ))
+                    };
+                }
                 route_registrations.push(quote! {
-                    router = router.route(#path, axum::routing::#axum_method(#handler_call));
+                    router = router.route(#path, #method_router);
                 });
 
+                let method_upper = method_name.to_uppercase();
+                route_list.push(quote! { (#method_upper, #path) });
+                route_methods.push(method_upper);
+                route_paths.push(path.clone());
+
+                // Routed but left out of the OpenAPI documentation
+                if has_attr(&method.attrs, "skip_docs") {
+                    continue;
+                }
+
                 // Create a wrapper function name for utoipa path documentation
                 // This function will be created outside the impl block with #[utoipa::path]
-                let utoipa_wrapper_name = format_ident!("__utoipa_path_{}", fn_name);
+                let utoipa_wrapper_name = utoipa_wrapper_name(fn_name, route_index);
                 let utoipa_method = format_ident!("{}", method_name);
                 let path_str = path.clone();
                 
@@ -80,43 +264,154 @@ pub fn controller(_args: TokenStream, input: TokenStream) -> TokenStream {
                 let fn_vis = &method.vis;
                 let fn_async = method.sig.asyncness;
                 let fn_inputs = &method.sig.inputs;
-                let fn_output = &method.sig.output;
+                // `impl Trait` can't be mirrored, the wrapper body wouldn't satisfy the bound.
+                // Its responses come from `#[utoipa_response]` instead
+                let returns_impl_trait = matches!(
+                    &method.sig.output,
+                    syn::ReturnType::Type(_, ty) if matches!(**ty, Type::ImplTrait(_))
+                );
+                let fn_output = if returns_impl_trait {
+                    quote! {}
+                } else {
+                    let output = &method.sig.output;
+                    quote! { #output }
+                };
                 let fn_generics = &method.sig.generics;
                 let fn_where_clause = &method.sig.generics.where_clause;
                 
                 // Generate a wrapper function with utoipa::path attribute outside the impl block
                 // The wrapper has the same signature as the original but is just for documentation
                 // Remove leading slash from path since it will be nested under "/" in MainApiDoc
-                let path_for_utoipa = if path_str.starts_with('/') {
-                    &path_str[1..]
-                } else {
-                    &path_str
-                };
-                let path_lit = syn::LitStr::new(path_for_utoipa, method.span());
+                let path_for_utoipa = openapi_path(path_str.strip_prefix('/').unwrap_or(&path_str));
+                let path_lit = syn::LitStr::new(&path_for_utoipa, method.span());
+
+                let operation_path = openapi_path(&path_str);
+                operation_list.push(quote! { (#method_name, #operation_path) });
                 
                 let struct_name_str = struct_name.to_string();
                 let fn_name_str = fn_name.to_string();
                 
+                // Extract request/response examples from openapi_example attributes
+                let examples = match extract_openapi_example_attrs(&method.attrs) {
+                    Ok(examples) => examples,
+                    Err(err) => return err.to_compile_error().into(),
+                };
+                let mut response_example = examples.response.map(|value| ResponseExample {
+                    status: examples.status,
+                    value,
+                });
+
                 // Extract all utoipa_response attributes (supports multiple)
-                let response_attrs = extract_utoipa_response_attrs(&method.attrs);
-                
+                let mut response_attrs = extract_utoipa_response_attrs(&method.attrs, &mut response_example);
+
+                if returns_impl_trait && response_attrs.is_empty() {
+                    return syn::Error::new(
+                        method.sig.output.span(),
+                        "handlers returning `impl Trait` need a `#[utoipa_response(...)]` describing the response, or `#[skip_docs]`"
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+
+                // Document the 422 answered by a `Valid<T>` body, unless declared by hand
+                if has_valid_argument(fn_inputs) && !declares_status(&method.attrs, 422) {
+                    response_attrs.push(quote! {
+                        (status = 422, body = argon_core::response::ValidationErrorResponse, description = "Validation failed")
+                    });
+                }
+
+                // Standard errors of the handler and the controller, unless declared by hand
+                let handler_errors = match extract_utoipa_errors_attrs(&method.attrs) {
+                    Ok(errors) => errors,
+                    Err(err) => return err.to_compile_error().into(),
+                };
+                let mut documented_errors = Vec::new();
+                for name in handler_errors.iter().chain(&args.errors) {
+                    let Some((status, description)) = standard_error(&name.to_string()) else {
+                        continue;
+                    };
+                    if documented_errors.contains(&status) || declares_status(&method.attrs, status) {
+                        continue;
+                    }
+                    documented_errors.push(status);
+
+                    response_attrs.push(quote! {
+                        (status = #status, body = argon_core::response::ErrorResponse, description = #description)
+                    });
+                }
+
+                if response_example.is_some() {
+                    return syn::Error::new(
+                        fn_name.span(),
+                        "response example needs a matching `#[utoipa_response(status = ..., body = ...)]` to attach to"
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+
+                // utoipa only detects `Json<T>`, a `Body<T>` is documented as JSON and as a form.
+                // A request example replaces the auto-detected request body, so the body type
+                // is taken from the handler's `Json<T>` argument
+                let request_body_tokens = match (examples.request, body_argument_type(fn_inputs, "Body")) {
+                    (value, Some(body_type)) => {
+                        let example = value.map(|value| quote! { , example = #value });
+
+                        quote! {
+                            request_body(content(
+                                (#body_type = "application/json" #example),
+                                (#body_type = "application/x-www-form-urlencoded")
+                            )),
+                        }
+                    }
+                    (Some(value), None) => match body_argument_type(fn_inputs, "Json") {
+                        Some(body_type) => quote! {
+                            request_body(content = #body_type, example = #value),
+                        },
+                        None => {
+                            return syn::Error::new(
+                                fn_name.span(),
+                                "request example needs a `Json<T>` or `Body<T>` argument to describe the request body"
+                            )
+                            .to_compile_error()
+                            .into();
+                        }
+                    },
+                    (None, None) => quote! {},
+                };
+
+                // `RequiredHeader<H, T>`, `IfMatch` and `IfNoneMatch` arguments are header parameters
+                let header_params = header_params(fn_inputs);
+                let params_tokens = if header_params.is_empty() {
+                    quote! {}
+                } else {
+                    quote! { params(#(#header_params),*), }
+                };
+
                 // Build the utoipa::path attribute with optional responses
                 let mut path_attr_tokens = quote! {
                     #utoipa_method,
                     path = #path_lit,
+                    #request_body_tokens
+                    #params_tokens
                 };
                 
                 if !response_attrs.is_empty() {
                     path_attr_tokens = quote! {
                         #utoipa_method,
                         path = #path_lit,
+                        #request_body_tokens
+                        #params_tokens
                         responses(
                             #(#response_attrs),*
                         ),
                     };
                 }
                 
+                // utoipa marks operations of `#[deprecated]` functions as deprecated
+                let deprecated_attr = deprecation.as_ref().map(|_| quote! { #[deprecated] });
+
                 openapi_path_functions.push(quote! {
+                    #deprecated_attr
                     #[doc = concat!("Auto-generated utoipa path wrapper for ", #struct_name_str, "::", #fn_name_str)]
                     #[doc = concat!("This function is only for OpenAPI documentation generation.")]
                     #[doc = concat!("The actual handler is ", #struct_name_str, "::", #fn_name_str)]
@@ -134,6 +429,134 @@ pub fn controller(_args: TokenStream, input: TokenStream) -> TokenStream {
         }
     }
 
+    // Proc macros can't emit warnings on stable, so use of a deprecated item carries it.
+    // The controller still gets an empty router and an empty but valid spec
+    let no_routes_warning = if route_list.is_empty() {
+        let note = format!(
+            "`#[controller]` on `{}` has no route handlers, add `#[get(\"...\")]`-style attributes to its methods",
+            struct_name
+        );
+
+        quote! {
+            const _: () = {
+                #[deprecated(note = #note)]
+                struct NoRoutes;
+
+                let _ = NoRoutes;
+            };
+        }
+    } else {
+        quote! {}
+    };
+
+    // Answer the other verbs of each path with a JSON `405` listing the registered ones
+    let mut allowed_methods: Vec<(String, Vec<String>)> = Vec::new();
+    for (method, path) in route_methods.iter().zip(&route_paths) {
+        match allowed_methods.iter_mut().find(|(candidate, _)| candidate == path) {
+            Some((_, methods)) if methods.contains(method) => {}
+            Some((_, methods)) => methods.push(method.clone()),
+            None => allowed_methods.push((path.clone(), vec![method.clone()])),
+        }
+    }
+    let mut preflight_registrations = Vec::new();
+    for (path, methods) in &mut allowed_methods {
+        // Answer `OPTIONS`, e.g. CORS preflights, when no CORS layer does it first. The
+        // routes are merged outside the controller middleware, preflights carry no credentials
+        if args.preflight {
+            methods.push("OPTIONS".to_string());
+            let allow = methods.join(", ");
+
+            preflight_registrations.push(quote! {
+                .route(#path, axum::routing::options(|| async {
+                    argon_core::routing::preflight(#allow)
+                }))
+            });
+        }
+        let allow = methods.join(", ");
+
+        route_registrations.push(quote! {
+            router = router.route(#path, axum::routing::MethodRouter::new().fallback(|| async {
+                (
+                    axum::http::StatusCode::METHOD_NOT_ALLOWED,
+                    [(axum::http::header::ALLOW, #allow)],
+                    axum::Json(argon_core::response::BaseErrorResponse::<()>::new("Method Not Allowed", None)),
+                )
+            }));
+        });
+    }
+
+    // The last layer added is the outermost one, so reverse to run them in declaration order
+    let controller_middleware = args.middleware.iter().rev();
+
+    let build_router = quote! {
+        use axum::Router;
+
+        let mut router = Router::new();
+
+        #(#route_registrations)*
+
+        let router = router
+            #(.layer(axum::middleware::from_fn(#controller_middleware)))*;
+    };
+
+    let router_fns = match (&args.setup, args.preflight) {
+        (Some(setup), false) => quote! {
+            /// Generates an Axum router from the controller methods, panicking if the setup fails
+            fn router() -> axum::Router {
+                <Self as argon_core::controller::Controller>::try_router()
+                    .unwrap_or_else(|err| panic!("setup of `{}` failed: {:?}", #controller_name, err))
+            }
+
+            fn try_router() -> anyhow::Result<axum::Router> {
+                #build_router
+
+                #setup(router)
+            }
+        },
+        (None, false) => quote! {
+            /// Generates an Axum router from the controller methods
+            fn router() -> axum::Router {
+                #build_router
+
+                router
+            }
+        },
+        // The `OPTIONS` routes are kept apart, so `routes!` can mount them outside its layers
+        (setup, true) => {
+            let guarded_router = match setup {
+                Some(setup) => quote! { #setup(router) },
+                None => quote! { Ok(router) },
+            };
+
+            quote! {
+                /// Generates an Axum router from the controller methods, panicking if the setup fails
+                fn router() -> axum::Router {
+                    <Self as argon_core::controller::Controller>::try_router()
+                        .unwrap_or_else(|err| panic!("setup of `{}` failed: {:?}", #controller_name, err))
+                }
+
+                fn try_router() -> anyhow::Result<axum::Router> {
+                    let router = <Self as argon_core::controller::Controller>::try_guarded_router()?;
+
+                    Ok(match <Self as argon_core::controller::Controller>::preflight_router() {
+                        Some(preflight) => router.merge(preflight),
+                        None => router,
+                    })
+                }
+
+                fn try_guarded_router() -> anyhow::Result<axum::Router> {
+                    #build_router
+
+                    #guarded_router
+                }
+
+                fn preflight_router() -> Option<axum::Router> {
+                    Some(axum::Router::new() #(#preflight_registrations)*)
+                }
+            }
+        }
+    };
+
     // Create a name for the generated OpenAPI struct: "MyController" -> "MyControllerApi"
     let api_struct_name = format_ident!("{}Api", struct_name);
     
@@ -143,10 +566,10 @@ pub fn controller(_args: TokenStream, input: TokenStream) -> TokenStream {
     
     for item in &impl_block.items {
         if let ImplItem::Fn(method) = item {
-            if extract_route_attr(&method.attrs).is_some() {
+            let route_count = extract_route_attrs(&method.attrs).len();
+            if route_count > 0 && !has_attr(&method.attrs, "skip_docs") {
                 let fn_name = &method.sig.ident;
-                let wrapper_name = format_ident!("__utoipa_path_{}", fn_name);
-                openapi_path_names.push(wrapper_name);
+                openapi_path_names.extend((0..route_count).map(|index| utoipa_wrapper_name(fn_name, index)));
                 
                 // Extract schema types from utoipa_response attributes
                 let response_types = extract_response_schema_types(&method.attrs);
@@ -166,48 +589,68 @@ pub fn controller(_args: TokenStream, input: TokenStream) -> TokenStream {
         }
     }
 
-    // Generate the router function and OpenAPI struct
-    // Conditionally include components section if we have schemas
-    let openapi_attr = if unique_schemas.is_empty() {
-        quote! {
-            #[derive(utoipa::OpenApi)]
-            #[openapi(
-                paths(
-                    #(#openapi_path_names),*
-                )
-            )]
-        }
-    } else {
-        quote! {
-            #[derive(utoipa::OpenApi)]
-            #[openapi(
-                paths(
-                    #(#openapi_path_names),*
-                ),
-                components(schemas(
-                    #(#unique_schemas),*
-                ))
-            )]
-        }
+    // Generate the OpenAPI struct. Named schemas aren't listed as its components but
+    // registered with argon_core::docs, so a schema shared by controllers is defined once
+    let openapi_attr = quote! {
+        #[derive(utoipa::OpenApi)]
+        #[openapi(
+            paths(
+                #(#openapi_path_names),*
+            )
+        )]
     };
-    
+
+    let registered_schemas: Vec<_> = unique_schemas.iter().filter(|schema_type| !is_std_type(schema_type)).collect();
+
     let expanded = quote! {
         // The original impl block
         #impl_block
 
+        #no_routes_warning
+
         impl argon_core::controller::Controller for #self_ty {
-            /// Generates an Axum router from the controller methods
-            fn router() -> axum::Router {
-                use axum::Router;
+            #router_fns
 
-                let mut router = Router::new();
+            fn routes() -> &'static [(&'static str, &'static str)] {
+                &[#(#route_list),*]
+            }
 
-                #(#route_registrations)*
+            fn operation_paths() -> &'static [(&'static str, &'static str)] {
+                &[#(#operation_list),*]
+            }
 
-                router
+            fn api_doc() -> utoipa::openapi::OpenApi {
+                argon_core::docs::with_schemas_of(<#api_struct_name as utoipa::OpenApi>::openapi(), #controller_key)
             }
         }
 
+        // Registers the response schemas for argon_core::docs::with_schemas()
+        #(
+            argon_core::inventory::submit! {
+                argon_core::docs::SchemaEntry {
+                    controller: #controller_key,
+                    collect: |schemas| {
+                        schemas.push((
+                            <#registered_schemas as utoipa::ToSchema>::name().into_owned(),
+                            <#registered_schemas as utoipa::PartialSchema>::schema(),
+                        ));
+                        <#registered_schemas as utoipa::ToSchema>::schemas(schemas);
+                    },
+                }
+            }
+        )*
+
+        // Registers the routes for argon_core::routing::registered_routes()
+        #(
+            argon_core::inventory::submit! {
+                argon_core::routing::RouteInfo {
+                    method: #route_methods,
+                    path: #route_paths,
+                    controller: #controller_name,
+                }
+            }
+        )*
+
         // Auto-generated utoipa path wrapper functions (must be at module level)
         #(#openapi_path_functions)*
 
@@ -224,7 +667,9 @@ pub fn controller(_args: TokenStream, input: TokenStream) -> TokenStream {
 /// Extract route information from attributes
 /// Looks for route macro attributes like #[get("/path")] or #[argon_macros::get("/path")]
 /// Note: This will only work if the attributes haven't been consumed by attribute macros yet
-fn extract_route_attr(attrs: &[Attribute]) -> Option<(String, String)> {
+fn extract_route_attrs(attrs: &[Attribute]) -> Vec<(String, String)> {
+    let mut routes = Vec::new();
+
     for attr in attrs {
         // Check if this is one of our route macros
         let path_segments: Vec<_> = attr.path().segments.iter().collect();
@@ -241,12 +686,266 @@ fn extract_route_attr(attrs: &[Attribute]) -> Option<(String, String)> {
                 // Extract the path from the tokens - it should be a string literal
                 let tokens = meta.tokens.clone();
                 if let Ok(path_lit) = syn::parse2::<LitStr>(tokens) {
-                    return Some((method, path_lit.value()));
+                    routes.push((method, path_lit.value()));
+                }
+            }
+        }
+    }
+
+    routes
+}
+
+/// Check that a `{*name}` catch-all is named and ends the path, as axum requires
+fn validate_wildcard(path: &str) -> Result<(), String> {
+    let Some(start) = path.find("{*") else {
+        return Ok(());
+    };
+
+    let Some(end) = path[start..].find('}').map(|end| start + end) else {
+        return Err(format!("unclosed catch-all in `{}`", path));
+    };
+
+    if end == start + 2 {
+        return Err(format!("the catch-all of `{}` needs a name, e.g. `{{*rest}}`", path));
+    }
+
+    if end + 1 != path.len() {
+        return Err(format!("the catch-all of `{}` must be its last segment", path));
+    }
+
+    Ok(())
+}
+
+/// Route path as an OpenAPI path template, the `{*name}` catch-all becomes the `{name}` parameter
+fn openapi_path(path: &str) -> String {
+    path.replace("{*", "{")
+}
+
+/// Name of the documentation wrapper of a handler's `index`-th route
+fn utoipa_wrapper_name(fn_name: &syn::Ident, index: usize) -> syn::Ident {
+    if index == 0 {
+        format_ident!("__utoipa_path_{}", fn_name)
+    } else {
+        format_ident!("__utoipa_path_{}_{}", fn_name, index)
+    }
+}
+
+/// Check for a marker attribute like #[skip_docs] or #[argon_macros::skip_docs]
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .any(|attr| attr.path().segments.last().is_some_and(|segment| segment.ident == name))
+}
+
+/// Extract the `Cache-Control` value declared with #[cache_control(...)], if any
+fn extract_cache_control_attr(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    for attr in attrs {
+        let Some(last_segment) = attr.path().segments.last() else {
+            continue;
+        };
+
+        if last_segment.ident == "cache_control" {
+            let parsed = attr.parse_args::<CacheControlArgs>()?;
+
+            if parsed.directives.is_empty() {
+                return Err(syn::Error::new(attr.span(), "cache_control needs at least one directive"));
+            }
+
+            return Ok(Some(parsed.directives.join(", ")));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Extract the content types declared with #[accepts("application/json", ...)], if any
+fn extract_accepts_attr(attrs: &[Attribute]) -> syn::Result<Option<Vec<LitStr>>> {
+    for attr in attrs {
+        let Some(last_segment) = attr.path().segments.last() else {
+            continue;
+        };
+
+        if last_segment.ident == "accepts" {
+            let content_types = attr.parse_args_with(
+                syn::punctuated::Punctuated::<LitStr, syn::Token![,]>::parse_terminated,
+            )?;
+
+            if content_types.is_empty() {
+                return Err(syn::Error::new(attr.span(), "accepts needs at least one content type"));
+            }
+
+            return Ok(Some(content_types.into_iter().collect()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Extract #[deprecated_route] or #[deprecated_route(sunset = "YYYY-MM-DD")]
+/// Returns `Some(sunset)` for deprecated routes, with the sunset as an HTTP date
+fn extract_deprecated_route_attr(attrs: &[Attribute]) -> syn::Result<Option<Option<String>>> {
+    for attr in attrs {
+        let Some(last_segment) = attr.path().segments.last() else {
+            continue;
+        };
+
+        if last_segment.ident != "deprecated_route" {
+            continue;
+        }
+
+        let Meta::List(meta) = &attr.meta else {
+            return Ok(Some(None));
+        };
+
+        let mut sunset = None;
+        meta.parse_nested_meta(|nested| {
+            if nested.path.is_ident("sunset") {
+                let lit: LitStr = nested.value()?.parse()?;
+                let date = sunset_http_date(&lit.value())
+                    .ok_or_else(|| syn::Error::new(lit.span(), "sunset must be a `YYYY-MM-DD` date"))?;
+
+                sunset = Some(date);
+                Ok(())
+            } else {
+                Err(nested.error(format!(
+                    "Unknown argument: {}",
+                    nested.path.get_ident().map(|ident| ident.to_string()).unwrap_or_default()
+                )))
+            }
+        })?;
+
+        return Ok(Some(sunset));
+    }
+
+    Ok(None)
+}
+
+/// Convert `YYYY-MM-DD` to the HTTP date used by the `Sunset` header,
+/// e.g. "2025-12-31" -> "Wed, 31 Dec 2025 00:00:00 GMT"
+fn sunset_http_date(date: &str) -> Option<String> {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+    let mut parts = date.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let month_days = [31, if leap { 29 } else { 28 }, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    if parts.next().is_some() || !(1..=12).contains(&month) || day < 1 || day > month_days[month as usize - 1] {
+        return None;
+    }
+
+    // days since 1970-01-01 (a Thursday), from Howard Hinnant's days_from_civil
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    Some(format!(
+        "{}, {:02} {} {} 00:00:00 GMT",
+        DAYS[days.rem_euclid(7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year
+    ))
+}
+
+/// Arguments of `#[controller(...)]`
+#[derive(Default)]
+struct ControllerArgs {
+    middleware: Vec<syn::Expr>,
+    setup: Option<syn::Expr>,
+    errors: Vec<syn::Ident>,
+    preflight: bool,
+}
+
+impl syn::parse::Parse for ControllerArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = ControllerArgs::default();
+
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+
+            match key.to_string().as_str() {
+                "middleware" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+
+                    let middleware =
+                        syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated(&content)?;
+                    args.middleware.extend(middleware);
+                }
+                "setup" => {
+                    let _eq: syn::Token![=] = input.parse()?;
+                    args.setup = Some(input.parse()?);
                 }
+                "errors" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+
+                    args.errors.extend(parse_standard_errors(&content)?);
+                }
+                "preflight" => args.preflight = true,
+                _ => return Err(syn::Error::new(key.span(), format!("Unknown argument: {}", key))),
+            }
+
+            if !input.is_empty() {
+                let _comma: syn::Token![,] = input.parse()?;
             }
         }
+
+        Ok(args)
+    }
+}
+
+/// Helper struct to parse cache_control attribute arguments
+struct CacheControlArgs {
+    directives: Vec<String>,
+}
+
+impl syn::parse::Parse for CacheControlArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut directives = Vec::new();
+        let mut visibility: Option<syn::Ident> = None;
+
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            let key_str = key.to_string();
+
+            match key_str.as_str() {
+                "public" | "private" => {
+                    if let Some(previous) = &visibility {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            format!("`{}` conflicts with `{}`", key_str, previous),
+                        ));
+                    }
+
+                    directives.push(key_str);
+                    visibility = Some(key);
+                }
+                "no_store" => directives.push("no-store".to_string()),
+                "max_age" | "s_maxage" => {
+                    let _eq: syn::Token![=] = input.parse()?;
+                    let lit: LitInt = input.parse()?;
+                    let seconds = lit.base10_parse::<u32>()?;
+
+                    directives.push(format!("{}={}", key_str.replace('_', "-"), seconds));
+                }
+                _ => return Err(syn::Error::new(key.span(), format!("Unknown argument: {}", key_str))),
+            }
+
+            if !input.is_empty() {
+                let _comma: syn::Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(CacheControlArgs { directives })
     }
-    None
 }
 
 /// Extract all utoipa_response attribute information
@@ -255,9 +954,10 @@ fn extract_route_attr(attrs: &[Attribute]) -> Option<(String, String)> {
 /// - #[utoipa_response(response = Type)] - use Type as IntoResponses (just the type name)
 /// - #[utoipa_response(status = 200, body = Type)] - with explicit status
 /// - #[utoipa_response(status = 200, body = Type, description = "Success")] - with description
+/// - #[utoipa_response(status = 200, body = Binary, content_type = "image/png")] - with content type
 /// 
 /// Example with multiple responses:
-/// ```rust
+/// ```rust,ignore
 /// #[get("/users/{id}")]
 /// #[utoipa_response(status = 200, body = User, description = "User found")]
 /// #[utoipa_response(status = 404, body = Error, description = "User not found")]
@@ -265,8 +965,14 @@ fn extract_route_attr(attrs: &[Attribute]) -> Option<(String, String)> {
 /// async fn get_user() -> Result<User, Error> { ... }
 /// ```
 /// 
+/// A pending `#[openapi_example]` response example is attached to the first matching
+/// response and taken out of `example`.
+///
 /// Returns a vector of response tokens to be inserted into the utoipa::path attribute
-fn extract_utoipa_response_attrs(attrs: &[Attribute]) -> Vec<proc_macro2::TokenStream> {
+fn extract_utoipa_response_attrs(
+    attrs: &[Attribute],
+    example: &mut Option<ResponseExample>,
+) -> Vec<proc_macro2::TokenStream> {
     let mut responses = Vec::new();
     
     for attr in attrs {
@@ -295,9 +1001,28 @@ fn extract_utoipa_response_attrs(attrs: &[Attribute]) -> Vec<proc_macro2::TokenS
                     if let Some(body_type) = parsed.body {
                         let status = parsed.status.unwrap_or(200);
                         let description = parsed.description.as_deref().unwrap_or("Success");
+                        // an example on the response itself wins over #[openapi_example]
+                        let example_tokens = match (parsed.example, parsed.examples) {
+                            (None, None) => match take_response_example(example, status) {
+                                // other content types aren't JSON, their example can't be generated
+                                tokens if tokens.is_empty() && parsed.content_type.is_none() => {
+                                    generated_example(&body_type)
+                                }
+                                tokens => tokens,
+                            },
+                            (example, examples) => {
+                                let example = example.map(|example| quote! { , example = #example });
+                                let examples = examples.map(|examples| quote! { , examples(#examples) });
+
+                                quote! { #example #examples }
+                            }
+                        };
+                        let content_type_tokens = parsed.content_type.map(|content_type| quote! {
+                            , content_type = #content_type
+                        });
                         
                         responses.push(quote! {
-                            (status = #status, description = #description, body = #body_type)
+                            (status = #status, description = #description, body = #body_type #content_type_tokens #example_tokens)
                         });
                         continue;
                     }
@@ -307,8 +1032,13 @@ fn extract_utoipa_response_attrs(attrs: &[Attribute]) -> Vec<proc_macro2::TokenS
                 // This defaults to body type for backward compatibility
                 if let Ok(response_type) = syn::parse2::<Type>(tokens) {
                     // Simple form: just a type, default to status 200 with body
+                    let example_tokens = match take_response_example(example, 200) {
+                        tokens if tokens.is_empty() => generated_example(&response_type),
+                        tokens => tokens,
+                    };
+
                     responses.push(quote! {
-                        (status = 200, description = "Success", body = #response_type)
+                        (status = 200, description = "Success", body = #response_type #example_tokens)
                     });
                 }
             }
@@ -318,6 +1048,242 @@ fn extract_utoipa_response_attrs(attrs: &[Attribute]) -> Vec<proc_macro2::TokenS
     responses
 }
 
+/// Response example waiting to be attached to a response
+struct ResponseExample {
+    status: Option<u16>,
+    value: syn::Expr,
+}
+
+/// Take the example if it belongs to a response with `status`
+/// Without an explicit status the example goes to the first 2xx response
+fn take_response_example(example: &mut Option<ResponseExample>, status: u16) -> proc_macro2::TokenStream {
+    let matches = example.as_ref().is_some_and(|example| match example.status {
+        Some(expected) => expected == status,
+        None => (200..300).contains(&status),
+    });
+
+    match example.take_if(|_| matches) {
+        Some(ResponseExample { value, .. }) => quote! { , example = #value },
+        None => quote! {},
+    }
+}
+
+/// Example built from the schema of `body_type` when the response has none
+fn generated_example(body_type: &Type) -> proc_macro2::TokenStream {
+    quote! { , example = json!(argon_core::docs::example_for::<#body_type>()) }
+}
+
+/// Extract and merge all openapi_example attributes
+/// - #[openapi_example(json!({...}))] - response example
+/// - #[openapi_example(response = json!({...}), status = 201)] - response example for a status
+/// - #[openapi_example(request = json!({...}))] - request body example
+fn extract_openapi_example_attrs(attrs: &[Attribute]) -> syn::Result<OpenapiExampleArgs> {
+    let mut examples = OpenapiExampleArgs::default();
+
+    for attr in attrs {
+        let Some(last_segment) = attr.path().segments.last() else {
+            continue;
+        };
+
+        if last_segment.ident == "openapi_example" {
+            let parsed = attr.parse_args::<OpenapiExampleArgs>()?;
+
+            examples.request = parsed.request.or(examples.request);
+            examples.response = parsed.response.or(examples.response);
+            examples.status = parsed.status.or(examples.status);
+        }
+    }
+
+    Ok(examples)
+}
+
+/// Find `T` in an `extractor<T>` handler argument, e.g. `Json<T>`
+fn body_argument_type(inputs: &syn::punctuated::Punctuated<FnArg, syn::Token![,]>, extractor: &str) -> Option<Type> {
+    inputs.iter().find_map(|input| {
+        let FnArg::Typed(pat_type) = input else {
+            return None;
+        };
+        let Type::Path(type_path) = &*pat_type.ty else {
+            return None;
+        };
+        let segment = type_path.path.segments.last()?;
+        if segment.ident != extractor {
+            return None;
+        }
+        let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+            return None;
+        };
+
+        args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        })
+    })
+}
+
+/// Whether a handler argument is the `Valid<T>` extractor
+fn has_valid_argument(inputs: &syn::punctuated::Punctuated<FnArg, syn::Token![,]>) -> bool {
+    inputs.iter().any(|input| {
+        let FnArg::Typed(pat_type) = input else {
+            return false;
+        };
+        let Type::Path(type_path) = &*pat_type.ty else {
+            return false;
+        };
+
+        type_path.path.segments.last().is_some_and(|segment| segment.ident == "Valid")
+    })
+}
+
+/// `params(...)` entries of the handler's header extractors
+///
+/// `RequiredHeader<H, T>`, `IfMatch` and `IfNoneMatch` implement `IntoParams`, an
+/// `Option<IfMatch>` is documented as an optional header instead.
+fn header_params(inputs: &syn::punctuated::Punctuated<FnArg, syn::Token![,]>) -> Vec<proc_macro2::TokenStream> {
+    inputs
+        .iter()
+        .filter_map(|input| {
+            let FnArg::Typed(pat_type) = input else {
+                return None;
+            };
+            let segment = last_segment(&pat_type.ty)?;
+
+            if matches!(segment.ident.to_string().as_str(), "RequiredHeader" | "IfMatch" | "IfNoneMatch") {
+                let ty = &pat_type.ty;
+                return Some(quote! { #ty });
+            }
+
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return None;
+            };
+            let Some(syn::GenericArgument::Type(inner)) = args.args.first() else {
+                return None;
+            };
+
+            let name = match last_segment(inner)?.ident.to_string().as_str() {
+                "IfMatch" => "If-Match",
+                "IfNoneMatch" => "If-None-Match",
+                _ => return None,
+            };
+
+            (segment.ident == "Option").then(|| quote! { (#name = Option<String>, Header) })
+        })
+        .collect()
+}
+
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+
+    type_path.path.segments.last()
+}
+
+/// Status and description of the errors named in `#[utoipa_errors(...)]`
+fn standard_error(name: &str) -> Option<(u16, &'static str)> {
+    match name {
+        "bad_request" => Some((400, "Bad request")),
+        "unauthorized" => Some((401, "Unauthorized")),
+        "forbidden" => Some((403, "Forbidden")),
+        "not_found" => Some((404, "Not found")),
+        "conflict" => Some((409, "Conflict")),
+        "internal" => Some((500, "Internal server error")),
+        "unavailable" => Some((503, "Service unavailable")),
+        _ => None,
+    }
+}
+
+/// Comma separated error names, e.g. `unauthorized, not_found`
+fn parse_standard_errors(input: syn::parse::ParseStream) -> syn::Result<Vec<syn::Ident>> {
+    let names = syn::punctuated::Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated(input)?;
+
+    for name in &names {
+        if standard_error(&name.to_string()).is_none() {
+            return Err(syn::Error::new(name.span(), format!("Unknown argument: {}", name)));
+        }
+    }
+
+    Ok(names.into_iter().collect())
+}
+
+/// Errors of the handler's #[utoipa_errors(...)] attributes
+fn extract_utoipa_errors_attrs(attrs: &[Attribute]) -> syn::Result<Vec<syn::Ident>> {
+    let mut errors = Vec::new();
+
+    for attr in attrs {
+        if attr.path().segments.last().is_some_and(|segment| segment.ident == "utoipa_errors") {
+            errors.extend(attr.parse_args_with(parse_standard_errors)?);
+        }
+    }
+
+    Ok(errors)
+}
+
+/// Whether a #[utoipa_response(status = ...)] declares `status`
+fn declares_status(attrs: &[Attribute], status: u16) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().segments.last().is_some_and(|segment| segment.ident == "utoipa_response"))
+        .filter_map(|attr| attr.parse_args::<UtoipaResponseArgs>().ok())
+        .any(|args| args.status == Some(status))
+}
+
+/// Helper struct to parse openapi_example attribute arguments
+#[derive(Default)]
+struct OpenapiExampleArgs {
+    request: Option<syn::Expr>,
+    response: Option<syn::Expr>,
+    status: Option<u16>,
+}
+
+impl syn::parse::Parse for OpenapiExampleArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = OpenapiExampleArgs::default();
+
+        // Bare expression is the response example, e.g. #[openapi_example(json!({...}))]
+        if !(input.peek(syn::Ident) && input.peek2(syn::Token![=])) {
+            args.response = Some(input.parse()?);
+            return Ok(args);
+        }
+
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            let _eq: syn::Token![=] = input.parse()?;
+
+            if key == "request" {
+                args.request = Some(input.parse()?);
+            } else if key == "response" {
+                args.response = Some(input.parse()?);
+            } else if key == "status" {
+                let lit: LitInt = input.parse()?;
+                args.status = Some(lit.base10_parse::<u16>()?);
+            } else {
+                return Err(syn::Error::new(key.span(), format!("Unknown argument: {}", key)));
+            }
+
+            if !input.is_empty() {
+                let _comma: syn::Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+/// Primitives and std containers, documented inline rather than as named components
+fn is_std_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path.path.segments.last().is_some_and(|segment| {
+            let name = segment.ident.to_string();
+
+            name.starts_with(|c: char| c.is_lowercase())
+                || matches!(name.as_str(), "String" | "Vec" | "Option" | "Box" | "HashMap" | "BTreeMap")
+        }),
+        Type::Reference(_) | Type::Slice(_) | Type::Array(_) | Type::Tuple(_) => true,
+        _ => false,
+    }
+}
+
 /// Extract schema types from utoipa_response attributes
 /// Returns a vector of types that should be included in components(schemas(...))
 fn extract_response_schema_types(attrs: &[Attribute]) -> Vec<Type> {
@@ -366,28 +1332,19 @@ fn extract_response_schema_types(attrs: &[Attribute]) -> Vec<Type> {
 /// Recursively extract types from generic type parameters
 /// For example, CoreResponse<T, N, U, I> would extract T, N, U, I
 fn extract_types_from_generic(ty: &Type, schema_types: &mut Vec<Type>) {
-    match ty {
-        Type::Path(type_path) => {
-            if let Some(path_segment) = type_path.path.segments.last() {
-                match &path_segment.arguments {
-                    syn::PathArguments::AngleBracketed(args) => {
-                        for arg in &args.args {
-                            match arg {
-                                syn::GenericArgument::Type(ty) => {
-                                    // Recursively extract from nested generics
-                                    extract_types_from_generic(ty, schema_types);
-                                    // Add the type itself
-                                    schema_types.push(ty.clone());
-                                }
-                                _ => {}
-                            }
-                        }
+    if let Type::Path(type_path) = ty {
+        if let Some(path_segment) = type_path.path.segments.last() {
+            if let syn::PathArguments::AngleBracketed(args) = &path_segment.arguments {
+                for arg in &args.args {
+                    if let syn::GenericArgument::Type(ty) = arg {
+                        // Recursively extract from nested generics
+                        extract_types_from_generic(ty, schema_types);
+                        // Add the type itself
+                        schema_types.push(ty.clone());
                     }
-                    _ => {}
                 }
             }
         }
-        _ => {}
     }
 }
 
@@ -398,6 +1355,10 @@ struct UtoipaResponseArgs {
     body: Option<Type>,
     response: Option<Type>,
     description: Option<String>,
+    content_type: Option<String>,
+    example: Option<syn::Expr>,
+    /// Content of `examples(...)`, passed through to utoipa as is
+    examples: Option<proc_macro2::TokenStream>,
 }
 
 impl syn::parse::Parse for UtoipaResponseArgs {
@@ -406,6 +1367,9 @@ impl syn::parse::Parse for UtoipaResponseArgs {
         let mut body = None;
         let mut response = None;
         let mut description = None;
+        let mut content_type = None;
+        let mut example = None;
+        let mut examples = None;
         
         // Parse comma-separated key-value pairs
         while !input.is_empty() {
@@ -426,6 +1390,17 @@ impl syn::parse::Parse for UtoipaResponseArgs {
                 let _eq: syn::Token![=] = input.parse()?;
                 let lit: LitStr = input.parse()?;
                 description = Some(lit.value());
+            } else if key_str == "content_type" {
+                let _eq: syn::Token![=] = input.parse()?;
+                let lit: LitStr = input.parse()?;
+                content_type = Some(lit.value());
+            } else if key_str == "example" {
+                let _eq: syn::Token![=] = input.parse()?;
+                example = Some(input.parse()?);
+            } else if key_str == "examples" {
+                let content;
+                syn::parenthesized!(content in input);
+                examples = Some(content.parse()?);
             } else {
                 return Err(syn::Error::new(key.span(), format!("Unknown argument: {}", key_str)));
             }
@@ -440,12 +1415,20 @@ impl syn::parse::Parse for UtoipaResponseArgs {
         if body.is_some() && response.is_some() {
             return Err(input.error("Cannot specify both 'body' and 'response'. Use 'body' for simple types or 'response' for IntoResponses types."));
         }
+
+        // IntoResponses types document their own examples
+        if body.is_none() && (example.is_some() || examples.is_some()) {
+            return Err(input.error("'example' and 'examples' need a 'body'."));
+        }
         
         Ok(UtoipaResponseArgs {
             status,
             body,
             response,
             description,
+            content_type,
+            example,
+            examples,
         })
     }
 }
@@ -485,7 +1468,7 @@ pub fn patch(args: TokenStream, input: TokenStream) -> TokenStream {
 /// You can chain multiple `#[utoipa_response]` attributes to specify multiple status codes.
 /// 
 /// Usage:
-/// ```rust
+/// ```rust,ignore
 /// // Simple type (defaults to status 200)
 /// #[get("/users")]
 /// #[utoipa_response(User)]
@@ -503,6 +1486,19 @@ pub fn patch(args: TokenStream, input: TokenStream) -> TokenStream {
 /// #[utoipa_response(status = 500, body = Error, description = "Internal server error")]
 /// async fn get_user() -> Result<User, Error> { ... }
 /// 
+/// // Binary body with its content type
+/// #[get("/users/{id}/avatar")]
+/// #[utoipa_response(status = 200, body = Binary, content_type = "image/png")]
+/// async fn get_avatar() -> Binary { ... }
+/// 
+/// // Sample payloads shown by Swagger UI
+/// #[get("/users/{id}")]
+/// #[utoipa_response(status = 200, body = User, example = json!({"id": 1, "name": "Ada"}))]
+/// #[utoipa_response(status = 404, body = Error, examples(
+///     ("Deleted" = (summary = "The user was deleted", value = json!({"message": "Not Found"}))),
+/// ))]
+/// async fn get_user() -> Result<User, Error> { ... }
+/// 
 /// // IntoResponses type (like UserResponse<T, N, U, I>)
 /// #[get("/users/{id}")]
 /// #[utoipa_response(response = UserResponse<User, NotFound, Unauthorized, InternalError>)]
@@ -514,11 +1510,161 @@ pub fn patch(args: TokenStream, input: TokenStream) -> TokenStream {
 /// #[utoipa_response(status = 503, body = Error, description = "Service unavailable")]
 /// async fn get_user() -> Result<UserResponse<...>, Error> { ... }
 /// ```
-/// 
-/// This attribute is consumed by the `#[controller]` macro to generate
-/// OpenAPI documentation. It's a pass-through macro that doesn't modify the function.
+/// 
+/// This attribute is consumed by the `#[controller]` macro to generate
+/// OpenAPI documentation. It's a pass-through macro that doesn't modify the function.
+#[proc_macro_attribute]
+pub fn utoipa_response(_args: TokenStream, input: TokenStream) -> TokenStream {
+    // Pass through - the controller macro will read this attribute
+    input
+}
+
+/// Attribute macro documenting standard error responses with an `ErrorResponse` body,
+/// the shape of `BaseErrorResponse::<()>`
+///
+/// Usage:
+/// ```rust,ignore
+/// #[get("/users/{id}")]
+/// #[utoipa_response(status = 200, body = User)]
+/// #[utoipa_errors(unauthorized, not_found, internal)]
+/// async fn get_user() -> Result<Json<User>, HandlerError> { ... }
+/// ```
+///
+/// Known errors are `bad_request` (400), `unauthorized` (401), `forbidden` (403),
+/// `not_found` (404), `conflict` (409), `internal` (500) and `unavailable` (503).
+/// A status also declared with `#[utoipa_response]` keeps that declaration.
+///
+/// Errors every handler of a controller can answer are listed once on the controller:
+/// ```rust,ignore
+/// #[controller(errors(unauthorized, internal))]
+/// impl UserController { ... }
+/// ```
+///
+/// This attribute is consumed by the `#[controller]` macro to generate
+/// OpenAPI documentation. It's a pass-through macro that doesn't modify the function.
+#[proc_macro_attribute]
+pub fn utoipa_errors(_args: TokenStream, input: TokenStream) -> TokenStream {
+    // Pass through - the controller macro will read this attribute
+    input
+}
+
+/// Attribute macro for attaching examples to the generated OpenAPI operation
+///
+/// Usage:
+/// ```rust,ignore
+/// // Response example, attached to the first 2xx `#[utoipa_response]`
+/// #[get("/users/{id}")]
+/// #[utoipa_response(status = 200, body = User)]
+/// #[openapi_example(json!({ "id": 1, "name": "John" }))]
+/// async fn get_user() -> Json<User> { ... }
+///
+/// // Request and response examples, with the response picked by status
+/// #[post("/users")]
+/// #[utoipa_response(status = 201, body = User, description = "User created")]
+/// #[openapi_example(request = json!({ "name": "John" }))]
+/// #[openapi_example(response = json!({ "id": 1, "name": "John" }), status = 201)]
+/// async fn create_user(Json(user): Json<NewUser>) -> Json<User> { ... }
+/// ```
+///
+/// A request example needs a `Json<T>` argument, which becomes the documented request body.
+/// This attribute is consumed by the `#[controller]` macro. It's a pass-through macro.
+#[proc_macro_attribute]
+pub fn openapi_example(_args: TokenStream, input: TokenStream) -> TokenStream {
+    // Pass through - the controller macro will read this attribute
+    input
+}
+
+/// Attribute macro for handlers whose response must reach the client as is
+///
+/// The response is tagged with `argon_core::response::RawResponse`, which the
+/// `envelope` middleware and any other transforming layer should pass through.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[get("/files/{id}")]
+/// #[raw_response]
+/// async fn download(Path(id): Path<i32>) -> FileDownload { ... }
+/// ```
+///
+/// This attribute is consumed by the `#[controller]` macro. It's a pass-through macro
+/// that doesn't modify the function.
+#[proc_macro_attribute]
+pub fn raw_response(_args: TokenStream, input: TokenStream) -> TokenStream {
+    // Pass through - the controller macro will read this attribute
+    input
+}
+
+/// Attribute macro for keeping a handler out of the generated OpenAPI documentation
+///
+/// The route is still registered by `#[controller]`, but no `#[utoipa::path]` wrapper
+/// is generated and its response schemas aren't collected.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[post("/webhooks/payment")]
+/// #[skip_docs]
+/// async fn payment_webhook(body: String) -> StatusCode { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn skip_docs(_args: TokenStream, input: TokenStream) -> TokenStream {
+    // Pass through - the controller macro will read this attribute
+    input
+}
+
+/// Attribute macro for setting `Cache-Control` on successful responses of a handler
+///
+/// Supports `public`, `private`, `no_store`, `max_age = <seconds>` and `s_maxage = <seconds>`.
+/// The header is only added to `2xx` responses, so errors are never cached.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[get("/countries")]
+/// #[cache_control(public, max_age = 60, s_maxage = 300)]
+/// async fn countries() -> Json<Vec<Country>> { ... }
+/// ```
+///
+/// This attribute is consumed by the `#[controller]` macro. It's a pass-through macro.
+#[proc_macro_attribute]
+pub fn cache_control(_args: TokenStream, input: TokenStream) -> TokenStream {
+    // Pass through - the controller macro will read this attribute
+    input
+}
+
+/// Attribute macro restricting the body content types of a route
+///
+/// `POST`, `PUT` and `PATCH` requests with another `Content-Type` are answered with
+/// `415 Unsupported Media Type` listing the accepted types, see
+/// `argon_core::limits::require_content_type`.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[patch("/users/{id}")]
+/// #[accepts("application/json", "application/merge-patch+json")]
+/// async fn update_user(Path(id): Path<i32>, Json(body): Json<UpdateUser>) -> ... { ... }
+/// ```
+///
+/// This attribute is consumed by the `#[controller]` macro. It's a pass-through macro.
 #[proc_macro_attribute]
-pub fn utoipa_response(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn accepts(_args: TokenStream, input: TokenStream) -> TokenStream {
+    // Pass through - the controller macro will read this attribute
+    input
+}
+
+/// Attribute macro for deprecating a route
+///
+/// The operation is marked deprecated in the OpenAPI spec and every response gets a
+/// `Deprecation: true` header, plus a `Sunset` header when a sunset date is given.
+///
+/// Usage:
+/// ```rust,ignore
+/// #[get("/users/legacy")]
+/// #[deprecated_route(sunset = "2025-12-31")]
+/// async fn legacy_users() -> Json<Vec<User>> { ... }
+/// ```
+///
+/// This attribute is consumed by the `#[controller]` macro. It's a pass-through macro.
+#[proc_macro_attribute]
+pub fn deprecated_route(_args: TokenStream, input: TokenStream) -> TokenStream {
     // Pass through - the controller macro will read this attribute
     input
 }
@@ -542,7 +1688,7 @@ fn route_attr_macro(_method: &str, _args: TokenStream, input: TokenStream) -> To
 /// Macro that generates a response enum with IntoResponse implementation
 ///
 /// Usage:
-/// ```rust
+/// ```rust,ignore
 /// // Simple form (generates enum named "Response")
 /// response! {
 ///     StatusCode::OK = User,
@@ -561,10 +1707,50 @@ fn route_attr_macro(_method: &str, _args: TokenStream, input: TokenStream) -> To
 /// You can optionally provide a custom description as a string literal after the type.
 /// If no description is provided, one will be auto-generated from the status code name.
 ///
+/// Variants answer JSON by default. An entry can end with `content_type = "..."` to send
+/// its data as the raw body with that content type, or with `redirect` (3xx statuses only)
+/// to send it as the `Location` header without a body:
+/// ```rust,ignore
+/// response! {
+///     ReportResponse {
+///         StatusCode::OK = String, "the report", content_type = "text/csv",
+///         StatusCode::SEE_OTHER = String, "the report moved", redirect
+///     }
+/// }
+///
+/// ReportResponse::see_other(format!("/reports/{}", id))
+/// ```
+///
+/// A constructor is generated per status, named after the status constant
+/// (`BasicResponse::ok(..)`, `BasicResponse::not_found(..)`). Map domain errors once
+/// with a `From` impl built on them, then use `?` in handlers:
+/// ```rust,ignore
+/// impl From<UserError> for BasicResponse {
+///     fn from(err: UserError) -> Self {
+///         match err {
+///             UserError::Missing(id) => Self::not_found(NotFoundError::new(id)),
+///         }
+///     }
+/// }
+///
+/// async fn get_user(Path(id): Path<i32>) -> Result<BasicResponse, BasicResponse> {
+///     let user = users::find(id).await?;
+///
+///     Ok(BasicResponse::ok(user.name))
+/// }
+/// ```
+///
 /// This generates an enum similar to:
-/// ```rust
-/// #[derive(utoipa::IntoResponses)]
+/// ```rust,ignore
 /// pub enum BasicResponse {
+///     Ok(String),
+///     NotFound(NotFoundError),
+///     ...
+/// }
+///
+/// // documented through a hidden twin, so a redirect can be documented without a body
+/// #[derive(utoipa::IntoResponses)]
+/// enum __BasicResponseDocs {
 ///     #[response(status = 200, description = "user found")]
 ///     Ok(String),
 ///     #[response(status = 404, description = "user not found")]
@@ -572,6 +1758,11 @@ fn route_attr_macro(_method: &str, _args: TokenStream, input: TokenStream) -> To
 ///     ...
 /// }
 /// 
+/// impl BasicResponse {
+///     pub fn ok(data: String) -> Self { Self::Ok(data) }
+///     pub fn not_found(data: NotFoundError) -> Self { Self::NotFound(data) }
+/// }
+///
 /// impl axum::response::IntoResponse for BasicResponse
 /// where
 ///     String: serde::Serialize + utoipa::ToSchema,
@@ -607,7 +1798,28 @@ pub fn response(input: TokenStream) -> TokenStream {
     for entry in entries {
         // Extract status code constant (e.g., StatusCode::OK -> OK)
         let status_code_constant = extract_status_code_constant(&entry.status_code);
+
+        // Two entries for one status would make the variant and the documented response ambiguous
+        if status_code_constants.contains(&status_code_constant) {
+            return syn::Error::new(
+                entry.status_code.span(),
+                format!("duplicate status code `{}` in response!", status_code_constant)
+            )
+            .to_compile_error()
+            .into();
+        }
         status_code_constants.push(status_code_constant.clone());
+
+        if matches!(entry.body, ResponseBody::Redirect)
+            && !(300..400).contains(&status_code_constant_to_number(&status_code_constant))
+        {
+            return syn::Error::new(
+                entry.status_code.span(),
+                format!("`redirect` needs a 3xx status, not `{}`", status_code_constant)
+            )
+            .to_compile_error()
+            .into();
+        }
         
         // Generate variant name from status code (e.g., OK -> Ok, NOT_FOUND -> NotFound)
         let variant_name = status_code_to_variant_name(&status_code_constant);
@@ -628,17 +1840,39 @@ pub fn response(input: TokenStream) -> TokenStream {
     }
     
     
-    // Generate enum variants with utoipa attributes using actual types
     let enum_variants: Vec<_> = variant_idents
+        .iter()
+        .zip(types.iter())
+        .map(|(variant, ty)| quote! { #variant(#ty), })
+        .collect();
+
+    // Generate the documented variants with utoipa attributes using actual types,
+    // a redirect is documented by its `Location` header and has no body
+    let doc_variants: Vec<_> = variant_idents
         .iter()
         .zip(types.iter())
         .zip(descriptions.iter())
         .zip(status_code_constants.iter())
-        .map(|(((variant, ty), desc), status_const)| {
+        .zip(entries.iter())
+        .map(|((((variant, ty), desc), status_const), entry)| {
             let status_code_num = status_code_constant_to_number(status_const);
-            quote! {
-                #[response(status = #status_code_num, description = #desc)]
-                #variant(#ty),
+            match &entry.body {
+                ResponseBody::Json => quote! {
+                    #[response(status = #status_code_num, description = #desc)]
+                    #variant(#ty),
+                },
+                ResponseBody::ContentType(content_type) => quote! {
+                    #[response(status = #status_code_num, description = #desc, content_type = #content_type)]
+                    #variant(#ty),
+                },
+                ResponseBody::Redirect => quote! {
+                    #[response(
+                        status = #status_code_num,
+                        description = #desc,
+                        headers(("Location" = String, description = "Where the resource is found"))
+                    )]
+                    #variant,
+                },
             }
         })
         .collect();
@@ -659,25 +1893,78 @@ pub fn response(input: TokenStream) -> TokenStream {
     let match_arms: Vec<_> = variant_idents
         .iter()
         .zip(status_code_constants_for_match.iter())
-        .map(|(variant, status_const)| {
-            quote! {
+        .zip(entries.iter())
+        .map(|((variant, status_const), entry)| match &entry.body {
+            ResponseBody::Json => quote! {
                 Self::#variant(data) => (axum::http::StatusCode::#status_const, axum::Json(data)).into_response(),
-            }
+            },
+            ResponseBody::ContentType(content_type) => quote! {
+                Self::#variant(data) => (
+                    axum::http::StatusCode::#status_const,
+                    [(
+                        axum::http::header::CONTENT_TYPE,
+                        axum::http::HeaderValue::from_static(#content_type),
+                    )],
+                    axum::body::Body::from(data),
+                )
+                    .into_response(),
+            },
+            ResponseBody::Redirect => quote! {
+                Self::#variant(data) => (
+                    axum::http::StatusCode::#status_const,
+                    [(axum::http::header::LOCATION, data.to_string())],
+                )
+                    .into_response(),
+            },
         })
         .collect();
     
+    // Generate a constructor per status, e.g. NOT_FOUND -> not_found(data)
+    let constructors: Vec<_> = variant_idents
+        .iter()
+        .zip(types.iter())
+        .zip(status_code_constants.iter())
+        .map(|((variant, ty), status_const)| {
+            let constructor = format_ident!("{}", status_const.to_lowercase());
+            quote! {
+                pub fn #constructor(data: #ty) -> Self {
+                    Self::#variant(data)
+                }
+            }
+        })
+        .collect();
+
     // Use custom enum name if provided, otherwise default to "Response"
     let enum_name = input.enum_name
         .as_ref()
         .map(|ident| format_ident!("{}", ident))
         .unwrap_or_else(|| format_ident!("Response"));
     
+    let doc_enum_name = format_ident!("__{}Docs", enum_name);
+
     let expanded = quote! {
-        #[derive(utoipa::IntoResponses)]
         pub enum #enum_name {
             #(#enum_variants)*
         }
+
+        // Only documents the responses, never constructed
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        #[derive(utoipa::IntoResponses)]
+        enum #doc_enum_name {
+            #(#doc_variants)*
+        }
+
+        impl utoipa::IntoResponses for #enum_name {
+            fn responses() -> std::collections::BTreeMap<String, utoipa::openapi::RefOr<utoipa::openapi::response::Response>> {
+                <#doc_enum_name as utoipa::IntoResponses>::responses()
+            }
+        }
         
+        impl #enum_name {
+            #(#constructors)*
+        }
+
         impl axum::response::IntoResponse for #enum_name {
             fn into_response(self) -> axum::response::Response {
                 match self {
@@ -700,6 +1987,65 @@ struct ResponseEntry {
     status_code: syn::Path,
     response_type: Type,
     description: Option<LitStr>,
+    body: ResponseBody,
+}
+
+/// How a `response!` variant writes its data
+enum ResponseBody {
+    /// `axum::Json`, the default
+    Json,
+    /// The data as the raw body, e.g. `content_type = "text/plain"`
+    ContentType(LitStr),
+    /// The data as the `Location` header and no body, e.g. `redirect`
+    Redirect,
+}
+
+impl ResponseBody {
+    /// Parse the entry modifiers following the type and description
+    fn parse_modifiers(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut body = ResponseBody::Json;
+
+        while input.peek(syn::Ident) && !input.peek2(syn::Token![::]) {
+            let fork = input.fork();
+            let name: syn::Ident = fork.parse()?;
+
+            // a single segment status path, e.g. `OK = User`, starts the next entry
+            if name != "redirect" && name != "content_type" {
+                break;
+            }
+            input.parse::<syn::Ident>()?;
+
+            if !matches!(body, ResponseBody::Json) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "`redirect` and `content_type` cannot be combined or repeated",
+                ));
+            }
+
+            body = if name == "redirect" {
+                ResponseBody::Redirect
+            } else {
+                input.parse::<syn::Token![=]>()?;
+                let content_type: LitStr = input.parse()?;
+
+                let value = content_type.value();
+                if value.is_empty() || !value.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+                    return Err(syn::Error::new(
+                        content_type.span(),
+                        "`content_type` must be a valid header value",
+                    ));
+                }
+
+                ResponseBody::ContentType(content_type)
+            };
+
+            if input.peek(syn::Token![,]) {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        Ok(body)
+    }
 }
 
 impl syn::parse::Parse for ResponseMacroInput {
@@ -748,18 +2094,20 @@ impl syn::parse::Parse for ResponseMacroInput {
             
             let has_description = description.is_some();
             
+            // If we parsed a description, check for another comma if there are more entries
+            if has_description && !parse_stream.is_empty() && parse_stream.peek(syn::Token![,]) {
+                let _comma: syn::Token![,] = parse_stream.parse()?;
+            }
+
+            // Optionally parse `redirect` or `content_type = "..."`
+            let body = ResponseBody::parse_modifiers(parse_stream)?;
+
             entries.push(ResponseEntry {
                 status_code,
                 response_type,
                 description,
+                body,
             });
-            
-            // If we parsed a description, check for another comma if there are more entries
-            if has_description && !parse_stream.is_empty() {
-                if parse_stream.peek(syn::Token![,]) {
-                    let _comma: syn::Token![,] = parse_stream.parse()?;
-                }
-            }
         }
         
         Ok(ResponseMacroInput { 
@@ -816,7 +2164,13 @@ fn status_code_constant_to_number(status_code: &str) -> u16 {
     match status_code {
         "OK" => 200,
         "CREATED" => 201,
+        "ACCEPTED" => 202,
         "NO_CONTENT" => 204,
+        "MOVED_PERMANENTLY" => 301,
+        "FOUND" => 302,
+        "SEE_OTHER" => 303,
+        "TEMPORARY_REDIRECT" => 307,
+        "PERMANENT_REDIRECT" => 308,
         "BAD_REQUEST" => 400,
         "UNAUTHORIZED" => 401,
         "FORBIDDEN" => 403,
@@ -835,6 +2189,135 @@ fn status_code_constant_to_number(status_code: &str) -> u16 {
     }
 }
 
+/// Macro that builds the app router from controllers grouped by prefix, each group
+/// wrapped by its own layers
+///
+/// Usage:
+/// ```rust,ignore
+/// let auth = axum::middleware::from_fn(auth_middleware::<BasicAuthenticator, BasicUser>);
+///
+/// let router: Router = routes! {
+///     "/api" => [UserController, PostController] with [auth],
+///     "/admin" => [AdminController] with [auth, admin_layer()],
+///     "/" => [HealthController]
+/// }?;
+/// ```
+///
+/// Every controller is mounted with `Controller::try_mount_guarded`, so its docs are recorded under
+/// the prefix and a failing setup makes the whole expression an `Err`. The expansion is an
+/// `anyhow::Result<axum::Router>`.
+///
+/// Layers only wrap their group. The first listed runs first, and each is cloned, so the
+/// same layer can be reused across groups. The `OPTIONS` routes of `#[controller(preflight)]`
+/// are mounted outside the group layers, so an auth layer doesn't answer preflights with `401`.
+#[proc_macro]
+pub fn routes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as RoutesMacroInput);
+
+    let groups: Vec<_> = input
+        .groups
+        .iter()
+        .map(|group| {
+            let prefix = &group.prefix;
+            let controllers = &group.controllers;
+            // applied in reverse so the first listed layer is the outermost
+            let layers = group.layers.iter().rev();
+
+            quote! {
+                let group = axum::Router::new();
+                #(
+                    let group = <#controllers as argon_core::controller::Controller>::try_mount_guarded(group, #prefix)?;
+                )*
+                let router = router.merge(group #(.layer(::core::clone::Clone::clone(&(#layers))))*);
+                #(
+                    let router = <#controllers as argon_core::controller::Controller>::mount_preflight(router, #prefix);
+                )*
+            }
+        })
+        .collect();
+
+    let expanded = quote! {
+        (|| -> anyhow::Result<axum::Router> {
+            let router = axum::Router::new();
+            #(#groups)*
+
+            Ok(router)
+        })()
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Parse the input for the routes! macro
+struct RoutesMacroInput {
+    groups: Vec<RouteGroup>,
+}
+
+struct RouteGroup {
+    prefix: LitStr,
+    controllers: Vec<syn::Path>,
+    layers: Vec<syn::Expr>,
+}
+
+impl syn::parse::Parse for RoutesMacroInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut groups = Vec::new();
+
+        while !input.is_empty() {
+            let prefix: LitStr = input.parse()?;
+            if !prefix.value().starts_with('/') {
+                return Err(syn::Error::new(prefix.span(), "route prefix must start with `/`"));
+            }
+
+            input.parse::<syn::Token![=>]>()?;
+
+            let content;
+            let brackets = syn::bracketed!(content in input);
+            let controllers: Vec<syn::Path> = content
+                .parse_terminated(syn::Path::parse, syn::Token![,])?
+                .into_iter()
+                .collect();
+            if controllers.is_empty() {
+                return Err(syn::Error::new(brackets.span.join(), "expected at least one controller"));
+            }
+
+            let mut layers = Vec::new();
+            if input.peek(syn::Ident) {
+                let with: syn::Ident = input.parse()?;
+                if with != "with" {
+                    return Err(syn::Error::new(with.span(), format!("Unknown argument: {}", with)));
+                }
+
+                let content;
+                syn::bracketed!(content in input);
+                layers = content
+                    .parse_terminated(syn::Expr::parse, syn::Token![,])?
+                    .into_iter()
+                    .collect();
+            }
+
+            groups.push(RouteGroup {
+                prefix,
+                controllers,
+                layers,
+            });
+
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        if groups.is_empty() {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "routes! macro requires at least one prefix and controller list"
+            ));
+        }
+
+        Ok(RoutesMacroInput { groups })
+    }
+}
+
 /// Derive macro for configuration structs
 /// 
 /// This macro generates:
@@ -843,7 +2326,7 @@ fn status_code_constant_to_number(status_code: &str) -> u16 {
 /// - Individual getter methods for each field
 /// 
 /// Usage:
-/// ```rust
+/// ```rust,ignore
 /// use argon_core::config::ConfigBuilder;
 /// use argon_macros::Config;
 /// 
@@ -947,3 +2430,215 @@ pub fn derive_config(input: TokenStream) -> TokenStream {
     
     TokenStream::from(expanded)
 }
+
+/// Derive macro implementing `argon_core::response::FromModel` for a response DTO
+///
+/// Every field is copied from the model field with the same name (converted with `.into()`).
+/// Fields that aren't declared on the DTO, like `password`, are simply dropped.
+///
+/// Field options:
+/// - `#[from_model(rename = "source")]` - read from a model field with another name
+/// - `#[from_model(lang = "en")]` - collapse a `MultilangField` into a `String` for that language
+///
+/// Usage:
+/// ```rust,ignore
+/// #[derive(serde::Serialize, utoipa::ToSchema, FromModel)]
+/// #[from_model(crate::app::model::post::Model)]
+/// pub struct PostResponse {
+///     pub id: i32,
+///     #[from_model(lang = "en")]
+///     pub title: String,
+///     #[from_model(rename = "created_at")]
+///     pub published_at: DateTime,
+/// }
+/// ```
+#[proc_macro_derive(FromModel, attributes(from_model))]
+pub fn derive_from_model(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let struct_name = &input.ident;
+
+    let model_type = match input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("from_model"))
+        .map(|attr| attr.parse_args::<Type>())
+    {
+        Some(Ok(model_type)) => model_type,
+        Some(Err(err)) => return err.to_compile_error().into(),
+        None => {
+            return syn::Error::new(
+                input.span(),
+                "FromModel derive requires a `#[from_model(path::to::Model)]` attribute"
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return syn::Error::new(
+                input.span(),
+                "FromModel derive macro only supports structs with named fields"
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let mut field_inits = Vec::new();
+
+    for field in fields {
+        let field_name = field.ident.as_ref().unwrap();
+
+        let mut options = FromModelFieldArgs::default();
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("from_model")) {
+            match attr.parse_args::<FromModelFieldArgs>() {
+                Ok(parsed) => {
+                    options.rename = parsed.rename.or(options.rename);
+                    options.lang = parsed.lang.or(options.lang);
+                }
+                Err(err) => return err.to_compile_error().into(),
+            }
+        }
+
+        let source = options
+            .rename
+            .map(|rename| format_ident!("{}", rename))
+            .unwrap_or_else(|| field_name.clone());
+
+        field_inits.push(match options.lang {
+            Some(lang) => quote! {
+                #field_name: model.#source.localized(#lang).unwrap_or_default().into(),
+            },
+            None => quote! {
+                #field_name: model.#source.into(),
+            },
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics argon_core::response::FromModel<#model_type> for #struct_name #ty_generics #where_clause {
+            fn from_model(model: #model_type) -> Self {
+                Self {
+                    #(#field_inits)*
+                }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Helper struct to parse field level `#[from_model(...)]` arguments
+#[derive(Default)]
+struct FromModelFieldArgs {
+    rename: Option<String>,
+    lang: Option<String>,
+}
+
+impl syn::parse::Parse for FromModelFieldArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = FromModelFieldArgs::default();
+
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            let _eq: syn::Token![=] = input.parse()?;
+            let lit: LitStr = input.parse()?;
+
+            if key == "rename" {
+                args.rename = Some(lit.value());
+            } else if key == "lang" {
+                args.lang = Some(lit.value());
+            } else {
+                return Err(syn::Error::new(key.span(), format!("Unknown argument: {}", key)));
+            }
+
+            if !input.is_empty() {
+                let _comma: syn::Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+/// Casings serde's `rename_all` accepts
+const SERDE_CASINGS: [&str; 8] = [
+    "lowercase",
+    "UPPERCASE",
+    "PascalCase",
+    "camelCase",
+    "snake_case",
+    "SCREAMING_SNAKE_CASE",
+    "kebab-case",
+    "SCREAMING-KEBAB-CASE",
+];
+
+/// Attribute macro giving a DTO the project's JSON casing
+///
+/// Adds `#[serde(rename_all = "...")]`, which both the serde derives and `utoipa::ToSchema`
+/// read, so the docs always match the wire format. The casing is `snake_case`, or
+/// `camelCase` for the whole project when the `camel-case` feature of `argon_macros`
+/// is enabled. A DTO can still pick its own with `#[api_model(rename_all = "...")]`,
+/// and one that already has a serde `rename_all` is left untouched.
+///
+/// Put it above the derives:
+/// ```rust,ignore
+/// #[api_model]
+/// #[derive(serde::Serialize, utoipa::ToSchema)]
+/// pub struct UserResponse {
+///     pub id: i32,
+///     // `createdAt` with the `camel-case` feature
+///     pub created_at: DateTime,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn api_model(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+
+    let mut rename_all = if cfg!(feature = "camel-case") {
+        "camelCase".to_string()
+    } else {
+        "snake_case".to_string()
+    };
+
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("rename_all") {
+            let lit: LitStr = meta.value()?.parse()?;
+            if !SERDE_CASINGS.contains(&lit.value().as_str()) {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!("unknown casing `{}`, expected one of {}", lit.value(), SERDE_CASINGS.join(", ")),
+                ));
+            }
+
+            rename_all = lit.value();
+            Ok(())
+        } else {
+            Err(meta.error(format!(
+                "Unknown argument: {}",
+                meta.path.get_ident().map(|ident| ident.to_string()).unwrap_or_default()
+            )))
+        }
+    });
+    parse_macro_input!(args with parser);
+
+    let renamed = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .any(|attr| attr.meta.to_token_stream().to_string().contains("rename_all"));
+
+    if !renamed {
+        input.attrs.push(syn::parse_quote! { #[serde(rename_all = #rename_all)] });
+    }
+
+    quote! { #input }.into()
+}
@@ -0,0 +1,15 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// String column only accepting one of `variants`, enforced by a `CHECK` constraint
+///
+/// Usage:
+/// ```rust,ignore
+/// Table::alter()
+///     .table("user")
+///     .add_column(enum_column("role", &["admin", "member"]).default("member").not_null())
+/// ```
+pub fn enum_column(name: &'static str, variants: &[&str]) -> ColumnDef {
+    string(name)
+        .check(Expr::col(name).is_in(variants.iter().copied()))
+        .to_owned()
+}
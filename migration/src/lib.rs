@@ -1,12 +1,20 @@
 pub use sea_orm_migration::prelude::*;
 
+pub mod helpers;
+
 mod m20220101_000001_create_user_table;
+mod m20261016_000001_add_role_to_user_table;
+mod m20261016_000002_create_audit_log_table;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_user_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_user_table::Migration),
+            Box::new(m20261016_000001_add_role_to_user_table::Migration),
+            Box::new(m20261016_000002_create_audit_log_table::Migration),
+        ]
     }
 }
@@ -0,0 +1,28 @@
+use sea_orm_migration::prelude::*;
+
+use crate::helpers::enum_column;
+
+pub const ROLES: [&str; 2] = ["admin", "member"];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table("user")
+                    .add_column(enum_column("role", &ROLES).default("member").not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(Table::alter().table("user").drop_column("role").to_owned())
+            .await
+    }
+}
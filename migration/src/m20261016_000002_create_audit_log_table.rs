@@ -0,0 +1,50 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::helpers::enum_column;
+
+pub const ACTIONS: [&str; 3] = ["create", "update", "delete"];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table("audit_log")
+                    .if_not_exists()
+                    .col(pk_auto("id"))
+                    .col(string("entity").not_null())
+                    .col(string("entity_id").not_null())
+                    .col(enum_column("action", &ACTIONS).not_null())
+                    .col(string_null("actor"))
+                    .col(json_binary("changes").not_null())
+                    .col(
+                        timestamp("created_at")
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_log_entity")
+                    .table("audit_log")
+                    .col("entity")
+                    .col("entity_id")
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table("audit_log").to_owned())
+            .await
+    }
+}
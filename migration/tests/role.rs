@@ -0,0 +1,58 @@
+use migration::{Migrator, MigratorTrait};
+use sea_orm_migration::sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection};
+
+/// A fresh in-memory SQLite database with every migration applied
+async fn migrated() -> DatabaseConnection {
+    // a single connection, every new one would open an empty database
+    let mut options = ConnectOptions::new("sqlite::memory:");
+    options.max_connections(1);
+
+    let db = Database::connect(options).await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+
+    db
+}
+
+fn insert(role: &str) -> String {
+    format!(
+        "INSERT INTO \"user\" (name, username, password, role) VALUES ('Alice', 'alice-{}', 'x', '{}')",
+        role, role
+    )
+}
+
+#[tokio::test]
+async fn known_roles_are_accepted() {
+    let db = migrated().await;
+
+    db.execute_unprepared(&insert("admin")).await.unwrap();
+    db.execute_unprepared(&insert("member")).await.unwrap();
+}
+
+#[tokio::test]
+async fn an_unknown_role_is_rejected() {
+    let db = migrated().await;
+
+    let err = db.execute_unprepared(&insert("superuser")).await.unwrap_err();
+
+    assert!(err.to_string().contains("CHECK constraint failed"), "{}", err);
+}
+
+#[tokio::test]
+async fn the_role_defaults_to_member() {
+    let db = migrated().await;
+
+    db.execute_unprepared("INSERT INTO \"user\" (name, username, password) VALUES ('Bob', 'bob', 'x')")
+        .await
+        .unwrap();
+
+    let row = db
+        .query_one_raw(sea_orm_migration::sea_orm::Statement::from_string(
+            db.get_database_backend(),
+            "SELECT role FROM \"user\" WHERE username = 'bob'",
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(row.try_get::<String>("", "role").unwrap(), "member");
+}
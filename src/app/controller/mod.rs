@@ -4,7 +4,63 @@ pub struct TestController;
 impl TestController {
     #[argon_macros::get("/hello/{id}")]
     #[argon_macros::utoipa_response(response = crate::app::response::BasicResponse)]
-    pub async fn index(axum::extract::Path(id): axum::extract::Path<u64>) -> Result<crate::app::response::BasicResponse, crate::app::response::BasicResponse> {
-        unimplemented!()
+    pub async fn index(axum::extract::Path(_id): axum::extract::Path<u64>) -> Result<crate::app::response::BasicResponse, crate::app::response::BasicResponse> {
+        Ok(crate::app::response::BasicResponse::ok(format!("record {} found", _id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use argon_core::controller::Controller;
+    use argon_core::plugin::{PluginRegistry, RoutesPlugin};
+    use argon_core::routing::registered_routes;
+    use argon_core::testing::{TestApp, empty_state};
+    use axum::Router;
+    use axum::http::StatusCode;
+
+    use super::TestController;
+
+    #[tokio::test]
+    async fn index_answers_the_record() {
+        let app = TestApp::controller::<TestController>().with_state(empty_state());
+
+        let (status, body) = app.get_json("/hello/7").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "record 7 found");
+    }
+
+    #[test]
+    fn test_controller_routes_are_registered() {
+        let route = registered_routes()
+            .into_iter()
+            .find(|route| route.controller == "TestController")
+            .expect("TestController should register its routes");
+
+        assert_eq!((route.method, route.path), ("GET", "/hello/{id}"));
+    }
+
+    #[test]
+    fn test_controller_documents_its_single_operation() {
+        assert_eq!(TestController::operation_paths(), [("get", "/hello/{id}")]);
+        assert_eq!(TestController::operation_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn the_routes_endpoint_lists_them() {
+        let app = TestApp::new(PluginRegistry::new().register(RoutesPlugin::new()).mount(Router::new()));
+
+        let (status, routes) = app.get_json("/_routes").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(
+            routes
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|route| route["path"] == "/hello/{id}" && route["controller"] == "TestController"),
+            "{}",
+            routes
+        );
     }
 }
@@ -1,5 +1,13 @@
+use std::sync::LazyLock;
+
 pub use argon_core::auth::auth_middleware;
-use sea_orm::DatabaseConnection;
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon_core::auth::BasicCredentials;
+use argon_core::db::UserStore;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+
+use crate::app::model::user;
 
 #[derive(Clone)]
 pub struct BasicUser {
@@ -8,6 +16,16 @@ pub struct BasicUser {
     password: String,
 }
 
+impl From<user::Model> for BasicUser {
+    fn from(user: user::Model) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            password: user.password,
+        }
+    }
+}
+
 impl argon_core::auth::AuthenticatableUser for BasicUser {
     type Username = String;
     type Password = String;
@@ -26,22 +44,83 @@ impl argon_core::auth::AuthenticatableUser for BasicUser {
     }
 }
 
-pub struct BasicAuthenticator {
-    db: DatabaseConnection,
+impl UserStore<BasicUser> for DatabaseConnection {
+    async fn find_by_username(&self, username: &String) -> Result<Option<BasicUser>, DbErr> {
+        let user = user::Entity::find()
+            .filter(user::Column::Username.eq(username))
+            .one(self)
+            .await?;
+
+        Ok(user.map(BasicUser::from))
+    }
+
+    async fn find_by_id(&self, id: &i32) -> Result<Option<BasicUser>, DbErr> {
+        Ok(user::Entity::find_by_id(*id).one(self).await?.map(BasicUser::from))
+    }
 }
 
-impl argon_core::auth::Authenticator<BasicUser> for BasicAuthenticator {
+// verified in place of a missing user's hash, so an unknown username takes as long to
+// reject as a wrong password
+static DUMMY_HASH: LazyLock<String> = LazyLock::new(|| {
+    let salt = SaltString::from_b64("YXJnb24tZHVtbXktc2FsdA").expect("the dummy salt is valid base64");
+
+    Argon2::default()
+        .hash_password(b"argon-dummy-password", &salt)
+        .expect("the dummy password hashes with the default parameters")
+        .to_string()
+});
+
+/// Authenticates `Basic base64(username:password)` tokens against a [`UserStore`]
+///
+/// Stored passwords are argon2 hashes in the PHC string format, `$argon2id$v=19$...`.
+#[derive(Clone)]
+pub struct BasicAuthenticator<S = DatabaseConnection> {
+    store: S,
+}
+
+impl<S> BasicAuthenticator<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+}
+
+impl<S> argon_core::auth::Authenticator<BasicUser> for BasicAuthenticator<S>
+where
+    S: UserStore<BasicUser>,
+{
     type Token = anyhow::Result<String>;
 
     async fn verify(&self, token: &str) -> Result<BasicUser, axum::http::StatusCode> {
-        unimplemented!()
+        let credentials = BasicCredentials::parse(token).ok_or(axum::http::StatusCode::UNAUTHORIZED)?;
+
+        self.attempt(credentials.username, credentials.password)
+            .await
+            .map_err(|err| {
+                tracing::debug!("rejecting the token: {:?}", err);
+
+                axum::http::StatusCode::UNAUTHORIZED
+            })
     }
 
     async fn attempt(&self, username: String, password: String) -> anyhow::Result<BasicUser> {
-        unimplemented!()
+        let Some(user) = self.store.find_by_username(&username).await? else {
+            let hash = PasswordHash::new(&DUMMY_HASH).expect("the dummy hash is a PHC string");
+            let _ = Argon2::default().verify_password(password.as_bytes(), &hash);
+
+            return Err(anyhow::anyhow!("no user named `{}`", username));
+        };
+
+        // the hash comparison runs in constant time
+        let hash = PasswordHash::new(&user.password)
+            .map_err(|err| anyhow::anyhow!("cannot read the password hash of `{}`: {}", username, err))?;
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .map_err(|_| anyhow::anyhow!("wrong password for `{}`", username))?;
+
+        Ok(user)
     }
 
-    async fn generate_token(&self, user: BasicUser) -> Self::Token {
+    async fn generate_token(&self, _user: BasicUser) -> Self::Token {
         unimplemented!()
     }
 
@@ -50,8 +129,73 @@ impl argon_core::auth::Authenticator<BasicUser> for BasicAuthenticator {
     }
 }
 
-impl Clone for BasicAuthenticator {
-    fn clone(&self) -> Self {
-        Self { db: self.db.clone() }
+#[cfg(test)]
+mod tests {
+    use argon_core::auth::Authenticator;
+    use argon_core::testing::MemoryUserStore;
+    use axum::http::StatusCode;
+
+    use super::*;
+
+    fn alice() -> BasicUser {
+        let salt = SaltString::from_b64("YXJnb24tdGVzdC1zYWx0").unwrap();
+        // cheap parameters, the verification reads them from the hash
+        let params = argon2::Params::new(8, 1, 1, None).unwrap();
+        let hash = Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+            .hash_password(b"secret", &salt)
+            .unwrap();
+
+        BasicUser {
+            id: 1,
+            username: "alice".to_string(),
+            password: hash.to_string(),
+        }
+    }
+
+    fn authenticator() -> BasicAuthenticator<MemoryUserStore<BasicUser>> {
+        BasicAuthenticator::new(MemoryUserStore::new().user(alice()))
+    }
+
+    #[tokio::test]
+    async fn attempt_checks_the_password_against_the_hash() {
+        let user = authenticator().attempt("alice".to_string(), "secret".to_string()).await.unwrap();
+        assert_eq!(user.id, 1);
+
+        let err = authenticator().attempt("alice".to_string(), "hunter2".to_string()).await.err().unwrap();
+        assert_eq!(err.to_string(), "wrong password for `alice`");
+
+        let err = authenticator().attempt("bob".to_string(), "secret".to_string()).await.err().unwrap();
+        assert_eq!(err.to_string(), "no user named `bob`");
+    }
+
+    #[test]
+    fn the_dummy_hash_costs_as_much_as_a_default_one() {
+        let hash = PasswordHash::new(&DUMMY_HASH).unwrap();
+
+        let (params, default) = (argon2::Params::try_from(&hash).unwrap(), argon2::Params::default());
+        assert_eq!(
+            (params.m_cost(), params.t_cost(), params.p_cost()),
+            (default.m_cost(), default.t_cost(), default.p_cost())
+        );
+        assert!(Argon2::default().verify_password(b"secret", &hash).is_err());
+    }
+
+    #[tokio::test]
+    async fn a_plaintext_password_never_matches() {
+        let plaintext = BasicUser {
+            password: "secret".to_string(),
+            ..alice()
+        };
+        let authenticator = BasicAuthenticator::new(MemoryUserStore::new().user(plaintext));
+
+        assert!(authenticator.attempt("alice".to_string(), "secret".to_string()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_reads_basic_credentials() {
+        // base64("alice:secret") and base64("alice:hunter2")
+        assert_eq!(authenticator().verify("Basic YWxpY2U6c2VjcmV0").await.unwrap().id, 1);
+        assert_eq!(authenticator().verify("Basic YWxpY2U6aHVudGVyMg==").await.err(), Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(authenticator().verify("Bearer YWxpY2U6c2VjcmV0").await.err(), Some(StatusCode::UNAUTHORIZED));
     }
 }
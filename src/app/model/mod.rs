@@ -1,5 +1,3 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 2.0.0-rc.19
-
-pub mod prelude;
+//! `SeaORM` Entity, @generated by `argon generate entities`
 
 pub mod user;
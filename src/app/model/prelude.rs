@@ -1,3 +0,0 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 2.0.0-rc.19
-
-pub use super::user::Entity as User;
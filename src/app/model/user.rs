@@ -1,5 +1,6 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 2.0.0-rc.19
+//! `SeaORM` Entity, @generated by `argon generate entities`
 
+use argon_core::model::{Auditable, audit};
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -12,9 +13,131 @@ pub struct Model {
     pub username: String,
     pub password: String,
     pub created_at: DateTime,
+    pub role: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
 
-impl ActiveModelBehavior for ActiveModel {}
+// the audit hooks are written by hand, `argon generate entities` keeps this file
+impl Auditable for ActiveModel {
+    fn redacted_columns() -> &'static [&'static str] {
+        &["password"]
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C: ConnectionTrait>(self, db: &C, insert: bool) -> Result<Self, DbErr> {
+        audit::before_save(&self, db, insert).await?;
+
+        Ok(self)
+    }
+
+    async fn after_save<C: ConnectionTrait>(model: Model, db: &C, insert: bool) -> Result<Model, DbErr> {
+        audit::after_save::<Self, _>(&model, db, insert).await?;
+
+        Ok(model)
+    }
+
+    async fn after_delete<C: ConnectionTrait>(self, db: &C) -> Result<Self, DbErr> {
+        audit::after_delete(&self, db).await?;
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use argon_core::model::audit::with_actor;
+    use sea_orm::{ActiveValue::Set, ConnectOptions, Database, DatabaseConnection, Schema, Statement};
+
+    use super::*;
+
+    /// A fresh in-memory SQLite database with the `user` and `audit_log` tables
+    async fn database() -> DatabaseConnection {
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1);
+
+        let db = Database::connect(options).await.unwrap();
+        let schema = Schema::new(db.get_database_backend());
+        db.execute(&schema.create_table_from_entity(Entity)).await.unwrap();
+        db.execute_unprepared(
+            "CREATE TABLE audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity TEXT NOT NULL,
+                entity_id TEXT NOT NULL,
+                action TEXT NOT NULL,
+                actor TEXT,
+                changes TEXT NOT NULL
+            )",
+        )
+        .await
+        .unwrap();
+
+        db
+    }
+
+    /// `(action, actor, changes)` of the audit rows, oldest first
+    async fn audit_rows(db: &DatabaseConnection) -> Vec<(String, Option<String>, serde_json::Value)> {
+        let rows = db
+            .query_all_raw(Statement::from_string(
+                db.get_database_backend(),
+                "SELECT entity, entity_id, action, actor, changes FROM audit_log ORDER BY id",
+            ))
+            .await
+            .unwrap();
+
+        rows.into_iter()
+            .map(|row| {
+                assert_eq!(row.try_get::<String>("", "entity").unwrap(), "user");
+                assert_eq!(row.try_get::<String>("", "entity_id").unwrap(), "1");
+
+                let changes: String = row.try_get("", "changes").unwrap();
+                (
+                    row.try_get("", "action").unwrap(),
+                    row.try_get("", "actor").unwrap(),
+                    serde_json::from_str(&changes).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn an_update_is_audited_with_its_actor_and_changed_fields() {
+        let db = database().await;
+        let created_at = DateTime::parse_from_str("2025-01-31 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let alice = ActiveModel {
+            name: Set("Alice".to_string()),
+            username: Set("alice".to_string()),
+            password: Set("hash".to_string()),
+            created_at: Set(created_at),
+            role: Set("member".to_string()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let mut update: ActiveModel = alice.into();
+        update.name = Set("Alice Liddell".to_string());
+        update.password = Set("new hash".to_string());
+        with_actor(Some("7".to_string()), update.update(&db)).await.unwrap();
+
+        let rows = audit_rows(&db).await;
+        assert_eq!(rows.len(), 2);
+
+        let (action, actor, changes) = &rows[0];
+        assert_eq!((action.as_str(), actor), ("create", &None));
+        assert_eq!(changes["username"], "alice");
+        assert_eq!(changes["password"], "***");
+
+        let (action, actor, changes) = &rows[1];
+        assert_eq!((action.as_str(), actor.as_deref()), ("update", Some("7")));
+        assert_eq!(
+            changes,
+            &serde_json::json!({ "name": "Alice Liddell", "password": "***" })
+        );
+    }
+}
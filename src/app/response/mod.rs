@@ -1,3 +1,4 @@
+#[argon_macros::api_model]
 #[derive(serde::Serialize, utoipa::ToSchema)]
 pub struct SimpleResponse {
     pub message: String
@@ -9,3 +10,48 @@ argon_macros::response! {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use argon_core::response::FromModel;
+    use sea_orm::prelude::DateTime;
+
+    use super::SimpleResponse;
+    use crate::app::model::user;
+
+    #[derive(serde::Serialize, utoipa::ToSchema, argon_macros::FromModel)]
+    #[from_model(crate::app::model::user::Model)]
+    struct UserResponse {
+        id: i32,
+        username: String,
+        created_at: DateTime,
+    }
+
+    #[test]
+    fn user_response_leaves_the_password_out() {
+        let created_at = DateTime::parse_from_str("2025-01-31 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let user = user::Model {
+            id: 1,
+            name: "Alice".to_string(),
+            username: "alice".to_string(),
+            password: "secret".to_string(),
+            created_at,
+            role: "member".to_string(),
+        };
+
+        let json = serde_json::to_value(UserResponse::from_model(user)).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({ "id": 1, "username": "alice", "created_at": "2025-01-31T12:00:00" })
+        );
+    }
+
+    #[test]
+    fn simple_response_has_a_generated_example() {
+        assert_eq!(
+            argon_core::docs::example_for::<SimpleResponse>(),
+            serde_json::json!({ "message": "string" })
+        );
+    }
+}
@@ -1,3 +1,3 @@
-pub async fn init_env() {
+pub fn init_env() {
     dotenvy::dotenv().expect("cannot load the .env file. is it there?");
 }
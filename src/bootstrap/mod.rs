@@ -1,10 +1,15 @@
+mod banner;
 mod env;
 mod server;
+mod startup;
 mod tracing;
 
+pub use env::init_env;
 pub use server::init_server;
+pub use tracing::shutdown_tracing;
 
+/// Set up what every command needs, once the environment is loaded by [`init_env`]
 pub async fn init_base() {
-    env::init_env().await;
     tracing::init_tracing().await;
+    argon_core::panic::install_panic_hook();
 }
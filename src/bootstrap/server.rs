@@ -1,29 +1,178 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::Path, time::Duration};
 
-use axum::Extension;
+use argon_core::extract::{JsonLimits, TrustedProxies};
+use argon_core::lifecycle::{self, LifecycleEvent};
+use argon_core::state::AppState;
+use axum::{Extension, Router};
 use sea_orm::{Database, DatabaseConnection};
 
 use crate::config::app::AppConfig;
 
 pub async fn init_server() -> anyhow::Result<()> {
-    crate::docs::generate_docs().await?;
+    lifecycle::publish(LifecycleEvent::Starting);
 
-    let database_url = AppConfig::database_url().await;
+    super::banner::log_startup(&AppConfig::get().await);
 
-    let db: DatabaseConnection = Database::connect(database_url).await?;
+    if AppConfig::docs().await {
+        crate::docs::generate_docs("api.json").await?;
+    }
 
-    let port = AppConfig::port().await;
+    let config = AppConfig::get().await;
 
-    // Build the router
-    let app = crate::routes::routes()
-        .layer(Extension(db));
+    let db: DatabaseConnection = Database::connect(config.require_database_url()?).await?;
+    // fail now rather than on the first request when the database isn't usable
+    argon_core::db::check_database(&db, &["user"]).await?;
+
+    let state = AppState::new(db)
+        .with(config.clone())
+        .with(TrustedProxies::new(config.trusted_proxies.clone()).header(config.forwarded_header))
+        .with(JsonLimits::new(config.json_max_depth, config.json_max_fields));
+    let state = super::startup::startup().run(state).await?;
+
+    argon_core::maintenance::set_enabled(config.maintenance_mode);
+
+    // Build the routers
+    let routers = crate::routes::routes(&config)?;
+    let app = routers.main.layer(Extension(state));
 
     // Start the server
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    tracing::info!("Server listening on {}", addr);
+    match &config.uds_path {
+        Some(path) => serve_unix(path, app, &config).await?,
+        None => serve_tcp(app, routers.metrics, &config, shutdown_signal()).await?,
+    }
+
+    // Give fire-and-forget tasks a chance to finish before exiting
+    argon_core::tasks::drain(Duration::from_secs(30)).await;
+
+    lifecycle::publish(LifecycleEvent::Stopped);
+
+    Ok(())
+}
+
+/// Serve `app` on the configured port, and `metrics` on `METRICS_PORT` when both are set
+///
+/// `LifecycleEvent::Listening` is published with the bound address once connections are accepted.
+async fn serve_tcp(
+    app: Router,
+    metrics: Option<Router>,
+    config: &AppConfig,
+    shutdown: impl Future<Output = ()>,
+) -> anyhow::Result<()> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    // the bound address, which differs from `addr` when port 0 was asked for
+    let addr = listener.local_addr()?;
+    tracing::info!("Server listening on {}", addr);
+    lifecycle::publish(LifecycleEvent::Listening(addr));
+
+    let mut servers = vec![(listener, app)];
+
+    if let (Some(metrics), Some(metrics_port)) = (metrics, config.metrics_port) {
+        let addr = SocketAddr::from(([0, 0, 0, 0], metrics_port));
+
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|err| anyhow::anyhow!("cannot bind the metrics port {}: {:?}", metrics_port, err))?;
+        tracing::info!("Metrics listening on {}", listener.local_addr()?);
+
+        servers.push((listener, metrics));
+    }
+
+    // one shutdown signal stops every listener, then they drain together
+    argon_core::server::serve_many(servers, config.server_options(), shutdown).await?;
 
     Ok(())
 }
+
+/// Serve on the Unix domain socket at `path`, removing the socket file once stopped
+///
+/// `LifecycleEvent::Listening` is not published, it carries a TCP address.
+#[cfg(unix)]
+async fn serve_unix(path: &Path, app: Router, config: &AppConfig) -> anyhow::Result<()> {
+    let listener = argon_core::server::bind_unix(path)
+        .map_err(|err| anyhow::anyhow!("cannot bind the socket {}: {:?}", path.display(), err))?;
+    tracing::info!("Server listening on unix:{}", path.display());
+
+    let served = argon_core::server::serve(listener, app, config.server_options(), shutdown_signal()).await;
+
+    if let Err(err) = std::fs::remove_file(path) {
+        tracing::warn!("cannot remove the socket {}: {:?}", path.display(), err);
+    }
+
+    Ok(served?)
+}
+
+#[cfg(not(unix))]
+async fn serve_unix(_path: &Path, _app: Router, _config: &AppConfig) -> anyhow::Result<()> {
+    anyhow::bail!("`SERVER_UDS_PATH` is only supported on Unix")
+}
+
+async fn shutdown_signal() {
+    if let Err(err) = tokio::signal::ctrl_c().await {
+        tracing::error!("cannot listen for the shutdown signal: {:?}", err);
+
+        std::future::pending::<()>().await;
+    }
+
+    tracing::info!("shutting down");
+    lifecycle::publish(LifecycleEvent::ShuttingDown);
+}
+
+#[cfg(test)]
+mod tests {
+    use argon_core::config::ConfigBuilder;
+    use axum::routing::get;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    /// The raw HTTP/1.1 response to `GET path` on `addr`
+    async fn fetch(addr: SocketAddr, path: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        response
+    }
+
+    // a single test, `Listening` is published to every subscriber of the binary
+    #[tokio::test]
+    async fn the_app_and_metrics_are_served_on_their_own_ports() {
+        // a port that was free a moment ago, the metrics address isn't published
+        let metrics_port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+
+        let mut config = AppConfig::build().unwrap();
+        config.port = 0;
+        config.metrics_port = Some(metrics_port);
+
+        let mut events = lifecycle::subscribe();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+        let app = Router::new().route("/ping", get(|| async { "pong" }));
+        let metrics = Router::new().route("/metrics", get(|| async { "requests 1" }));
+        let server = tokio::spawn(async move {
+            serve_tcp(app, Some(metrics), &config, async {
+                let _ = stopped.await;
+            })
+            .await
+        });
+
+        let addr = lifecycle::listening(&mut events).await.unwrap();
+        assert_ne!(addr.port(), 0);
+
+        let response = fetch(addr, "/ping").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("pong"));
+
+        let response = fetch(SocketAddr::from(([127, 0, 0, 1], metrics_port)), "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("requests 1"));
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,12 @@
+use argon_core::bootstrap::StartupHooks;
+
+use crate::app::middleware::auth::BasicAuthenticator;
+
+/// App initialization run after the database connects and before serving
+pub fn startup() -> StartupHooks {
+    StartupHooks::new().on_startup("authenticator", |state| async move {
+        let authenticator = BasicAuthenticator::new(state.db().clone());
+
+        Ok(state.with(authenticator))
+    })
+}
@@ -1,12 +1,191 @@
+use argon_core::config::Profile;
+use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Targets that log every executed SQL statement
+///
+/// sqlx logs each statement at `info` under `sqlx::query`, so without an explicit
+/// directive they would show up with the default `info` level.
+const SQL_TARGETS: [&str; 2] = ["sqlx::query", "sea_orm"];
+
 pub async fn init_tracing() {
     // Initialize tracing with customizable log level from RUST_LOG environment variable
     // Default to "info" if not set
     let default_level = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
 
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(&default_level)),
-        )
-        .init();
+    // Set LOG_SQL=true to log the SQL statements sent to the database
+    let log_sql = std::env::var("LOG_SQL")
+        .map(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false);
+
+    // Set LOG_JSON=true to write JSON lines, the default in the production profile
+    let log_json = std::env::var("LOG_JSON")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        // an unknown `APP_ENV` fails the config right after, log as in production meanwhile
+        .unwrap_or_else(|| Profile::from_env().unwrap_or(Profile::Production).json_logs());
+
+    // Set OTEL_EXPORTER_OTLP_ENDPOINT to export spans to a collector (needs the `otel` feature)
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok();
+
+    let registry = tracing_subscriber::registry()
+        .with(build_env_filter(&default_level, log_sql))
+        .with(log_json.then(|| fmt::layer().json()))
+        .with((!log_json).then(fmt::layer));
+
+    #[cfg(feature = "otel")]
+    {
+        let otel = otel::layer(endpoint.as_deref());
+        let installed = otel.is_some();
+
+        registry.with(otel).init();
+
+        if installed {
+            tracing::info!("exporting spans over OTLP");
+        }
+    }
+
+    #[cfg(not(feature = "otel"))]
+    {
+        registry.init();
+
+        if endpoint.is_some_and(|endpoint| !endpoint.trim().is_empty()) {
+            tracing::warn!("OTEL_EXPORTER_OTLP_ENDPOINT is set but argon was built without the `otel` feature");
+        }
+    }
+}
+
+/// Flush the spans still buffered by the OTLP exporter, a no-op without the `otel` feature
+pub fn shutdown_tracing() {
+    #[cfg(feature = "otel")]
+    otel::shutdown();
+}
+
+/// Build the `EnvFilter` from `RUST_LOG` directives plus crate-specific defaults
+///
+/// SQL targets are set to `debug` when `log_sql` is on and `warn` otherwise.
+/// A target that is already mentioned in `rust_log` is left as configured there.
+pub fn build_env_filter(rust_log: &str, log_sql: bool) -> EnvFilter {
+    let sql_level = if log_sql { "debug" } else { "warn" };
+
+    let mut directives = vec![rust_log.to_string()];
+
+    for target in SQL_TARGETS {
+        if !rust_log.contains(target) {
+            directives.push(format!("{}={}", target, sql_level));
+        }
+    }
+
+    EnvFilter::new(directives.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::Level;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::build_env_filter;
+
+    /// Whether SQL statements and app `info` events pass the filter
+    fn enabled(rust_log: &str, log_sql: bool) -> (bool, bool) {
+        let subscriber = tracing_subscriber::registry().with(build_env_filter(rust_log, log_sql));
+
+        tracing::subscriber::with_default(subscriber, || {
+            (
+                tracing::enabled!(target: "sqlx::query", Level::DEBUG),
+                tracing::enabled!(target: "argon", Level::INFO),
+            )
+        })
+    }
+
+    #[test]
+    fn sql_targets_follow_log_sql() {
+        assert_eq!(enabled("info", true), (true, true));
+        assert_eq!(enabled("info", false), (false, true));
+    }
+
+    #[test]
+    fn rust_log_directives_win_over_log_sql() {
+        assert_eq!(enabled("info,sqlx::query=debug", false), (true, true));
+        assert_eq!(enabled("info,sqlx::query=warn", true), (false, true));
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::sync::OnceLock;
+
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
+    use tracing_opentelemetry::OpenTelemetryLayer;
+    use tracing_subscriber::registry::LookupSpan;
+
+    const SERVICE_NAME: &str = "argon";
+
+    static PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+    /// OpenTelemetry layer exporting spans to `endpoint` over OTLP/HTTP
+    ///
+    /// `None` when the endpoint is unset or empty, or when the exporter cannot be built,
+    /// so the application keeps running with the fmt layer only.
+    pub fn layer<S>(
+        endpoint: Option<&str>,
+    ) -> Option<OpenTelemetryLayer<S, opentelemetry_sdk::trace::SdkTracer>>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let endpoint = endpoint.map(str::trim).filter(|endpoint| !endpoint.is_empty())?;
+
+        let exporter = match SpanExporter::builder()
+            .with_http()
+            .with_endpoint(endpoint)
+            .build()
+        {
+            Ok(exporter) => exporter,
+            Err(err) => {
+                // the subscriber is not installed yet, so tracing macros would go nowhere
+                eprintln!("cannot build the OTLP exporter for {}: {}", endpoint, err);
+
+                return None;
+            }
+        };
+
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+            .build();
+
+        let tracer = provider.tracer(SERVICE_NAME);
+        opentelemetry::global::set_tracer_provider(provider.clone());
+        let _ = PROVIDER.set(provider);
+
+        Some(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+
+    pub fn shutdown() {
+        if let Some(provider) = PROVIDER.get()
+            && let Err(err) = provider.shutdown()
+        {
+            eprintln!("cannot flush the OTLP exporter: {}", err);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use tracing_subscriber::Registry;
+
+        use super::layer;
+
+        #[tokio::test]
+        async fn the_layer_is_installed_with_an_endpoint() {
+            assert!(layer::<Registry>(Some("http://localhost:4318/v1/traces")).is_some());
+        }
+
+        #[test]
+        fn there_is_no_layer_without_an_endpoint() {
+            assert!(layer::<Registry>(None).is_none());
+            assert!(layer::<Registry>(Some("  ")).is_none());
+        }
+    }
 }
@@ -0,0 +1,341 @@
+use std::{collections::BTreeMap, path::Path};
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+/// Tables SeaORM manages itself
+const IGNORED_TABLES: [&str; 1] = ["seaql_migrations"];
+
+const COLUMNS_QUERY: &str = r#"
+SELECT table_name::text, column_name::text, data_type::text,
+       is_nullable = 'YES' AS nullable,
+       COALESCE(column_default LIKE 'nextval(%', false) OR is_identity = 'YES' AS auto_increment
+FROM information_schema.columns
+WHERE table_schema = current_schema()
+ORDER BY table_name, ordinal_position
+"#;
+
+// single column constraints only, a composite unique key isn't a per-field attribute
+const KEYS_QUERY: &str = r#"
+SELECT tc.table_name::text, MIN(kcu.column_name::text) AS column_name, tc.constraint_type::text
+FROM information_schema.table_constraints tc
+JOIN information_schema.key_column_usage kcu
+  ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema
+WHERE tc.table_schema = current_schema() AND tc.constraint_type = 'UNIQUE'
+GROUP BY tc.table_name, tc.constraint_name, tc.constraint_type
+HAVING COUNT(*) = 1
+UNION ALL
+SELECT tc.table_name::text, kcu.column_name::text, tc.constraint_type::text
+FROM information_schema.table_constraints tc
+JOIN information_schema.key_column_usage kcu
+  ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema
+WHERE tc.table_schema = current_schema() AND tc.constraint_type = 'PRIMARY KEY'
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    pub name: String,
+    /// Postgres type as named by `information_schema`, e.g. `character varying`
+    pub data_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+    pub auto_increment: bool,
+    pub unique: bool,
+}
+
+/// Tables of the current Postgres schema with their columns, in declaration order
+pub async fn read_schema(db: &DatabaseConnection) -> anyhow::Result<Vec<Table>> {
+    let mut tables: BTreeMap<String, Vec<Column>> = BTreeMap::new();
+
+    for row in db
+        .query_all_raw(Statement::from_string(DbBackend::Postgres, COLUMNS_QUERY))
+        .await?
+    {
+        let table: String = row.try_get("", "table_name")?;
+        if IGNORED_TABLES.contains(&table.as_str()) {
+            continue;
+        }
+
+        tables.entry(table).or_default().push(Column {
+            name: row.try_get("", "column_name")?,
+            data_type: row.try_get("", "data_type")?,
+            nullable: row.try_get("", "nullable")?,
+            primary_key: false,
+            auto_increment: row.try_get("", "auto_increment")?,
+            unique: false,
+        });
+    }
+
+    for row in db
+        .query_all_raw(Statement::from_string(DbBackend::Postgres, KEYS_QUERY))
+        .await?
+    {
+        let table: String = row.try_get("", "table_name")?;
+        let column: String = row.try_get("", "column_name")?;
+        let constraint: String = row.try_get("", "constraint_type")?;
+
+        let Some(column) = tables
+            .get_mut(&table)
+            .and_then(|columns| columns.iter_mut().find(|candidate| candidate.name == column))
+        else {
+            continue;
+        };
+
+        match constraint.as_str() {
+            "PRIMARY KEY" => column.primary_key = true,
+            _ => column.unique = true,
+        }
+    }
+
+    Ok(tables
+        .into_iter()
+        .map(|(name, columns)| Table { name, columns })
+        .collect())
+}
+
+/// Write one module per table plus a `mod.rs` declaring them
+///
+/// The module of a table that already has one is kept, so hooks written into an entity
+/// survive. Delete the file to generate it again, e.g. after a migration changed the table.
+pub async fn write_entities(tables: &[Table], dir: &Path) -> anyhow::Result<()> {
+    tokio::fs::create_dir_all(dir).await?;
+
+    for table in tables {
+        let path = dir.join(format!("{}.rs", table.name));
+
+        if tokio::fs::try_exists(&path).await? {
+            tracing::info!("keeping the existing entity {}", path.display());
+            continue;
+        }
+
+        tokio::fs::write(path, render_entity(table)).await?;
+    }
+
+    let modules: String = tables
+        .iter()
+        .map(|table| format!("pub mod {};\n", table.name))
+        .collect();
+    tokio::fs::write(dir.join("mod.rs"), format!("{}\n{}", HEADER, modules)).await?;
+
+    Ok(())
+}
+
+const HEADER: &str = "//! `SeaORM` Entity, @generated by `argon generate entities`\n";
+
+/// Source of the SeaORM entity module of `table`
+///
+/// For the `user` table:
+/// ```rust,ignore
+/// #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+/// #[sea_orm(table_name = "user")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     #[sea_orm(unique)]
+///     pub username: String,
+///     ...
+/// }
+/// ```
+pub fn render_entity(table: &Table) -> String {
+    // a float column rules out `Eq`
+    let eq = if table.columns.iter().any(|column| rust_type(&column.data_type).starts_with('f')) {
+        ""
+    } else {
+        ", Eq"
+    };
+    let composite_key = table.columns.iter().filter(|column| column.primary_key).count() > 1;
+
+    let mut fields = String::new();
+    for column in &table.columns {
+        let mut attributes = Vec::new();
+        if column.primary_key {
+            attributes.push("primary_key".to_string());
+
+            if composite_key || !column.auto_increment {
+                attributes.push("auto_increment = false".to_string());
+            }
+        }
+        if column.unique {
+            attributes.push("unique".to_string());
+        }
+
+        let field = field_name(&column.name);
+        if field != column.name {
+            attributes.push(format!("column_name = \"{}\"", column.name));
+        }
+
+        if !attributes.is_empty() {
+            fields.push_str(&format!("    #[sea_orm({})]\n", attributes.join(", ")));
+        }
+
+        let ty = match (rust_type(&column.data_type), column.nullable) {
+            (ty, true) => format!("Option<{}>", ty),
+            (ty, false) => ty.to_string(),
+        };
+        fields.push_str(&format!("    pub {}: {},\n", field, ty));
+    }
+
+    format!(
+        "{header}
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq{eq}, DeriveEntityModel)]
+#[sea_orm(table_name = \"{table}\")]
+pub struct Model {{
+{fields}}}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {{}}
+
+impl ActiveModelBehavior for ActiveModel {{}}
+",
+        header = HEADER,
+        eq = eq,
+        table = table.name,
+        fields = fields,
+    )
+}
+
+/// Rust type of a Postgres column, anything unknown is read as text
+fn rust_type(data_type: &str) -> &'static str {
+    match data_type {
+        "smallint" => "i16",
+        "integer" => "i32",
+        "bigint" => "i64",
+        "real" => "f32",
+        "double precision" => "f64",
+        "boolean" => "bool",
+        "date" => "Date",
+        "time without time zone" => "Time",
+        "timestamp without time zone" => "DateTime",
+        "timestamp with time zone" => "DateTimeWithTimeZone",
+        "json" | "jsonb" => "Json",
+        "bytea" => "Vec<u8>",
+        _ => "String",
+    }
+}
+
+/// Column name as a Rust field, lowercased and without characters an identifier can't hold
+fn field_name(column: &str) -> String {
+    let name: String = column
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+
+    match name.as_str() {
+        "type" | "match" | "ref" | "self" | "struct" | "enum" | "fn" | "mod" | "use" | "impl" | "trait"
+        | "where" | "loop" | "move" | "static" | "const" | "crate" | "super" | "async" | "await" => {
+            format!("{}_", name)
+        }
+        _ if name.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", name),
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: false,
+            primary_key: false,
+            auto_increment: false,
+            unique: false,
+        }
+    }
+
+    /// The `user` table as the migrations leave it
+    fn user_table() -> Table {
+        Table {
+            name: "user".to_string(),
+            columns: vec![
+                Column {
+                    primary_key: true,
+                    auto_increment: true,
+                    ..column("id", "integer")
+                },
+                column("name", "character varying"),
+                Column {
+                    unique: true,
+                    ..column("username", "character varying")
+                },
+                column("password", "character varying"),
+                column("created_at", "timestamp without time zone"),
+                column("role", "character varying"),
+            ],
+        }
+    }
+
+    #[test]
+    fn the_user_entity_has_the_table_fields() {
+        let source = render_entity(&user_table());
+
+        assert!(source.starts_with(HEADER));
+        assert!(source.contains("#[sea_orm(table_name = \"user\")]"));
+        assert!(source.contains(
+            "    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub name: String,
+    #[sea_orm(unique)]
+    pub username: String,
+    pub password: String,
+    pub created_at: DateTime,
+    pub role: String,
+"
+        ));
+    }
+
+    #[test]
+    fn columns_become_valid_fields() {
+        let table = Table {
+            name: "event".to_string(),
+            columns: vec![
+                Column {
+                    primary_key: true,
+                    ..column("Type", "uuid")
+                },
+                Column {
+                    nullable: true,
+                    ..column("2fa-score", "double precision")
+                },
+            ],
+        };
+        let source = render_entity(&table);
+
+        assert!(source.contains("#[sea_orm(primary_key, auto_increment = false, column_name = \"Type\")]"));
+        assert!(source.contains("pub type_: String,"));
+        assert!(source.contains("pub _2fa_score: Option<f64>,"));
+        // a float column rules out `Eq`
+        assert!(source.contains("#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]"));
+    }
+
+    #[tokio::test]
+    async fn existing_entities_are_kept() {
+        let dir = std::env::temp_dir().join(format!("argon-entities-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("user.rs"), "// hooks").unwrap();
+
+        let post = Table {
+            name: "post".to_string(),
+            columns: vec![column("title", "text")],
+        };
+        write_entities(&[user_table(), post.clone()], &dir).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(dir.join("user.rs")).unwrap(), "// hooks");
+        assert_eq!(std::fs::read_to_string(dir.join("post.rs")).unwrap(), render_entity(&post));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("mod.rs")).unwrap(),
+            format!("{}\npub mod user;\npub mod post;\n", HEADER)
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,44 @@
+mod generate;
+
+use crate::config::app::AppConfig;
+
+/// Run the subcommand named by `args`, `None` when there is none and the server should start
+///
+/// Subcommands:
+/// - `openapi [path]`: write the OpenAPI spec to `path`, `api.json` by default, no
+///   database needed
+/// - `generate entities [dir]`: write a SeaORM entity per table of the database to `dir`,
+///   `src/app/model` by default, keeping the entities already there
+pub async fn run(args: &[String]) -> Option<anyhow::Result<()>> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        [] => None,
+        ["openapi", rest @ ..] => {
+            let path = rest.first().copied().unwrap_or("api.json");
+
+            Some(crate::docs::generate_docs(path).await)
+        }
+        ["generate", "entities", rest @ ..] => {
+            let dir = rest.first().copied().unwrap_or("src/app/model");
+
+            Some(generate_entities(dir).await)
+        }
+        _ => Some(Err(anyhow::anyhow!(
+            "unknown command `{}`, expected `openapi [path]` or `generate entities [dir]`",
+            args.join(" ")
+        ))),
+    }
+}
+
+async fn generate_entities(dir: &str) -> anyhow::Result<()> {
+    let config = AppConfig::get().await;
+    let db = sea_orm::Database::connect(config.require_database_url()?).await?;
+
+    let tables = generate::read_schema(&db).await?;
+    generate::write_entities(&tables, std::path::Path::new(dir)).await?;
+
+    tracing::info!("generated {} entities in {}", tables.len(), dir);
+
+    Ok(())
+}
@@ -1,29 +1,151 @@
-use argon_core::config::ConfigBuilder;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use argon_core::config::env::env_or;
+use argon_core::config::{ConfigBuilder, Profile};
+use argon_core::extract::ForwardedHeader;
+use argon_core::routing::TrailingSlash;
+use argon_core::server::ServerOptions;
 use argon_macros::Config;
 
 #[derive(Clone, Config)]
 pub struct AppConfig {
+    pub profile: Profile,
     pub port: u16,
-    pub database_url: String,
+    /// Serve `/metrics` on this port instead of the app one
+    pub metrics_port: Option<u16>,
+    pub uds_path: Option<PathBuf>,
+    /// Only needed to connect, see [`AppConfig::require_database_url`]
+    pub database_url: Option<String>,
+    pub max_uri_len: usize,
+    pub max_header_bytes: usize,
+    pub json_max_depth: usize,
+    pub json_max_fields: usize,
+    pub max_concurrent_requests: usize,
+    pub maintenance_mode: bool,
+    pub maintenance_allowed_ips: Vec<IpAddr>,
+    pub trusted_proxies: Vec<IpAddr>,
+    /// The only header the trusted proxies are read from
+    pub forwarded_header: ForwardedHeader,
+    pub slow_request_ms: u64,
+    pub trailing_slash: TrailingSlash,
+    pub keep_alive: bool,
+    pub keep_alive_timeout_secs: u64,
+    pub http2: bool,
+    pub docs: bool,
+    pub permissive_cors: bool,
 }
 
 impl ConfigBuilder for AppConfig {
     fn build() -> anyhow::Result<Self> {
-        let port = std::env::var("SERVER_PORT")
-            .unwrap_or_else(|_| {
-                tracing::warn!("cannot read `SERVER_PORT` defaulting to `3000`");
-
-                "3000".into()
-            })
-            .parse()
-            .unwrap_or_else(|err| {
-                tracing::error!("cannot parse `SERVER_PORT`. defaulting to 3000 {:?}", err);
-                3000
-            });
+        let profile = Profile::from_env()?;
+
+        let port = env_or("SERVER_PORT", 3000);
+
+        // e.g. a port only reachable by the monitoring, unused with `SERVER_UDS_PATH`
+        let metrics_port = std::env::var("METRICS_PORT")
+            .ok()
+            .filter(|port| !port.trim().is_empty())
+            .map(|port| port.trim().parse())
+            .transpose()
+            .map_err(|err| anyhow::anyhow!("cannot parse `METRICS_PORT`: {:?}", err))?;
 
+        // serve on this Unix domain socket instead of the TCP port
+        let uds_path = std::env::var("SERVER_UDS_PATH")
+            .ok()
+            .filter(|path| !path.trim().is_empty())
+            .map(PathBuf::from);
+
+        // required once connecting, so docs can be generated without a database
         let database_url = std::env::var("DATABASE_URL")
-            .map_err(|err| anyhow::anyhow!("cannot read `DATABASE_URL`: {:?}", err))?;
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        let max_uri_len = env_or("MAX_URI_LEN", 8 * 1024);
+        let max_header_bytes = env_or("MAX_HEADER_BYTES", 16 * 1024);
+        let json_max_depth = env_or("JSON_MAX_DEPTH", 32);
+        let json_max_fields = env_or("JSON_MAX_FIELDS", 1000);
+        let max_concurrent_requests = env_or("MAX_CONCURRENT_REQUESTS", 1024);
+        // only the initial state, see `argon_core::maintenance::set_enabled`
+        let maintenance_mode = env_or("MAINTENANCE_MODE", false);
+        let maintenance_allowed_ips = ip_list("MAINTENANCE_ALLOWED_IPS");
+        let slow_request_ms = env_or("SLOW_REQUEST_MS", 1000);
+        let trailing_slash = env_or("TRAILING_SLASH", TrailingSlash::Strict);
+        let keep_alive = env_or("KEEP_ALIVE", true);
+        let keep_alive_timeout_secs = env_or("KEEP_ALIVE_TIMEOUT_SECS", 75);
+        let http2 = env_or("HTTP2", true);
+        let docs = env_or("DOCS", profile.docs());
+        let permissive_cors = env_or("PERMISSIVE_CORS", profile.permissive_cors());
+
+        // addresses of the reverse proxies in front of the server
+        let trusted_proxies = ip_list("TRUSTED_PROXIES");
+        let forwarded_header = env_or("FORWARDED_HEADER", ForwardedHeader::XForwardedFor);
+
+        Ok(AppConfig {
+            profile,
+            port,
+            metrics_port,
+            uds_path,
+            database_url,
+            max_uri_len,
+            max_header_bytes,
+            json_max_depth,
+            json_max_fields,
+            max_concurrent_requests,
+            maintenance_mode,
+            maintenance_allowed_ips,
+            trusted_proxies,
+            forwarded_header,
+            slow_request_ms,
+            trailing_slash,
+            keep_alive,
+            keep_alive_timeout_secs,
+            http2,
+            docs,
+            permissive_cors,
+        })
+    }
+}
+
+/// Comma separated addresses of `key`, skipping the ones that don't parse
+fn ip_list(key: &str) -> Vec<IpAddr> {
+    std::env::var(key)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .filter_map(|ip| {
+            ip.parse()
+                .inspect_err(|err| tracing::error!("cannot parse `{}` address `{}`, ignoring it {:?}", key, ip, err))
+                .ok()
+        })
+        .collect()
+}
+
+/// `DATABASE_URL` is unset while a database connection is needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingDatabaseUrl;
+
+impl std::fmt::Display for MissingDatabaseUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`DATABASE_URL` is not set, it is required to connect to the database")
+    }
+}
+
+impl std::error::Error for MissingDatabaseUrl {}
+
+impl AppConfig {
+    /// The database url, for the commands connecting to the database
+    pub fn require_database_url(&self) -> Result<&str, MissingDatabaseUrl> {
+        self.database_url.as_deref().ok_or(MissingDatabaseUrl)
+    }
 
-        Ok(AppConfig { port, database_url })
+    pub fn server_options(&self) -> ServerOptions {
+        ServerOptions {
+            keep_alive: self.keep_alive,
+            keep_alive_timeout: Duration::from_secs(self.keep_alive_timeout_secs),
+            http2: self.http2,
+        }
     }
 }
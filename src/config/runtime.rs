@@ -0,0 +1,76 @@
+use argon_core::config::env::env_or;
+use tokio::runtime::{Builder, Runtime};
+
+/// Tokio's own cap on the blocking pool, raised or lowered with `MAX_BLOCKING_THREADS`
+pub const DEFAULT_MAX_BLOCKING_THREADS: usize = 512;
+
+/// Thread counts of the tokio runtime, read before the runtime exists so it can't be
+/// part of the async [`super::app::AppConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    pub worker_threads: usize,
+    pub max_blocking_threads: usize,
+}
+
+impl RuntimeConfig {
+    /// Read before tracing is installed, so invalid values are reported on stderr
+    pub fn from_env() -> Self {
+        let stderr = tracing_subscriber::fmt().with_writer(std::io::stderr).finish();
+
+        tracing::subscriber::with_default(stderr, Self::read)
+    }
+
+    fn read() -> Self {
+        // the CPUs this process may use, which respects container CPU limits
+        let parallelism = std::thread::available_parallelism().map_or(1, |count| count.get());
+
+        RuntimeConfig {
+            worker_threads: env_or("WORKER_THREADS", parallelism).max(1),
+            max_blocking_threads: env_or("MAX_BLOCKING_THREADS", DEFAULT_MAX_BLOCKING_THREADS).max(1),
+        }
+    }
+
+    /// Multi-thread runtime builder with the configured thread counts
+    pub fn builder(&self) -> Builder {
+        let mut builder = Builder::new_multi_thread();
+
+        builder
+            .worker_threads(self.worker_threads)
+            .max_blocking_threads(self.max_blocking_threads)
+            .enable_all();
+
+        builder
+    }
+
+    pub fn build(&self) -> std::io::Result<Runtime> {
+        self.builder().build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_blocking_pool_keeps_tokio_s_cap_by_default() {
+        if std::env::var_os("MAX_BLOCKING_THREADS").is_some() {
+            return;
+        }
+
+        let config = RuntimeConfig::from_env();
+        assert_eq!(config.max_blocking_threads, DEFAULT_MAX_BLOCKING_THREADS);
+        assert!(config.worker_threads >= 1);
+    }
+
+    #[test]
+    fn the_runtime_has_the_configured_workers() {
+        let config = RuntimeConfig {
+            worker_threads: 2,
+            max_blocking_threads: 4,
+        };
+        let runtime = config.build().unwrap();
+
+        assert_eq!(runtime.metrics().num_workers(), 2);
+        assert_eq!(runtime.block_on(async { tokio::task::spawn_blocking(|| 7).await.unwrap() }), 7);
+    }
+}
@@ -1,30 +1,210 @@
+use argon_core::response::ValidationErrorResponse;
 use tokio::io::AsyncWriteExt;
 use utoipa::OpenApi;
+use utoipa::openapi::server::{Server, ServerBuilder, ServerVariableBuilder};
 
-use crate::app::controller::TestControllerApi;
 use crate::app::response::SimpleResponse;
 
+/// Components and info of the spec, the paths come from the mounted controllers
 #[derive(OpenApi)]
 #[openapi(
-    nest(
-        (path = "/", api = TestControllerApi)
-    ),
-    components(schemas(SimpleResponse)),
+    components(schemas(SimpleResponse, ValidationErrorResponse)),
     info(description = "API Docs")
 )]
 pub struct MainApiDoc;
 
-pub async fn generate_docs() -> anyhow::Result<()> {
+/// The full spec, including the servers configured from the environment
+///
+/// The paths are those of the controllers as mounted by [`crate::routes::controllers`], prefix included.
+pub fn openapi() -> anyhow::Result<utoipa::openapi::OpenApi> {
+    let _ = crate::routes::controllers()?;
+
+    let docs = MainApiDoc::openapi().merge_from(argon_core::controller::mounted_docs());
+    // schemas of every controller, each defined once
+    let mut docs = argon_core::docs::with_tags(argon_core::docs::with_schemas(docs));
+
+    let servers = servers();
+    if !servers.is_empty() {
+        docs.servers = Some(servers);
+    }
+
+    Ok(docs)
+}
+
+/// Write the spec as pretty JSON to `path`, overwriting it
+pub async fn generate_docs(path: &str) -> anyhow::Result<()> {
     let mut file = tokio::fs::OpenOptions::new()
         .write(true)
         .create(true)     // create if not exists
         .truncate(true)   // truncates existing file → overwrites
-        .open("api.json")
+        .open(path)
         .await?;
 
-    let docs = MainApiDoc::openapi().to_pretty_json()?;
+    let docs = openapi()?;
+
+    warn_unresolved_refs(&docs)?;
+
+    let docs = docs.to_pretty_json()?;
 
     file.write_all(docs.as_bytes()).await?;
 
     Ok(())
 }
+
+/// Warn about the `$ref`s of the spec that point to unregistered components
+fn warn_unresolved_refs(docs: &utoipa::openapi::OpenApi) -> anyhow::Result<()> {
+    let unresolved = unresolved_refs(docs)?;
+    if !unresolved.is_empty() {
+        tracing::warn!(
+            "the generated spec references unregistered components: {}. register them in `components(...)`",
+            unresolved.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Every `$ref` of the spec that doesn't point to a registered component, e.g. `schemas/User`
+pub fn unresolved_refs(docs: &utoipa::openapi::OpenApi) -> anyhow::Result<Vec<String>> {
+    let docs = serde_json::to_value(docs)?;
+
+    let mut refs = Vec::new();
+    collect_refs(&docs, &mut refs);
+
+    let mut unresolved: Vec<String> = refs
+        .into_iter()
+        .filter_map(|reference| {
+            let path = reference.strip_prefix("#/components/")?;
+            let (kind, name) = path.split_once('/')?;
+
+            let resolved = docs
+                .get("components")
+                .and_then(|components| components.get(kind))
+                .and_then(|components| components.get(name))
+                .is_some();
+
+            (!resolved).then(|| path.to_string())
+        })
+        .collect();
+
+    unresolved.sort();
+    unresolved.dedup();
+
+    Ok(unresolved)
+}
+
+fn collect_refs(value: &serde_json::Value, refs: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(object) => {
+            for (key, value) in object {
+                match value {
+                    serde_json::Value::String(reference) if key == "$ref" => refs.push(reference.clone()),
+                    value => collect_refs(value, refs),
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+        _ => {}
+    }
+}
+
+/// Servers listed in `API_SERVERS`, e.g.
+/// `API_SERVERS=https://{env}.example.com|Deployed,http://localhost:3000|Local`
+///
+/// `{name}` placeholders are declared from `API_SERVER_VARIABLES`, where the first
+/// value is the default, e.g. `API_SERVER_VARIABLES=env=dev|staging|prod`
+fn servers() -> Vec<Server> {
+    let Ok(servers) = std::env::var("API_SERVERS") else {
+        return Vec::new();
+    };
+
+    parse_servers(&servers, &std::env::var("API_SERVER_VARIABLES").unwrap_or_default())
+}
+
+fn parse_servers(servers: &str, variables: &str) -> Vec<Server> {
+    let variables: Vec<(&str, Vec<&str>)> = variables
+        .split(';')
+        .filter_map(|variable| {
+            let (name, values) = variable.split_once('=')?;
+            let values: Vec<&str> = values.split('|').map(str::trim).filter(|value| !value.is_empty()).collect();
+
+            if values.is_empty() {
+                tracing::warn!("server variable `{}` has no values, ignoring it", name.trim());
+                return None;
+            }
+
+            Some((name.trim(), values))
+        })
+        .collect();
+
+    servers
+        .split(',')
+        .map(str::trim)
+        .filter(|server| !server.is_empty())
+        .map(|server| {
+            let (url, description) = match server.split_once('|') {
+                Some((url, description)) => (url.trim(), Some(description.trim())),
+                None => (server, None),
+            };
+
+            let mut builder = ServerBuilder::new().url(url).description(description);
+
+            for (name, values) in &variables {
+                if url.contains(&format!("{{{}}}", name)) {
+                    builder = builder.parameter(
+                        *name,
+                        ServerVariableBuilder::new()
+                            .default_value(values[0])
+                            .enum_values(Some(values.clone())),
+                    );
+                }
+            }
+
+            builder.build()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Logs;
+
+    #[test]
+    fn servers_are_listed_with_their_variables() {
+        let servers = parse_servers(
+            "https://{env}.example.com|Deployed, http://localhost:3000|Local",
+            "env=dev|staging|prod",
+        );
+
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].description.as_deref(), Some("Deployed"));
+        assert_eq!(servers[1].url, "http://localhost:3000");
+        assert!(servers[1].variables.is_none());
+
+        let variables = serde_json::to_value(&servers[0].variables).unwrap();
+        assert_eq!(variables["env"]["default"], "dev");
+        assert_eq!(variables["env"]["enum"], serde_json::json!(["dev", "staging", "prod"]));
+    }
+
+    #[test]
+    fn unregistered_schemas_are_named_in_a_warning() {
+        let mut docs = openapi().unwrap();
+        let reference = utoipa::openapi::Ref::from_schema_name("Unregistered");
+        docs.components.get_or_insert_default().schemas.insert(
+            "Wrapper".to_string(),
+            utoipa::openapi::ObjectBuilder::new().property("inner", reference).into(),
+        );
+
+        assert_eq!(unresolved_refs(&docs).unwrap(), ["schemas/Unregistered"]);
+
+        let (logs, _guard) = Logs::capture();
+        warn_unresolved_refs(&docs).unwrap();
+        assert!(logs.contents().contains("unregistered components: schemas/Unregistered"));
+    }
+
+    #[test]
+    fn the_app_spec_has_no_unresolved_refs() {
+        assert!(unresolved_refs(&openapi().unwrap()).unwrap().is_empty());
+    }
+}
@@ -3,12 +3,27 @@ mod bootstrap;
 mod routes;
 mod docs;
 mod config;
+mod cli;
+#[cfg(test)]
+mod test_support;
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    bootstrap::init_base().await;
+fn main() -> anyhow::Result<()> {
+    // loaded first, the runtime is configured from it
+    bootstrap::init_env();
 
-    bootstrap::init_server().await?;
+    let runtime = config::runtime::RuntimeConfig::from_env().build()?;
 
-    Ok(())
+    runtime.block_on(async {
+        bootstrap::init_base().await;
+
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let result = match cli::run(&args).await {
+            Some(result) => result,
+            None => bootstrap::init_server().await,
+        };
+
+        bootstrap::shutdown_tracing();
+
+        result
+    })
 }
@@ -1,15 +1,99 @@
-use argon_core::controller::Controller;
+use std::time::Duration;
+
+use argon_core::model::audit::audit_actor;
+use argon_core::limits::{
+    ContentLengthCheck, RequestLimits, check_content_length, concurrency_limit, limit_request,
+};
+use argon_core::maintenance::{MaintenanceMode, maintenance};
+use argon_core::panic::catch_panic;
+use argon_core::plugin::{DocsPlugin, HealthPlugin, MetricsPlugin, PluginRegistry, RoutesPlugin};
+use argon_core::routing::trailing_slash;
+use argon_macros::routes;
 use axum::Router;
+use tower_http::cors::CorsLayer;
 
 use crate::app::controller::TestController;
+use crate::app::middleware::auth::{BasicAuthenticator, BasicUser, auth_middleware};
+use crate::config::app::AppConfig;
+
+/// Routers of the app, each served on its own listener
+pub struct AppRouters {
+    pub main: Router,
+    /// `/metrics` alone, set when it's served on `METRICS_PORT`
+    pub metrics: Option<Router>,
+}
+
+/// The controllers of the app under their prefixes, recording their docs for [`crate::docs::openapi`]
+pub fn controllers() -> anyhow::Result<Router> {
+    let auth = axum::middleware::from_fn(auth_middleware::<BasicAuthenticator, BasicUser>);
+    // inside auth, which provides the user recorded as the actor
+    let audit = axum::middleware::from_fn(audit_actor::<BasicUser>);
+
+    routes! {
+        "/" => [TestController] with [auth, audit]
+    }
+}
+
+pub fn routes(config: &AppConfig) -> anyhow::Result<AppRouters> {
+    let router = controllers()?;
+
+    // a separate port only makes sense over TCP
+    let metrics = MetricsPlugin::new().slow_request(Duration::from_millis(config.slow_request_ms));
+    let (metrics, metrics_router) = match config.metrics_port {
+        Some(_) if config.uds_path.is_none() => {
+            let router = metrics.endpoint();
+            (metrics.detached(), Some(router))
+        }
+        _ => (metrics, None),
+    };
+
+    let mut router = plugins(config, metrics)?
+        .mount(router)
+        .layer(axum::middleware::from_fn(catch_panic))
+        .layer(axum::middleware::from_fn_with_state(
+            config.trailing_slash,
+            trailing_slash,
+        ));
+
+    router = router.layer(axum::middleware::from_fn_with_state(
+        MaintenanceMode::new().allowed_ips(config.maintenance_allowed_ips.clone()),
+        maintenance,
+    ));
+
+    // outside maintenance, so its 503 still carries the CORS headers
+    if config.permissive_cors {
+        router = router.layer(CorsLayer::permissive());
+    }
+
+    let router = router.layer(axum::middleware::from_fn_with_state(
+        ContentLengthCheck::default(),
+        check_content_length,
+    ));
+
+    // so oversized requests are rejected before anything else runs
+    let router = router.layer(axum::middleware::from_fn_with_state(
+        RequestLimits::new(config.max_uri_len, config.max_header_bytes),
+        limit_request,
+    ));
+
+    // outermost, shedding the load before any other work is done
+    let router = router.layer(concurrency_limit(config.max_concurrent_requests));
+
+    Ok(AppRouters {
+        main: router,
+        metrics: metrics_router,
+    })
+}
+
+/// Optional modules mounted on top of the app routes, outside of the auth layer
+fn plugins(config: &AppConfig, metrics: MetricsPlugin) -> anyhow::Result<PluginRegistry> {
+    let registry = PluginRegistry::new().register(HealthPlugin::new().profile(config.profile));
+
+    let registry = if config.docs {
+        registry.register(DocsPlugin::new(crate::docs::openapi()?))
+    } else {
+        registry
+    };
 
-pub fn routes() -> Router {
-    Router::new()
-        .nest("/", TestController::router())
-        .layer(axum::middleware::from_fn(
-            crate::app::middleware::auth::auth_middleware::<
-                crate::app::middleware::auth::BasicAuthenticator,
-                crate::app::middleware::auth::BasicUser,
-            >,
-        ))
+    Ok(registry.register(RoutesPlugin::new()).register(metrics))
 }
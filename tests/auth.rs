@@ -0,0 +1,610 @@
+mod common;
+
+use argon_core::auth::{
+    AuthenticatableUser, Authenticator, HasPermissions, Permission, RequirePermission, auth_middleware_with_state,
+};
+use argon_core::testing::TestApp;
+use axum::{
+    Extension, Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+struct User {
+    id: i32,
+    name: String,
+}
+
+impl AuthenticatableUser for User {
+    type Username = String;
+    type Password = String;
+    type Id = i32;
+
+    fn get_username(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_password(&self) -> String {
+        String::new()
+    }
+
+    fn get_id(&self) -> i32 {
+        self.id
+    }
+}
+
+/// alice may edit posts, nothing more
+impl HasPermissions for User {
+    fn has_permission(&self, permission: &str) -> bool {
+        self.name == "alice" && permission == "posts.edit"
+    }
+}
+
+struct EditPosts;
+
+impl Permission for EditPosts {
+    const NAME: &'static str = "posts.edit";
+}
+
+struct DeletePosts;
+
+impl Permission for DeletePosts {
+    const NAME: &'static str = "posts.delete";
+}
+
+/// Accepts the `letmein` token as alice
+#[derive(Clone)]
+struct StubAuthenticator;
+
+impl Authenticator<User> for StubAuthenticator {
+    type Token = String;
+
+    async fn attempt(&self, _username: String, _password: String) -> anyhow::Result<User> {
+        anyhow::bail!("not supported")
+    }
+
+    async fn generate_token(&self, _user: User) -> String {
+        "letmein".to_string()
+    }
+
+    fn verify_header_name(&self) -> &'static str {
+        "Authorization"
+    }
+
+    async fn verify(&self, token: &str) -> Result<User, StatusCode> {
+        match token {
+            "letmein" => Ok(User {
+                id: 1,
+                name: "alice".to_string(),
+            }),
+            _ => Err(StatusCode::UNAUTHORIZED),
+        }
+    }
+}
+
+/// [`StubAuthenticator`] reading the token from the `session` cookie too
+#[derive(Clone)]
+struct CookieSessionAuthenticator;
+
+impl Authenticator<User> for CookieSessionAuthenticator {
+    type Token = String;
+
+    async fn attempt(&self, username: String, password: String) -> anyhow::Result<User> {
+        StubAuthenticator.attempt(username, password).await
+    }
+
+    async fn generate_token(&self, user: User) -> String {
+        StubAuthenticator.generate_token(user).await
+    }
+
+    fn verify_header_name(&self) -> &'static str {
+        "Authorization"
+    }
+
+    fn verify_cookie_name(&self) -> Option<&'static str> {
+        Some("session")
+    }
+
+    async fn verify(&self, token: &str) -> Result<User, StatusCode> {
+        StubAuthenticator.verify(token).await
+    }
+}
+
+async fn whoami(Extension(user): Extension<User>) -> String {
+    user.name
+}
+
+fn request(uri: &str, token: Option<&str>) -> Request<Body> {
+    let mut request = Request::get(uri);
+    if let Some(token) = token {
+        request = request.header("Authorization", token);
+    }
+
+    request.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn state_middleware_authenticates_with_the_router_state() {
+    let router = Router::new()
+        .route("/me", get(whoami))
+        .layer(axum::middleware::from_fn_with_state(
+            StubAuthenticator,
+            auth_middleware_with_state::<StubAuthenticator, User>,
+        ));
+    let app = TestApp::new(router);
+
+    let response = app.request(request("/me", Some("letmein"))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_text(response).await, "alice");
+
+    assert_eq!(app.request(request("/me", Some("wrong"))).await.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(app.request(request("/me", None)).await.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn a_session_cookie_authenticates() {
+    let router = Router::new()
+        .route("/me", get(whoami))
+        .layer(axum::middleware::from_fn_with_state(
+            CookieSessionAuthenticator,
+            auth_middleware_with_state::<CookieSessionAuthenticator, User>,
+        ));
+    let app = TestApp::new(router);
+
+    let with_cookie = |cookie: &str| {
+        Request::get("/me")
+            .header("Cookie", cookie)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.request(with_cookie("theme=dark; session=letmein")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_text(response).await, "alice");
+
+    assert_eq!(app.request(with_cookie("session=wrong")).await.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(app.request(with_cookie("other=letmein")).await.status(), StatusCode::UNAUTHORIZED);
+}
+
+async fn edit(guard: RequirePermission<EditPosts, User>) -> String {
+    format!("{} may {}", guard.user.name, guard.permission())
+}
+
+async fn delete(_guard: RequirePermission<DeletePosts, User>) -> &'static str {
+    "deleted"
+}
+
+#[tokio::test]
+async fn permission_guards_grant_and_deny() {
+    let guarded = Router::new()
+        .route("/edit", get(edit))
+        .route("/delete", get(delete));
+    let app = TestApp::new(guarded.clone().layer(axum::middleware::from_fn_with_state(
+        StubAuthenticator,
+        auth_middleware_with_state::<StubAuthenticator, User>,
+    )));
+
+    let response = app.request(request("/edit", Some("letmein"))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_text(response).await, "alice may posts.edit");
+
+    assert_eq!(app.request(request("/delete", Some("letmein"))).await.status(), StatusCode::FORBIDDEN);
+
+    // without the auth middleware there is no user to check
+    let anonymous = TestApp::new(guarded);
+    assert_eq!(anonymous.request(request("/edit", None)).await.status(), StatusCode::UNAUTHORIZED);
+}
+
+mod basic_credentials {
+    use argon_core::auth::BasicCredentials;
+    use argon_core::testing::TestApp;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+    };
+
+    async fn login(credentials: BasicCredentials) -> String {
+        format!("{}:{}", credentials.username, credentials.password)
+    }
+
+    async fn authorize(value: Option<&str>) -> (StatusCode, String) {
+        let app = TestApp::new(Router::new().route("/login", get(login)));
+
+        let mut request = Request::get("/login");
+        if let Some(value) = value {
+            request = request.header("Authorization", value);
+        }
+
+        let response = app.request(request.body(Body::empty()).unwrap()).await;
+
+        (response.status(), super::common::body_text(response).await)
+    }
+
+    #[tokio::test]
+    async fn a_valid_header_is_decoded() {
+        // base64("alice:open:sesame"), the password may hold colons
+        assert_eq!(
+            authorize(Some("Basic YWxpY2U6b3BlbjpzZXNhbWU=")).await,
+            (StatusCode::OK, "alice:open:sesame".to_string())
+        );
+        assert_eq!(authorize(Some("basic YWxpY2U6b3BlbjpzZXNhbWU=")).await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn malformed_headers_answer_401() {
+        for value in [
+            None,
+            Some("Bearer YWxpY2U6c2VjcmV0"),
+            Some("Basic"),
+            Some("Basic not-base64!"),
+            // base64("alice"), without the colon
+            Some("Basic YWxpY2U="),
+            // invalid utf-8
+            Some("Basic //79"),
+        ] {
+            assert_eq!(authorize(value).await.0, StatusCode::UNAUTHORIZED, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn the_password_is_never_printed() {
+        let credentials = BasicCredentials::parse("Basic YWxpY2U6c2VjcmV0").unwrap();
+
+        assert_eq!(
+            format!("{:?}", credentials),
+            r#"BasicCredentials { username: "alice", password: "***" }"#
+        );
+    }
+}
+
+mod token_expiry {
+    use std::time::{Duration, SystemTime};
+
+    use argon_core::auth::{Authenticator, jwt_expiry, token_expiry_header};
+    use argon_core::testing::TestApp;
+    use axum::{
+        Extension, Router,
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+    };
+
+    use super::User;
+
+    // {"sub":"1","exp":1767225600}, that is 2026-01-01T00:00:00Z
+    const EXPIRING: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiIxIiwiZXhwIjoxNzY3MjI1NjAwfQ.c2ln";
+    // {"sub":"1"}
+    const NEVER_EXPIRING: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiIxIn0.c2ln";
+
+    /// Reads the expiry of its JWTs from their `exp` claim
+    #[derive(Clone)]
+    struct JwtAuthenticator;
+
+    impl Authenticator<User> for JwtAuthenticator {
+        type Token = String;
+
+        async fn attempt(&self, _username: String, _password: String) -> anyhow::Result<User> {
+            anyhow::bail!("not supported")
+        }
+
+        async fn generate_token(&self, _user: User) -> String {
+            EXPIRING.to_string()
+        }
+
+        fn verify_header_name(&self) -> &'static str {
+            "Authorization"
+        }
+
+        async fn verify(&self, _token: &str) -> Result<User, StatusCode> {
+            Err(StatusCode::UNAUTHORIZED)
+        }
+
+        fn token_expiry(&self, token: &str) -> Option<SystemTime> {
+            jwt_expiry(token)
+        }
+    }
+
+    #[test]
+    fn the_exp_claim_is_the_expiry() {
+        let expiry = SystemTime::UNIX_EPOCH + Duration::from_secs(1_767_225_600);
+
+        assert_eq!(JwtAuthenticator.token_expiry(EXPIRING), Some(expiry));
+        assert_eq!(jwt_expiry(&format!("Bearer {}", EXPIRING)), Some(expiry));
+        assert_eq!(JwtAuthenticator.token_expiry(NEVER_EXPIRING), None);
+        assert_eq!(JwtAuthenticator.token_expiry("not-a-jwt"), None);
+    }
+
+    async fn expires_at(token: Option<&str>) -> Option<String> {
+        let router = Router::new()
+            .route("/me", get(|| async { "me" }))
+            .layer(axum::middleware::from_fn(token_expiry_header::<JwtAuthenticator, User>))
+            .layer(Extension(JwtAuthenticator));
+
+        let mut request = Request::get("/me");
+        if let Some(token) = token {
+            request = request.header("Authorization", token);
+        }
+        let response = TestApp::new(router).request(request.body(Body::empty()).unwrap()).await;
+
+        response
+            .headers()
+            .get("X-Token-Expires-At")
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn the_expiry_is_sent_as_a_header() {
+        assert_eq!(expires_at(Some(EXPIRING)).await.as_deref(), Some("2026-01-01T00:00:00+00:00"));
+        assert_eq!(expires_at(Some(NEVER_EXPIRING)).await, None);
+        assert_eq!(expires_at(None).await, None);
+    }
+}
+
+mod webhook {
+    use argon_core::auth::verify_hmac;
+    use argon_core::extract::RawBody;
+    use argon_core::testing::TestApp;
+    use axum::{
+        Router,
+        body::Body,
+        extract::DefaultBodyLimit,
+        http::{HeaderMap, Request, StatusCode},
+        routing::post,
+    };
+
+    const SECRET: &str = "key";
+    const PAYLOAD: &str = "The quick brown fox jumps over the lazy dog";
+    // HMAC-SHA256 of `PAYLOAD` with `SECRET`
+    const SIGNATURE: &str = "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8";
+
+    async fn webhook(headers: HeaderMap, RawBody(body): RawBody) -> StatusCode {
+        let signature = headers.get("x-hub-signature-256").and_then(|value| value.to_str().ok());
+
+        match signature {
+            Some(signature) if verify_hmac(SECRET, signature, &body) => StatusCode::NO_CONTENT,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    async fn deliver(signature: &str, body: &str) -> StatusCode {
+        let router = Router::new()
+            .route("/webhook", post(webhook))
+            .layer(DefaultBodyLimit::max(64));
+        let request = Request::post("/webhook")
+            .header("x-hub-signature-256", signature)
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        TestApp::new(router).request(request).await.status()
+    }
+
+    #[tokio::test]
+    async fn a_signed_payload_is_accepted_and_a_tampered_one_rejected() {
+        assert_eq!(deliver(SIGNATURE, PAYLOAD).await, StatusCode::NO_CONTENT);
+        assert_eq!(deliver(SIGNATURE, "The quick brown fox jumps over the lazy cat").await, StatusCode::UNAUTHORIZED);
+        assert_eq!(deliver("sha256=not-hex", PAYLOAD).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn bodies_over_the_limit_answer_413() {
+        assert_eq!(deliver(SIGNATURE, &"x".repeat(65)).await, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn the_prefix_is_optional() {
+        let bare = SIGNATURE.trim_start_matches("sha256=");
+
+        assert!(verify_hmac(SECRET, bare, PAYLOAD.as_bytes()));
+        assert!(!verify_hmac("other", bare, PAYLOAD.as_bytes()));
+    }
+}
+
+mod audience {
+    use argon_core::auth::{Authenticator, RequiredAudience, jwt_audiences, require_audience};
+    use argon_core::testing::TestApp;
+    use axum::{
+        Extension, Router,
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+    };
+
+    use super::User;
+
+    // {"sub":"1","aud":"billing"}
+    const BILLING: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiIxIiwiYXVkIjoiYmlsbGluZyJ9.c2ln";
+    // {"sub":"1","aud":["search","billing"]}
+    const SEARCH_AND_BILLING: &str =
+        "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiIxIiwiYXVkIjpbInNlYXJjaCIsImJpbGxpbmciXX0.c2ln";
+    // {"sub":"1","aud":"search"}
+    const SEARCH: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiIxIiwiYXVkIjoic2VhcmNoIn0.c2ln";
+    // {"sub":"1"}
+    const NO_AUDIENCE: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiIxIn0.c2ln";
+
+    /// Reads the audiences of its JWTs from their `aud` claim
+    #[derive(Clone)]
+    struct JwtAuthenticator;
+
+    impl Authenticator<User> for JwtAuthenticator {
+        type Token = String;
+
+        async fn attempt(&self, _username: String, _password: String) -> anyhow::Result<User> {
+            anyhow::bail!("not supported")
+        }
+
+        async fn generate_token(&self, _user: User) -> String {
+            BILLING.to_string()
+        }
+
+        fn verify_header_name(&self) -> &'static str {
+            "Authorization"
+        }
+
+        async fn verify(&self, _token: &str) -> Result<User, StatusCode> {
+            Err(StatusCode::UNAUTHORIZED)
+        }
+
+        fn token_audiences(&self, token: &str) -> Option<Vec<String>> {
+            jwt_audiences(token)
+        }
+    }
+
+    async fn status_for(token: Option<&str>) -> StatusCode {
+        let router = Router::new()
+            .route("/invoices", get(|| async { "invoices" }))
+            .layer(axum::middleware::from_fn_with_state(
+                RequiredAudience::new("billing"),
+                require_audience::<JwtAuthenticator, User>,
+            ))
+            .layer(Extension(JwtAuthenticator));
+
+        let mut request = Request::get("/invoices");
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+
+        TestApp::new(router).request(request.body(Body::empty()).unwrap()).await.status()
+    }
+
+    #[tokio::test]
+    async fn a_token_for_the_audience_passes() {
+        assert_eq!(status_for(Some(BILLING)).await, StatusCode::OK);
+        assert_eq!(status_for(Some(SEARCH_AND_BILLING)).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn other_or_missing_audiences_answer_403() {
+        assert_eq!(status_for(Some(SEARCH)).await, StatusCode::FORBIDDEN);
+        assert_eq!(status_for(Some(NO_AUDIENCE)).await, StatusCode::FORBIDDEN);
+        assert_eq!(status_for(None).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn the_aud_claim_is_a_string_or_a_list() {
+        assert_eq!(jwt_audiences(BILLING), Some(vec!["billing".to_string()]));
+        assert_eq!(
+            jwt_audiences(SEARCH_AND_BILLING),
+            Some(vec!["search".to_string(), "billing".to_string()])
+        );
+        assert_eq!(jwt_audiences(NO_AUDIENCE), None);
+    }
+}
+
+mod sliding_session {
+    use std::time::{Duration, SystemTime};
+
+    use argon_core::auth::{Authenticator, SlidingSession, auth_middleware};
+    use argon_core::testing::TestApp;
+    use axum::{
+        Extension, Router,
+        body::Body,
+        http::{Request, Response, StatusCode, header},
+        routing::get,
+    };
+
+    use super::User;
+
+    /// Accepts `expiring` tokens, which expire in a minute, and `fresh` ones, which
+    /// expire in an hour
+    #[derive(Clone)]
+    struct ExpiringAuthenticator;
+
+    impl Authenticator<User> for ExpiringAuthenticator {
+        type Token = String;
+
+        async fn attempt(&self, _username: String, _password: String) -> anyhow::Result<User> {
+            anyhow::bail!("not supported")
+        }
+
+        async fn generate_token(&self, _user: User) -> String {
+            "renewed".to_string()
+        }
+
+        fn verify_header_name(&self) -> &'static str {
+            "Authorization"
+        }
+
+        async fn verify(&self, _token: &str) -> Result<User, StatusCode> {
+            Ok(User {
+                id: 1,
+                name: "alice".to_string(),
+            })
+        }
+
+        fn token_expiry(&self, token: &str) -> Option<SystemTime> {
+            match token {
+                "Bearer expiring" => Some(SystemTime::now() + Duration::from_secs(60)),
+                "Bearer fresh" => Some(SystemTime::now() + Duration::from_secs(3600)),
+                _ => None,
+            }
+        }
+
+        fn token_value(&self, token: String) -> Option<String> {
+            Some(token)
+        }
+    }
+
+    async fn call(session: SlidingSession, uri: &str, token: &str) -> Response<Body> {
+        let router = Router::new()
+            .route("/me", get(|| async { "me" }))
+            .route("/broken", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+            .layer(axum::middleware::from_fn(auth_middleware::<ExpiringAuthenticator, User>))
+            .layer(Extension(session))
+            .layer(Extension(ExpiringAuthenticator));
+        let request = Request::get(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+
+        TestApp::new(router).request(request).await
+    }
+
+    fn session() -> SlidingSession {
+        SlidingSession::new(Duration::from_secs(5 * 60)).cookie("session")
+    }
+
+    #[tokio::test]
+    async fn a_token_about_to_expire_is_refreshed() {
+        let response = call(session(), "/me", "expiring").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-refreshed-token"], "renewed");
+        assert_eq!(
+            response.headers()[header::SET_COOKIE],
+            "session=renewed; Path=/; HttpOnly; SameSite=Lax; Secure"
+        );
+    }
+
+    #[tokio::test]
+    async fn a_fresh_token_is_left_alone() {
+        let response = call(session(), "/me", "fresh").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get("x-refreshed-token").is_none());
+        assert!(response.headers().get(header::SET_COOKIE).is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_responses_are_not_refreshed() {
+        let response = call(session(), "/broken", "expiring").await;
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.headers().get("x-refreshed-token").is_none());
+        assert!(response.headers().get(header::SET_COOKIE).is_none());
+    }
+
+    #[tokio::test]
+    async fn the_cookie_can_drop_secure_for_local_http() {
+        let response = call(session().secure(false).without_header(), "/me", "expiring").await;
+
+        assert!(response.headers().get("x-refreshed-token").is_none());
+        assert_eq!(
+            response.headers()[header::SET_COOKIE],
+            "session=renewed; Path=/; HttpOnly; SameSite=Lax"
+        );
+    }
+}
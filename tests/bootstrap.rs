@@ -0,0 +1,68 @@
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use argon_core::bootstrap::StartupHooks;
+use argon_core::testing::empty_state;
+
+#[derive(Clone)]
+struct Cache(&'static str);
+
+#[tokio::test]
+async fn hooks_run_once_in_registration_order() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let order = Arc::new(Mutex::new(Vec::new()));
+
+    let hooks = StartupHooks::new()
+        .on_startup("first", {
+            let (runs, order) = (runs.clone(), order.clone());
+            |state| async move {
+                runs.fetch_add(1, Ordering::SeqCst);
+                order.lock().unwrap().push("first");
+
+                Ok(state.with(Cache("warm")))
+            }
+        })
+        .on_startup("second", {
+            let order = order.clone();
+            |state| async move {
+                // sees what the previous hook registered
+                assert!(state.get::<Cache>().is_some());
+                order.lock().unwrap().push("second");
+
+                Ok(state)
+            }
+        });
+
+    // nothing runs until the server is about to start
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+    let state = hooks.run(empty_state()).await.unwrap();
+
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert_eq!(*order.lock().unwrap(), ["first", "second"]);
+    assert_eq!(state.get::<Cache>().unwrap().0, "warm");
+}
+
+#[tokio::test]
+async fn a_failing_hook_aborts_the_startup() {
+    let ran_after = Arc::new(AtomicUsize::new(0));
+
+    let result = StartupHooks::new()
+        .on_startup("broken", |_state| async { anyhow::bail!("no cache server") })
+        .on_startup("after", {
+            let ran_after = ran_after.clone();
+            |state| async move {
+                ran_after.fetch_add(1, Ordering::SeqCst);
+
+                Ok(state)
+            }
+        })
+        .run(empty_state())
+        .await;
+
+    let err = result.err().unwrap();
+    assert_eq!(err.to_string(), "startup hook `broken` failed");
+    assert_eq!(ran_after.load(Ordering::SeqCst), 0);
+}
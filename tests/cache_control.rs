@@ -0,0 +1,58 @@
+use argon_core::testing::TestApp;
+use argon_macros::{cache_control, controller, get};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+
+pub struct CatalogController;
+
+#[controller]
+impl CatalogController {
+    #[get("/catalog")]
+    #[cache_control(public, max_age = 60, s_maxage = 300)]
+    async fn index() -> String {
+        "catalog".to_string()
+    }
+
+    #[get("/cart")]
+    #[cache_control(private, no_store)]
+    async fn cart() -> String {
+        "cart".to_string()
+    }
+
+    #[get("/catalog/missing")]
+    #[cache_control(public, max_age = 60)]
+    async fn missing() -> StatusCode {
+        StatusCode::NOT_FOUND
+    }
+}
+
+async fn cache_control_of(uri: &str) -> (StatusCode, Option<String>) {
+    let app = TestApp::controller::<CatalogController>();
+    let response = app.request(Request::get(uri).body(Body::empty()).unwrap()).await;
+
+    let cache_control = response
+        .headers()
+        .get(header::CACHE_CONTROL)
+        .map(|value| value.to_str().unwrap().to_string());
+
+    (response.status(), cache_control)
+}
+
+#[tokio::test]
+async fn successful_responses_carry_the_declared_directives() {
+    assert_eq!(
+        cache_control_of("/catalog").await,
+        (StatusCode::OK, Some("public, max-age=60, s-maxage=300".to_string()))
+    );
+    assert_eq!(
+        cache_control_of("/cart").await,
+        (StatusCode::OK, Some("private, no-store".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn failed_responses_are_left_uncached() {
+    assert_eq!(cache_control_of("/catalog/missing").await, (StatusCode::NOT_FOUND, None));
+}
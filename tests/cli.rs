@@ -0,0 +1,45 @@
+use std::process::Command;
+
+/// A working directory of its own for each test, with an empty `.env`
+fn workdir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("argon-cli-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(".env"), "").unwrap();
+
+    dir
+}
+
+#[test]
+fn openapi_is_written_without_a_database_url() {
+    let dir = workdir("openapi");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_argon"))
+        .args(["openapi", "spec.json"])
+        .current_dir(&dir)
+        .env_remove("DATABASE_URL")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let spec: serde_json::Value = serde_json::from_slice(&std::fs::read(dir.join("spec.json")).unwrap()).unwrap();
+    assert!(spec["openapi"].is_string());
+    assert!(!spec["paths"].as_object().unwrap().is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unknown_commands_fail() {
+    let dir = workdir("unknown");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_argon"))
+        .arg("serve-forever")
+        .current_dir(&dir)
+        .env_remove("DATABASE_URL")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown command `serve-forever`"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,59 @@
+use argon_core::config::Profile;
+use argon_core::plugin::{HealthPlugin, PluginRegistry};
+use argon_core::testing::TestApp;
+use axum::{Router, http::StatusCode};
+use serde_json::json;
+
+#[test]
+fn defaults_differ_between_development_and_production() {
+    let development = Profile::Development;
+    let production = Profile::Production;
+
+    assert!(development.docs() && !production.docs());
+    assert!(development.permissive_cors() && !production.permissive_cors());
+    assert!(!development.json_logs() && production.json_logs());
+
+    // test runs like production, with readable logs
+    assert_eq!(
+        (Profile::Test.docs(), Profile::Test.permissive_cors(), Profile::Test.json_logs()),
+        (false, false, false)
+    );
+}
+
+// the only test of this binary touching the environment
+#[test]
+fn the_profile_is_read_from_app_env() {
+    let from_env = |value: Option<&str>| {
+        // SAFETY: no other test of this binary reads or writes the environment
+        unsafe {
+            match value {
+                Some(value) => std::env::set_var("APP_ENV", value),
+                None => std::env::remove_var("APP_ENV"),
+            }
+        }
+
+        Profile::from_env()
+    };
+
+    assert_eq!(from_env(None).unwrap(), Profile::Development);
+    assert_eq!(from_env(Some(" ")).unwrap(), Profile::Development);
+    assert_eq!(from_env(Some("prod")).unwrap(), Profile::Production);
+    assert_eq!(from_env(Some("Test")).unwrap(), Profile::Test);
+
+    let err = from_env(Some("staging")).unwrap_err().to_string();
+    assert!(err.contains("cannot parse `APP_ENV`: unknown profile `staging`"), "{}", err);
+}
+
+#[tokio::test]
+async fn health_reports_the_profile() {
+    let app = TestApp::new(
+        PluginRegistry::new()
+            .register(HealthPlugin::new().profile(Profile::Production))
+            .mount(Router::new()),
+    );
+
+    assert_eq!(
+        app.get_json("/health").await,
+        (StatusCode::OK, json!({ "status": "ok", "profile": "production" }))
+    );
+}
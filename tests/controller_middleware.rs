@@ -0,0 +1,103 @@
+mod common;
+
+use argon_core::controller::Controller;
+use argon_core::testing::TestApp;
+use argon_macros::{controller, get};
+use axum::{
+    Extension, Router,
+    body::Body,
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+
+/// Names of the middleware a request went through, in order
+#[derive(Clone, Default)]
+struct Trace(Vec<&'static str>);
+
+async fn require_token(request: Request, next: Next) -> Result<Response, StatusCode> {
+    match request.headers().get("X-Token") {
+        Some(token) if token == "secret" => Ok(next.run(request).await),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+async fn trace(mut request: Request, next: Next) -> Response {
+    let mut trace = request.extensions().get::<Trace>().cloned().unwrap_or_default();
+    trace.0.push("trace");
+    request.extensions_mut().insert(trace);
+
+    next.run(request).await
+}
+
+async fn audit(mut request: Request, next: Next) -> Response {
+    let mut trace = request.extensions().get::<Trace>().cloned().unwrap_or_default();
+    trace.0.push("audit");
+    request.extensions_mut().insert(trace);
+
+    next.run(request).await
+}
+
+pub struct AdminController;
+
+#[controller(middleware(require_token, trace, audit))]
+impl AdminController {
+    #[get("/admin/users")]
+    async fn users(Extension(_trace): Extension<Trace>) -> String {
+        _trace.0.join(",")
+    }
+
+    #[get("/admin/stats")]
+    async fn stats() -> String {
+        "stats".to_string()
+    }
+}
+
+pub struct PublicController;
+
+#[controller]
+impl PublicController {
+    #[get("/status")]
+    async fn status() -> String {
+        "up".to_string()
+    }
+}
+
+fn app() -> TestApp {
+    TestApp::new(Router::new().merge(AdminController::router()).merge(PublicController::router()))
+}
+
+fn request(uri: &str, token: Option<&str>) -> Request {
+    let mut request = axum::http::Request::get(uri);
+    if let Some(token) = token {
+        request = request.header("X-Token", token);
+    }
+
+    request.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn controller_middleware_protects_every_route_of_the_controller() {
+    let app = app();
+
+    for uri in ["/admin/users", "/admin/stats"] {
+        assert_eq!(app.request(request(uri, None)).await.status(), StatusCode::UNAUTHORIZED, "{}", uri);
+        assert_eq!(app.request(request(uri, Some("secret"))).await.status(), StatusCode::OK, "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn other_controllers_stay_open() {
+    let response = app().request(request("/status", None)).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_text(response).await, "up");
+}
+
+#[tokio::test]
+async fn middleware_runs_in_declaration_order() {
+    let response = app().request(request("/admin/users", Some("secret"))).await;
+
+    assert_eq!(common::body_text(response).await, "trace,audit");
+}
@@ -0,0 +1,59 @@
+use argon_core::controller::Controller;
+use argon_macros::{controller, get};
+use axum::Router;
+
+pub struct TemplateController;
+
+#[controller(setup = Self::load_templates)]
+impl TemplateController {
+    #[get("/pages")]
+    async fn pages() -> String {
+        "pages".to_string()
+    }
+
+    fn load_templates(_router: Router) -> anyhow::Result<Router> {
+        anyhow::bail!("templates/ is missing")
+    }
+}
+
+pub struct PageController;
+
+#[controller(setup = Self::ready)]
+impl PageController {
+    #[get("/home")]
+    async fn home() -> String {
+        "home".to_string()
+    }
+
+    fn ready(router: Router) -> anyhow::Result<Router> {
+        Ok(router)
+    }
+}
+
+fn routes() -> anyhow::Result<Router> {
+    Ok(Router::new()
+        .merge(PageController::try_router()?)
+        .merge(TemplateController::try_router()?))
+}
+
+#[test]
+fn a_failing_setup_bubbles_up() {
+    let err = TemplateController::try_router().unwrap_err();
+    assert_eq!(err.to_string(), "templates/ is missing");
+
+    let err = routes().unwrap_err();
+    assert_eq!(err.to_string(), "templates/ is missing");
+
+    assert!(TemplateController::try_mount(Router::new(), "/api").is_err());
+}
+
+#[test]
+fn a_successful_setup_builds_the_router() {
+    assert!(PageController::try_router().is_ok());
+}
+
+#[test]
+#[should_panic(expected = "setup of `TemplateController` failed")]
+fn router_panics_when_the_setup_fails() {
+    let _ = TemplateController::router();
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use argon_core::db::check_database;
+use sea_orm::Database;
+
+#[tokio::test]
+async fn a_fresh_database_fails_the_check_naming_the_missing_table() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+
+    let err = check_database(&db, &["user"]).await.unwrap_err().to_string();
+
+    assert_eq!(
+        err,
+        "the `user` table is missing, run the migrations first with `cargo run -p migration -- up`"
+    );
+}
+
+#[tokio::test]
+async fn a_migrated_database_passes_the_check() {
+    let db = common::sqlite_with_posts().await;
+
+    check_database(&db, &["post"]).await.unwrap();
+    assert!(check_database(&db, &["post", "user"]).await.is_err());
+}
+
+#[tokio::test]
+async fn a_closed_connection_fails_the_check() {
+    let db = common::sqlite_with_posts().await;
+    db.clone().close().await.unwrap();
+
+    let err = check_database(&db, &["post"]).await.unwrap_err().to_string();
+
+    assert!(err.starts_with("the database doesn't answer, check `DATABASE_URL`"), "{}", err);
+}
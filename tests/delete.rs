@@ -0,0 +1,49 @@
+mod common;
+
+use argon_core::repository::Repository;
+use argon_core::response::Deleted;
+use argon_core::state::Db;
+use argon_core::testing::{TestApp, mock_state};
+use axum::{
+    Router,
+    body::Body,
+    extract::Path,
+    http::{Request, StatusCode},
+    routing::delete,
+};
+use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+
+use common::post;
+
+async fn delete_post(Db(db): Db, Path(id): Path<i32>) -> Result<Deleted, StatusCode> {
+    let deleted = Repository::<post::Entity>::new(db)
+        .delete(id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Deleted::or_not_found(deleted)
+}
+
+async fn delete_with_rows_affected(rows_affected: u64) -> StatusCode {
+    let mock = MockDatabase::new(DatabaseBackend::Postgres).append_exec_results([MockExecResult {
+        last_insert_id: 0,
+        rows_affected,
+    }]);
+    let app = TestApp::new(Router::new().route("/posts/{id}", delete(delete_post))).with_state(mock_state(mock));
+
+    let response = app
+        .request(Request::delete("/posts/1").body(Body::empty()).unwrap())
+        .await;
+
+    response.status()
+}
+
+#[tokio::test]
+async fn deleting_an_existing_row_answers_204() {
+    assert_eq!(delete_with_rows_affected(1).await, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn deleting_a_missing_row_answers_404() {
+    assert_eq!(delete_with_rows_affected(0).await, StatusCode::NOT_FOUND);
+}
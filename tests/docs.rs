@@ -0,0 +1,520 @@
+mod common;
+
+use argon_core::controller::Controller;
+use argon_core::testing::TestApp;
+use argon_macros::{controller, deprecated_route, get, openapi_example, post, skip_docs, utoipa_response};
+use axum::Json;
+use serde_json::{Value, json};
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Post {
+    pub id: i32,
+    pub title: String,
+}
+
+pub struct PostController;
+
+#[controller]
+impl PostController {
+    #[get("/posts/{id}")]
+    #[utoipa_response(status = 200, body = Post)]
+    #[openapi_example(json!({ "id": 1, "title": "Hello" }))]
+    async fn show() -> Json<Post> {
+        Json(Post { id: 1, title: "Hello".to_string() })
+    }
+
+    #[post("/posts")]
+    #[utoipa_response(status = 201, body = Post)]
+    #[openapi_example(request = json!({ "id": 0, "title": "Draft" }))]
+    async fn create(Json(_post): Json<Post>) -> Json<Post> {
+        Json(Post { id: 2, title: "Draft".to_string() })
+    }
+}
+
+/// The spec of the controller nested at `/`, as the app's doc does
+fn spec<C: Controller>() -> Value {
+    serde_json::to_value(utoipa::openapi::OpenApi::default().nest("/", C::api_doc())).unwrap()
+}
+
+fn operation<'a>(spec: &'a Value, path: &str, method: &str) -> &'a Value {
+    let operation = &spec["paths"][path][method];
+    assert!(operation.is_object(), "no {} {} in {}", method, path, spec["paths"]);
+
+    operation
+}
+
+#[test]
+fn openapi_examples_appear_in_the_spec() {
+    let spec = spec::<PostController>();
+
+    let show = operation(&spec, "/posts/{id}", "get");
+    assert_eq!(
+        show["responses"]["200"]["content"]["application/json"]["example"],
+        json!({ "id": 1, "title": "Hello" })
+    );
+
+    let create = operation(&spec, "/posts", "post");
+    assert_eq!(
+        create["requestBody"]["content"]["application/json"]["example"],
+        json!({ "id": 0, "title": "Draft" })
+    );
+}
+
+pub struct WebhookController;
+
+#[controller]
+impl WebhookController {
+    #[get("/status")]
+    #[utoipa_response(status = 200, body = String)]
+    async fn status() -> String {
+        "up".to_string()
+    }
+
+    #[post("/webhooks/stripe")]
+    #[skip_docs]
+    async fn stripe() -> String {
+        "received".to_string()
+    }
+}
+
+#[tokio::test]
+async fn skipped_handlers_route_but_stay_out_of_the_spec() {
+    let spec = spec::<WebhookController>();
+
+    operation(&spec, "/status", "get");
+    assert!(spec["paths"].get("/webhooks/stripe").is_none());
+    assert_eq!(WebhookController::operation_count(), 1);
+
+    let app = TestApp::controller::<WebhookController>();
+    let response = app
+        .request(axum::http::Request::post("/webhooks/stripe").body(axum::body::Body::empty()).unwrap())
+        .await;
+    assert_eq!(common::body_text(response).await, "received");
+
+    let response = app
+        .request(axum::http::Request::get("/status").body(axum::body::Body::empty()).unwrap())
+        .await;
+    assert_eq!(common::body_text(response).await, "up");
+}
+
+#[test]
+fn operation_paths_leave_out_skipped_routes() {
+    assert_eq!(WebhookController::operation_paths(), [("get", "/status")]);
+    assert_eq!(WebhookController::routes().len(), 2);
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub struct Product {
+    pub id: i32,
+    pub name: argon_core::model::MultilangField,
+}
+
+pub struct ProductController;
+
+#[controller]
+impl ProductController {
+    #[get("/products/{id}")]
+    #[utoipa_response(status = 200, body = Product)]
+    async fn show_product() -> Json<Product> {
+        Json(Product {
+            id: 1,
+            name: argon_core::model::MultilangField::new(vec![("en".to_string(), "Tea".to_string()).into()]),
+        })
+    }
+}
+
+#[test]
+fn multilang_fields_reference_a_reusable_schema() {
+    let docs = argon_core::docs::with_schemas_of(
+        utoipa::openapi::OpenApi::default().nest("/", ProductController::api_doc()),
+        concat!(module_path!(), "::ProductController"),
+    );
+    let spec = serde_json::to_value(docs).unwrap();
+    let schemas = &spec["components"]["schemas"];
+
+    assert_eq!(
+        schemas["Product"]["properties"]["name"]["$ref"],
+        "#/components/schemas/MultilangField"
+    );
+    assert_eq!(schemas["MultilangField"]["type"], "array");
+    assert_eq!(schemas["MultilangField"]["items"]["$ref"], "#/components/schemas/LangField");
+    assert!(schemas["LangField"]["properties"]["lang"].is_object());
+}
+
+#[test]
+fn api_doc_holds_the_controller_paths() {
+    let doc = PostController::api_doc();
+
+    let mut paths: Vec<&str> = doc.paths.paths.keys().map(String::as_str).collect();
+    paths.sort();
+    // relative, to be nested by the caller
+    assert_eq!(paths, ["posts", "posts/{id}"]);
+
+    // the generated `...Api` struct is kept and documents the same paths
+    use utoipa::OpenApi;
+    let legacy = PostControllerApi::openapi();
+    assert_eq!(legacy.paths.paths.keys().collect::<Vec<_>>(), doc.paths.paths.keys().collect::<Vec<_>>());
+}
+
+pub struct LegacyController;
+
+#[controller]
+impl LegacyController {
+    #[get("/v1/feed")]
+    #[deprecated_route(sunset = "2025-12-31")]
+    #[utoipa_response(status = 200, body = String)]
+    async fn feed() -> String {
+        "feed".to_string()
+    }
+
+    #[get("/v1/stream")]
+    #[deprecated_route]
+    #[utoipa_response(status = 200, body = String)]
+    async fn stream() -> String {
+        "stream".to_string()
+    }
+}
+
+#[tokio::test]
+async fn deprecated_routes_are_flagged_in_the_spec_and_the_headers() {
+    let spec = spec::<LegacyController>();
+    assert_eq!(operation(&spec, "/v1/feed", "get")["deprecated"], true);
+    assert_eq!(operation(&spec, "/v1/stream", "get")["deprecated"], true);
+
+    let app = TestApp::controller::<LegacyController>();
+
+    let response = app
+        .request(axum::http::Request::get("/v1/feed").body(axum::body::Body::empty()).unwrap())
+        .await;
+    assert_eq!(response.headers()["deprecation"], "true");
+    assert_eq!(response.headers()["sunset"], "Wed, 31 Dec 2025 00:00:00 GMT");
+
+    let response = app
+        .request(axum::http::Request::get("/v1/stream").body(axum::body::Body::empty()).unwrap())
+        .await;
+    assert_eq!(response.headers()["deprecation"], "true");
+    assert!(response.headers().get("sunset").is_none());
+}
+
+mod shared_schemas {
+    use argon_core::controller::Controller;
+    use argon_macros::{controller, get, utoipa_response};
+    use axum::Json;
+    use utoipa::openapi::OpenApi;
+
+    #[derive(serde::Serialize, utoipa::ToSchema)]
+    pub struct SimpleResponse {
+        pub message: String,
+    }
+
+    pub struct InboxController;
+
+    #[controller]
+    impl InboxController {
+        #[get("/inbox")]
+        #[utoipa_response(status = 200, body = SimpleResponse)]
+        async fn inbox() -> Json<SimpleResponse> {
+            Json(SimpleResponse { message: "inbox".to_string() })
+        }
+    }
+
+    pub struct OutboxController;
+
+    #[controller]
+    impl OutboxController {
+        #[get("/outbox")]
+        #[utoipa_response(status = 200, body = SimpleResponse)]
+        async fn outbox() -> Json<SimpleResponse> {
+            Json(SimpleResponse { message: "outbox".to_string() })
+        }
+    }
+
+    #[test]
+    fn a_schema_of_two_controllers_is_defined_once() {
+        let docs = OpenApi::default()
+            .nest("/", InboxController::api_doc())
+            .nest("/", OutboxController::api_doc());
+        let docs = serde_json::to_value(argon_core::docs::with_schemas(docs)).unwrap();
+
+        let schemas = docs["components"]["schemas"].as_object().unwrap();
+        assert_eq!(schemas.keys().filter(|name| *name == "SimpleResponse").count(), 1);
+        assert_eq!(schemas["SimpleResponse"]["required"], serde_json::json!(["message"]));
+
+        for path in ["/inbox", "/outbox"] {
+            assert_eq!(
+                docs["paths"][path]["get"]["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+                "#/components/schemas/SimpleResponse"
+            );
+        }
+    }
+
+    #[test]
+    fn each_controller_registers_it() {
+        for controller in [
+            concat!(module_path!(), "::InboxController"),
+            concat!(module_path!(), "::OutboxController"),
+        ] {
+            let schemas = argon_core::docs::registered_schemas(Some(controller));
+
+            assert!(schemas.contains_key("SimpleResponse"), "{}: {:?}", controller, schemas.keys());
+        }
+    }
+}
+
+mod same_named_controllers {
+    use argon_core::controller::Controller;
+
+    mod v1 {
+        use argon_macros::{controller, get, utoipa_response};
+        use axum::Json;
+
+        #[derive(serde::Serialize, utoipa::ToSchema)]
+        pub struct UserV1 {
+            pub name: String,
+        }
+
+        pub struct UserController;
+
+        #[controller]
+        impl UserController {
+            #[get("/users")]
+            #[utoipa_response(status = 200, body = UserV1)]
+            async fn show() -> Json<UserV1> {
+                Json(UserV1 { name: "ada".to_string() })
+            }
+        }
+    }
+
+    mod v2 {
+        use argon_macros::{controller, get, utoipa_response};
+        use axum::Json;
+
+        #[derive(serde::Serialize, utoipa::ToSchema)]
+        pub struct UserV2 {
+            pub full_name: String,
+        }
+
+        pub struct UserController;
+
+        #[controller]
+        impl UserController {
+            #[get("/users")]
+            #[utoipa_response(status = 200, body = UserV2)]
+            async fn show() -> Json<UserV2> {
+                Json(UserV2 { full_name: "Ada Lovelace".to_string() })
+            }
+        }
+    }
+
+    fn schema_names(docs: utoipa::openapi::OpenApi) -> Vec<String> {
+        docs.components.unwrap().schemas.into_keys().collect()
+    }
+
+    #[test]
+    fn each_version_documents_only_its_own_schemas() {
+        assert_eq!(schema_names(v1::UserController::api_doc()), ["UserV1"]);
+        assert_eq!(schema_names(v2::UserController::api_doc()), ["UserV2"]);
+    }
+}
+
+mod standard_errors {
+    use argon_macros::{controller, get, utoipa_errors, utoipa_response};
+    use serde_json::Value;
+
+    pub struct AccountController;
+
+    #[controller(errors(unauthorized, internal))]
+    impl AccountController {
+        #[get("/accounts/{id}")]
+        #[utoipa_response(status = 200, body = String)]
+        #[utoipa_errors(not_found)]
+        async fn account() -> String {
+            "account".to_string()
+        }
+
+        #[get("/accounts/me")]
+        #[utoipa_response(status = 200, body = String)]
+        #[utoipa_response(status = 401, body = String, description = "Session expired")]
+        async fn me() -> String {
+            "me".to_string()
+        }
+    }
+
+    fn responses(path: &str) -> Value {
+        let spec = super::spec::<AccountController>();
+
+        super::operation(&spec, path, "get")["responses"].clone()
+    }
+
+    #[test]
+    fn the_shorthand_expands_to_the_standard_responses() {
+        let responses = responses("/accounts/{id}");
+
+        for (status, description) in [("401", "Unauthorized"), ("404", "Not found"), ("500", "Internal server error")] {
+            assert_eq!(responses[status]["description"], description, "{}", status);
+            assert_eq!(
+                responses[status]["content"]["application/json"]["schema"]["$ref"],
+                "#/components/schemas/ErrorResponse"
+            );
+        }
+        assert!(responses["200"].is_object());
+    }
+
+    #[test]
+    fn a_status_declared_by_hand_wins_over_the_controller_set() {
+        let responses = responses("/accounts/me");
+
+        assert_eq!(responses["401"]["description"], "Session expired");
+        assert_eq!(responses["500"]["description"], "Internal server error");
+        assert!(responses.get("404").is_none());
+    }
+}
+
+mod tags {
+    use argon_core::docs::{register_tag, with_tags};
+    use utoipa::openapi::{OpenApi, tag::TagBuilder};
+
+    fn tags(docs: OpenApi) -> Vec<(String, Option<String>)> {
+        docs.tags
+            .unwrap_or_default()
+            .into_iter()
+            .map(|tag| (tag.name, tag.description))
+            .collect()
+    }
+
+    // a single test, the registered tags are shared by the whole binary
+    #[test]
+    fn registered_tags_are_listed_with_their_description() {
+        assert!(with_tags(OpenApi::default()).tags.is_none());
+
+        register_tag("Users", "User endpoints");
+        register_tag("Users", "User management endpoints");
+        register_tag("Posts", "Blog posts");
+
+        let mut docs = OpenApi::default();
+        docs.tags = Some(vec![TagBuilder::new().name("Posts").description(Some("Own description")).build()]);
+
+        assert_eq!(
+            tags(with_tags(docs)),
+            [
+                ("Posts".to_string(), Some("Own description".to_string())),
+                ("Users".to_string(), Some("User management endpoints".to_string())),
+            ]
+        );
+    }
+}
+
+mod response_examples {
+    use argon_macros::{controller, get, utoipa_response};
+    use axum::Json;
+    use serde_json::json;
+
+    use super::{Post, operation, spec};
+
+    #[derive(serde::Serialize, utoipa::ToSchema)]
+    pub struct Missing {
+        pub message: String,
+    }
+
+    pub struct DraftController;
+
+    #[controller]
+    impl DraftController {
+        #[get("/drafts/{id}")]
+        #[utoipa_response(status = 200, body = Post, example = json!({ "id": 3, "title": "Draft" }))]
+        #[utoipa_response(status = 404, body = Missing, examples(
+            ("Deleted" = (summary = "The draft was deleted", value = json!({ "message": "Not Found" }))),
+        ))]
+        async fn draft() -> Json<Post> {
+            Json(Post { id: 3, title: "Draft".to_string() })
+        }
+    }
+
+    #[test]
+    fn response_examples_appear_in_the_spec() {
+        let spec = spec::<DraftController>();
+        let responses = &operation(&spec, "/drafts/{id}", "get")["responses"];
+
+        assert_eq!(
+            responses["200"]["content"]["application/json"]["example"],
+            json!({ "id": 3, "title": "Draft" })
+        );
+
+        let deleted = &responses["404"]["content"]["application/json"]["examples"]["Deleted"];
+        assert_eq!(deleted["summary"], "The draft was deleted");
+        assert_eq!(deleted["value"], json!({ "message": "Not Found" }));
+    }
+}
+
+mod generated_examples {
+    use argon_core::docs::example_for;
+    use argon_macros::{controller, get, utoipa_response};
+    use axum::Json;
+    use serde_json::json;
+
+    use super::{operation, spec};
+
+    #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Status {
+        Draft,
+        Published,
+    }
+
+    #[derive(serde::Serialize, utoipa::ToSchema)]
+    pub struct Comment {
+        pub body: String,
+        // utoipa needs it to list the schemas of a recursive type
+        #[schema(no_recursion)]
+        pub replies: Vec<Comment>,
+    }
+
+    #[derive(serde::Serialize, utoipa::ToSchema)]
+    pub struct Article {
+        pub id: i64,
+        pub title: String,
+        pub summary: Option<String>,
+        pub published: bool,
+        pub status: Status,
+        #[schema(example = "ada@example.com")]
+        pub author: String,
+        pub comments: Vec<Comment>,
+    }
+
+    pub struct ArticleController;
+
+    #[controller]
+    impl ArticleController {
+        #[get("/articles/{id}")]
+        #[utoipa_response(status = 200, body = Article)]
+        async fn article() -> Json<Article> {
+            unimplemented!()
+        }
+    }
+
+    fn expected() -> serde_json::Value {
+        json!({
+            "id": 0,
+            "title": "string",
+            "summary": "string",
+            "published": false,
+            "status": "draft",
+            "author": "ada@example.com",
+            // a comment doesn't nest itself
+            "comments": [{ "body": "string", "replies": [] }],
+        })
+    }
+
+    #[test]
+    fn an_example_is_built_from_the_schema() {
+        assert_eq!(example_for::<Article>(), expected());
+    }
+
+    #[test]
+    fn a_response_without_an_example_gets_the_generated_one() {
+        let spec = spec::<ArticleController>();
+        let response = &operation(&spec, "/articles/{id}", "get")["responses"]["200"];
+
+        assert_eq!(response["content"]["application/json"]["example"], expected());
+    }
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use std::path::PathBuf;
+
+use argon_core::response::FileDownload;
+use argon_core::testing::TestApp;
+use axum::{
+    Router,
+    body::Body,
+    http::{HeaderMap, Request, StatusCode, header},
+    routing::get,
+};
+
+const CONTENT: &[u8] = b"0123456789abcdefghij";
+
+/// A file of [`CONTENT`] only this test process uses
+fn fixture(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("argon-{}-{}", std::process::id(), name));
+    std::fs::write(&path, CONTENT).unwrap();
+
+    path
+}
+
+fn app(path: PathBuf) -> TestApp {
+    TestApp::new(Router::new().route(
+        "/download",
+        get(move |headers: HeaderMap| async move {
+            FileDownload::from_path(&path).await.unwrap().with_range(&headers)
+        }),
+    ))
+}
+
+#[tokio::test]
+async fn a_full_download_streams_the_whole_file() {
+    let app = app(fixture("full.txt"));
+
+    let response = app.request(Request::get("/download").body(Body::empty()).unwrap()).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::ACCEPT_RANGES], "bytes");
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "text/plain");
+    assert_eq!(common::body_bytes(response).await, CONTENT);
+}
+
+#[tokio::test]
+async fn a_range_request_returns_the_slice() {
+    let app = app(fixture("range.txt"));
+
+    let request = Request::get("/download")
+        .header(header::RANGE, "bytes=5-9")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.request(request).await;
+
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 5-9/20");
+    assert_eq!(common::body_bytes(response).await, b"56789");
+}
+
+#[tokio::test]
+async fn an_unsatisfiable_range_answers_416() {
+    let app = app(fixture("unsatisfiable.txt"));
+
+    let request = Request::get("/download")
+        .header(header::RANGE, "bytes=50-60")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.request(request).await;
+
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes */20");
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use argon_core::config::env::{env_or, env_required};
+
+// a single test, the only one of this binary touching the environment
+#[test]
+fn variables_are_parsed_or_defaulted() {
+    // SAFETY: no other test of this binary reads or writes the environment
+    unsafe {
+        std::env::set_var("ARGON_TEST_PORT", "8080");
+        std::env::set_var("ARGON_TEST_WORKERS", "many");
+        std::env::set_var("ARGON_TEST_BLANK", " ");
+        std::env::remove_var("ARGON_TEST_UNSET");
+    }
+
+    // present and valid
+    assert_eq!(env_or("ARGON_TEST_PORT", 3000_u16), 8080);
+    assert_eq!(env_required::<u16>("ARGON_TEST_PORT").unwrap(), 8080);
+
+    // present but invalid, falling back with a warning
+    let (logs, guard) = common::Logs::capture();
+    assert_eq!(env_or("ARGON_TEST_WORKERS", 4_usize), 4);
+    drop(guard);
+    let logs = logs.contents();
+    assert!(logs.contains("WARN"), "{}", logs);
+    assert!(logs.contains("cannot parse `ARGON_TEST_WORKERS`. defaulting to 4"), "{}", logs);
+
+    let err = env_required::<usize>("ARGON_TEST_WORKERS").unwrap_err().to_string();
+    assert!(err.starts_with("cannot parse `ARGON_TEST_WORKERS`"), "{}", err);
+
+    // missing, blank counts as unset
+    assert_eq!(env_or("ARGON_TEST_UNSET", 3000_u16), 3000);
+    assert_eq!(env_or("ARGON_TEST_BLANK", 3000_u16), 3000);
+    for key in ["ARGON_TEST_UNSET", "ARGON_TEST_BLANK"] {
+        let err = env_required::<String>(key).unwrap_err().to_string();
+        assert_eq!(err, format!("cannot read `{}`: it is not set", key));
+    }
+}
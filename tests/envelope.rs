@@ -0,0 +1,96 @@
+mod common;
+
+use argon_core::response::envelope;
+use argon_core::testing::TestApp;
+use axum::{
+    Json, Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use serde_json::{Value, json};
+
+fn app() -> TestApp {
+    let router = Router::new()
+        .route("/ok", get(|| async { Json(json!({ "x": 1 })) }))
+        .route(
+            "/missing",
+            get(|| async { (StatusCode::NOT_FOUND, Json(json!({ "message": "not found", "detail": null }))) }),
+        )
+        .route(
+            "/stream",
+            get(|| async {
+                let chunks = futures_util::stream::iter([Ok::<_, std::io::Error>("{\"x\":"), Ok("1}")]);
+                ([("content-type", "application/json")], Body::from_stream(chunks))
+            }),
+        )
+        .layer(axum::middleware::from_fn(envelope));
+
+    TestApp::new(router)
+}
+
+async fn get_json(app: &TestApp, uri: &str) -> (StatusCode, Value) {
+    let response = app.request(Request::get(uri).body(Body::empty()).unwrap()).await;
+
+    (response.status(), common::body_json(response).await)
+}
+
+#[tokio::test]
+async fn successful_json_is_wrapped_with_meta() {
+    let (status, body) = get_json(&app(), "/ok").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!({ "x": 1 }));
+    assert!(body["meta"]["timestamp"].is_string());
+}
+
+#[tokio::test]
+async fn errors_keep_their_shape() {
+    let (status, body) = get_json(&app(), "/missing").await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body, json!({ "message": "not found", "detail": null }));
+}
+
+#[tokio::test]
+async fn streamed_bodies_pass_through() {
+    let (_, body) = get_json(&app(), "/stream").await;
+
+    assert_eq!(body, json!({ "x": 1 }));
+}
+
+mod raw_response {
+    use argon_core::controller::Controller;
+    use argon_core::response::envelope;
+    use argon_core::testing::TestApp;
+    use argon_macros::{controller, get, raw_response};
+    use axum::{Json, Router, http::StatusCode};
+    use serde_json::{Value, json};
+
+    pub struct WebhookController;
+
+    #[controller]
+    impl WebhookController {
+        #[get("/enveloped")]
+        async fn enveloped() -> Json<Value> {
+            Json(json!({ "x": 1 }))
+        }
+
+        #[get("/raw")]
+        #[raw_response]
+        async fn raw() -> Json<Value> {
+            Json(json!({ "x": 1 }))
+        }
+    }
+
+    #[tokio::test]
+    async fn only_handlers_without_the_attribute_are_wrapped() {
+        let app = TestApp::new(WebhookController::mount(Router::new(), "/").layer(axum::middleware::from_fn(envelope)));
+
+        let (status, body) = app.get_json("/enveloped").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"], json!({ "x": 1 }));
+
+        assert_eq!(app.get_json("/raw").await, (StatusCode::OK, json!({ "x": 1 })));
+    }
+}
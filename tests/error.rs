@@ -0,0 +1,73 @@
+mod common;
+
+use anyhow::Context;
+use argon_core::error::HandlerError;
+use argon_core::testing::TestApp;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use serde_json::json;
+
+use common::Logs;
+
+async fn report() -> Result<String, HandlerError> {
+    let rows: u32 = "many".parse().context("cannot count the report rows")?;
+
+    Ok(rows.to_string())
+}
+
+#[tokio::test]
+async fn a_propagated_anyhow_error_is_logged_and_answers_500() {
+    let (logs, _guard) = Logs::capture();
+    let app = TestApp::new(Router::new().route("/report", get(report)));
+
+    let response = app.request(Request::get("/report").body(Body::empty()).unwrap()).await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        common::body_json(response).await,
+        json!({ "message": "internal server error", "detail": null })
+    );
+
+    // the whole chain is logged, while the client only gets the generic message
+    let logs = logs.contents();
+    assert!(logs.contains("cannot count the report rows"), "{}", logs);
+    assert!(logs.contains("invalid digit"), "{}", logs);
+}
+
+fn status_of(err: sea_orm::DbErr) -> StatusCode {
+    use axum::response::IntoResponse;
+
+    HandlerError::from(err).into_response().status()
+}
+
+#[test]
+fn db_errors_map_to_statuses() {
+    use sea_orm::{DbErr, RuntimeErr};
+
+    assert_eq!(status_of(DbErr::RecordNotFound("post 7".to_string())), StatusCode::NOT_FOUND);
+    assert_eq!(
+        status_of(DbErr::Conn(RuntimeErr::Internal("connection refused".to_string()))),
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+    assert_eq!(
+        status_of(DbErr::Custom("syntax error".to_string())),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+}
+
+#[tokio::test]
+async fn a_missing_record_answers_404_json() {
+    async fn show() -> Result<String, HandlerError> {
+        Err(sea_orm::DbErr::RecordNotFound("post 7".to_string()))?
+    }
+
+    let app = TestApp::new(Router::new().route("/posts/7", get(show)));
+    let response = app.request(Request::get("/posts/7").body(Body::empty()).unwrap()).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(common::body_json(response).await, json!({ "message": "not found", "detail": null }));
+}
@@ -0,0 +1,537 @@
+mod common;
+
+use argon_core::extract::{Json, Path};
+use argon_core::testing::TestApp;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    routing::{get, post},
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct NewPost {
+    title: String,
+}
+
+async fn create(Json(post): Json<NewPost>) -> String {
+    post.title
+}
+
+async fn show(Path(id): Path<u32>) -> String {
+    id.to_string()
+}
+
+fn app() -> TestApp {
+    TestApp::new(
+        Router::new()
+            .route("/posts", post(create))
+            .route("/posts/{id}", get(show)),
+    )
+}
+
+#[tokio::test]
+async fn malformed_json_answers_a_json_error() {
+    let request = Request::post("/posts")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from("{\"title\": "))
+        .unwrap();
+    let response = app().request(request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = common::body_json(response).await;
+    assert!(body["message"].as_str().is_some_and(|m| !m.is_empty()));
+}
+
+#[tokio::test]
+async fn a_bad_path_segment_answers_a_json_error() {
+    let response = app()
+        .request(Request::get("/posts/abc").body(Body::empty()).unwrap())
+        .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = common::body_json(response).await;
+    assert!(body["message"].as_str().is_some_and(|m| !m.is_empty()));
+}
+
+#[tokio::test]
+async fn well_formed_requests_pass_through() {
+    let request = Request::post("/posts")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(r#"{"title": "hello"}"#))
+        .unwrap();
+    let response = app().request(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_text(response).await, "hello");
+
+    let response = app()
+        .request(Request::get("/posts/7").body(Body::empty()).unwrap())
+        .await;
+    assert_eq!(common::body_text(response).await, "7");
+}
+
+mod client_ip {
+    use std::net::{IpAddr, SocketAddr};
+
+    use argon_core::extract::{ClientIp, ForwardedHeader, TrustedProxies};
+    use argon_core::testing::TestApp;
+    use axum::{
+        Router,
+        body::Body,
+        extract::ConnectInfo,
+        http::Request,
+        routing::get,
+    };
+
+    const PROXY: &str = "10.0.0.1";
+
+    async fn client_ip(peer: &str, headers: &[(&str, &str)]) -> String {
+        client_ip_through(ForwardedHeader::XForwardedFor, peer, headers).await
+    }
+
+    async fn client_ip_through(header: ForwardedHeader, peer: &str, headers: &[(&str, &str)]) -> String {
+        let router = Router::new().route("/ip", get(|ClientIp(ip): ClientIp| async move { ip.to_string() }));
+        let app = TestApp::new(router)
+            .with_extension(TrustedProxies::new(vec![PROXY.parse::<IpAddr>().unwrap()]).header(header));
+
+        let mut request = Request::get("/ip");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let mut request = request.body(Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 4000)));
+
+        super::common::body_text(app.request(request).await).await
+    }
+
+    #[tokio::test]
+    async fn a_direct_connection_is_the_client() {
+        assert_eq!(client_ip("203.0.113.7", &[]).await, "203.0.113.7");
+    }
+
+    #[tokio::test]
+    async fn trusted_proxies_forward_the_client_address() {
+        assert_eq!(client_ip(PROXY, &[("X-Forwarded-For", "203.0.113.7")]).await, "203.0.113.7");
+        assert_eq!(
+            client_ip_through(ForwardedHeader::Forwarded, PROXY, &[("Forwarded", "for=\"[2001:db8::1]:4711\"")]).await,
+            "2001:db8::1"
+        );
+        assert_eq!(
+            client_ip_through(ForwardedHeader::XRealIp, PROXY, &[("X-Real-IP", "203.0.113.9")]).await,
+            "203.0.113.9"
+        );
+        // an entry prepended by the client is skipped, the proxy saw 203.0.113.7
+        assert_eq!(
+            client_ip(PROXY, &[("X-Forwarded-For", "1.1.1.1, 203.0.113.7")]).await,
+            "203.0.113.7"
+        );
+    }
+
+    #[tokio::test]
+    async fn only_the_header_of_the_proxies_is_read() {
+        // the proxy appends to `X-Forwarded-For` and passes the forged `Forwarded` through
+        let headers = [("Forwarded", "for=1.2.3.4"), ("X-Forwarded-For", "203.0.113.7")];
+        assert_eq!(client_ip(PROXY, &headers).await, "203.0.113.7");
+
+        assert_eq!(client_ip(PROXY, &[("X-Real-IP", "1.2.3.4")]).await, PROXY);
+    }
+
+    #[tokio::test]
+    async fn a_hop_that_does_not_parse_ends_the_walk() {
+        assert_eq!(
+            client_ip(PROXY, &[("X-Forwarded-For", "203.0.113.7, unknown")]).await,
+            PROXY
+        );
+        assert_eq!(
+            client_ip(PROXY, &[("X-Forwarded-For", "203.0.113.7, unknown, 10.0.0.1")]).await,
+            PROXY
+        );
+        assert_eq!(
+            client_ip_through(ForwardedHeader::Forwarded, PROXY, &[("Forwarded", "for=1.2.3.4, for=_hidden")]).await,
+            PROXY
+        );
+    }
+
+    #[tokio::test]
+    async fn headers_from_untrusted_peers_are_ignored() {
+        assert_eq!(client_ip("203.0.113.7", &[("X-Forwarded-For", "1.1.1.1")]).await, "203.0.113.7");
+        assert_eq!(client_ip("203.0.113.7", &[("X-Real-IP", "1.1.1.1")]).await, "203.0.113.7");
+    }
+}
+
+mod required_header {
+    use argon_core::controller::Controller;
+    use argon_core::extract::{HeaderName, RequiredHeader};
+    use argon_core::testing::TestApp;
+    use argon_macros::{controller, get, utoipa_response};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use serde_json::json;
+
+    pub struct ClientVersion;
+
+    impl HeaderName for ClientVersion {
+        const NAME: &'static str = "X-Client-Version";
+    }
+
+    pub struct FeedController;
+
+    #[controller]
+    impl FeedController {
+        #[get("/feed")]
+        #[utoipa_response(status = 200, body = String)]
+        async fn feed(_version: RequiredHeader<ClientVersion, u32>) -> String {
+            format!("feed for v{}", _version.value)
+        }
+    }
+
+    async fn feed(version: Option<&str>) -> (StatusCode, String) {
+        let mut request = Request::get("/feed");
+        if let Some(version) = version {
+            request = request.header("X-Client-Version", version);
+        }
+
+        let response = TestApp::new(FeedController::router())
+            .request(request.body(Body::empty()).unwrap())
+            .await;
+
+        (response.status(), super::common::body_text(response).await)
+    }
+
+    #[tokio::test]
+    async fn a_valid_header_is_parsed() {
+        assert_eq!(feed(Some(" 3 ")).await, (StatusCode::OK, "feed for v3".to_string()));
+    }
+
+    #[tokio::test]
+    async fn a_missing_header_answers_400() {
+        let (status, body) = feed(None).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("missing `X-Client-Version` header"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn an_unparseable_header_answers_400() {
+        let (status, body) = feed(Some("three")).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("invalid `X-Client-Version` header"), "{}", body);
+    }
+
+    #[test]
+    fn the_header_is_documented_as_a_required_parameter() {
+        let docs = serde_json::to_value(FeedController::api_doc()).unwrap();
+
+        assert_eq!(
+            docs["paths"]["feed"]["get"]["parameters"],
+            json!([{
+                "name": "X-Client-Version",
+                "in": "header",
+                "required": true,
+                "schema": {"type": "integer", "format": "int32", "minimum": 0}
+            }])
+        );
+    }
+}
+
+mod path_segments {
+    use argon_core::extract::Path;
+    use argon_core::testing::TestApp;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+    };
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct PostPath {
+        uid: u32,
+        pid: u64,
+    }
+
+    async fn by_tuple(Path((uid, pid)): Path<(u32, u64)>) -> String {
+        format!("{}/{}", uid, pid)
+    }
+
+    async fn by_struct(Path(path): Path<PostPath>) -> String {
+        format!("{}/{}", path.uid, path.pid)
+    }
+
+    async fn fetch(uri: &str) -> (StatusCode, serde_json::Value) {
+        let app = TestApp::new(
+            Router::new()
+                .route("/users/{uid}/posts/{pid}", get(by_tuple))
+                .route("/authors/{uid}/posts/{pid}", get(by_struct)),
+        );
+        let response = app.request(Request::get(uri).body(Body::empty()).unwrap()).await;
+
+        match response.status() {
+            StatusCode::OK => (StatusCode::OK, super::common::body_text(response).await.into()),
+            status => (status, super::common::body_json(response).await["message"].clone()),
+        }
+    }
+
+    #[tokio::test]
+    async fn the_failing_segment_is_named() {
+        assert_eq!(
+            fetch("/users/7/posts/abc").await,
+            (StatusCode::BAD_REQUEST, "invalid path segment `pid`: `abc` is not a valid u64".into())
+        );
+        assert_eq!(
+            fetch("/users/x/posts/1").await,
+            (StatusCode::BAD_REQUEST, "invalid path segment `uid`: `x` is not a valid u32".into())
+        );
+        assert_eq!(
+            fetch("/authors/7/posts/abc").await,
+            (StatusCode::BAD_REQUEST, "invalid path segment `pid`: `abc` is not a valid u64".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn valid_segments_pass() {
+        assert_eq!(fetch("/users/7/posts/8").await, (StatusCode::OK, "7/8".into()));
+        assert_eq!(fetch("/authors/7/posts/8").await, (StatusCode::OK, "7/8".into()));
+    }
+}
+
+mod json_limits {
+    use argon_core::extract::{Json, JsonLimits};
+    use argon_core::testing::TestApp;
+    use axum::{
+        Extension, Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+        routing::post,
+    };
+
+    async fn accept(Json(_value): Json<serde_json::Value>) -> &'static str {
+        "accepted"
+    }
+
+    async fn send(body: String) -> (StatusCode, String) {
+        let app = TestApp::new(
+            Router::new()
+                .route("/documents", post(accept))
+                .layer(Extension(JsonLimits::new(4, 10))),
+        );
+        let request = Request::post("/documents")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        let response = app.request(request).await;
+
+        (response.status(), super::common::body_text(response).await)
+    }
+
+    fn nested(depth: usize) -> String {
+        "[".repeat(depth) + &"]".repeat(depth)
+    }
+
+    fn object(fields: usize) -> String {
+        let fields: Vec<_> = (0..fields).map(|field| format!("\"f{}\": 1", field)).collect();
+
+        format!("{{{}}}", fields.join(", "))
+    }
+
+    #[tokio::test]
+    async fn an_over_deep_body_answers_400() {
+        assert_eq!(send(nested(4)).await.0, StatusCode::OK);
+
+        let (status, body) = send(nested(5)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("nested deeper than 4 levels"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn an_over_large_object_answers_413() {
+        assert_eq!(send(object(10)).await.0, StatusCode::OK);
+
+        let (status, body) = send(object(11)).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(body.contains("more than 10 fields"), "{}", body);
+    }
+
+    #[test]
+    fn brackets_and_colons_inside_strings_are_not_counted() {
+        let limits = JsonLimits::new(1, 1);
+
+        assert!(limits.check(br#"{"url": "http://[::1]/{a:b}\"[["}"#).is_ok());
+    }
+}
+
+mod conditional_headers {
+    use argon_core::controller::Controller;
+    use argon_core::extract::{ETagMatch, EntityTag, IfMatch, IfNoneMatch};
+    use argon_core::testing::TestApp;
+    use argon_macros::{controller, get, put, utoipa_response};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+
+    pub struct DocumentController;
+
+    #[controller]
+    impl DocumentController {
+        #[put("/documents/1")]
+        #[utoipa_response(status = 200, body = String)]
+        async fn update(_if_match: IfMatch) -> String {
+            format!("{:?}", _if_match.0)
+        }
+
+        #[get("/documents/1")]
+        #[utoipa_response(status = 200, body = String)]
+        async fn show(_if_none_match: Option<IfNoneMatch>) -> String {
+            format!("{:?}", _if_none_match.map(|header| header.0))
+        }
+    }
+
+    async fn update(if_match: Option<&str>) -> (StatusCode, String) {
+        let mut request = Request::put("/documents/1");
+        if let Some(if_match) = if_match {
+            request = request.header("If-Match", if_match);
+        }
+
+        let response = TestApp::new(DocumentController::router())
+            .request(request.body(Body::empty()).unwrap())
+            .await;
+
+        (response.status(), super::common::body_text(response).await)
+    }
+
+    #[tokio::test]
+    async fn entity_tags_and_the_wildcard_are_parsed() {
+        let tags = |tags: Vec<EntityTag>| (StatusCode::OK, format!("{:?}", ETagMatch::Tags(tags)));
+
+        assert_eq!(update(Some("\"abc\"")).await, tags(vec![EntityTag::strong("abc")]));
+        assert_eq!(
+            update(Some("\"abc\", W/\"def\"")).await,
+            tags(vec![EntityTag::strong("abc"), EntityTag::weak("def")])
+        );
+        assert_eq!(update(Some("*")).await, (StatusCode::OK, format!("{:?}", ETagMatch::Any)));
+    }
+
+    #[tokio::test]
+    async fn malformed_or_missing_headers_are_rejected() {
+        for value in ["abc", "*, \"abc\"", ""] {
+            assert_eq!(update(Some(value)).await.0, StatusCode::BAD_REQUEST, "{:?}", value);
+        }
+
+        let (status, body) = update(None).await;
+        assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+        assert!(body.contains("missing `If-Match` header"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn an_optional_header_may_be_missing() {
+        let response = TestApp::new(DocumentController::router())
+            .request(Request::get("/documents/1").body(Body::empty()).unwrap())
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(super::common::body_text(response).await, "None");
+    }
+
+    #[test]
+    fn if_match_compares_strongly_and_if_none_match_weakly() {
+        let current = EntityTag::strong("abc");
+        let weak = ETagMatch::Tags(vec![EntityTag::weak("abc")]);
+
+        assert!(!IfMatch(weak.clone()).matches(&current));
+        assert!(IfNoneMatch(weak).matches(&current));
+        assert!(IfMatch(ETagMatch::Any).matches(&current));
+    }
+
+    #[test]
+    fn the_headers_are_documented_as_parameters() {
+        let docs = serde_json::to_value(DocumentController::api_doc()).unwrap();
+        let parameter = |method: &str| docs["paths"]["documents/1"][method]["parameters"][0].clone();
+
+        assert_eq!(parameter("put")["name"], "If-Match");
+        assert_eq!(parameter("put")["in"], "header");
+        assert_eq!(parameter("put")["required"], true);
+
+        assert_eq!(parameter("get")["name"], "If-None-Match");
+        assert_eq!(parameter("get")["required"], false);
+    }
+}
+
+mod json_or_form {
+    use argon_core::controller::Controller;
+    use argon_core::extract::Body;
+    use argon_core::testing::TestApp;
+    use argon_macros::{controller, post, utoipa_response};
+    use axum::http::{Request, StatusCode, header};
+
+    #[derive(serde::Deserialize, utoipa::ToSchema)]
+    pub struct Subscription {
+        pub email: String,
+        pub weekly: bool,
+    }
+
+    pub struct NewsletterController;
+
+    #[controller]
+    impl NewsletterController {
+        #[post("/subscribe")]
+        #[utoipa_response(status = 200, body = String)]
+        async fn subscribe(Body(_subscription): Body<Subscription>) -> String {
+            format!("{} weekly={}", _subscription.email, _subscription.weekly)
+        }
+    }
+
+    async fn subscribe(content_type: &str, body: &'static str) -> (StatusCode, String) {
+        let request = Request::post("/subscribe")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(axum::body::Body::from(body))
+            .unwrap();
+        let response = TestApp::new(NewsletterController::router()).request(request).await;
+
+        (response.status(), super::common::body_text(response).await)
+    }
+
+    #[tokio::test]
+    async fn json_and_form_bodies_read_the_same() {
+        let json = subscribe("application/json", r#"{"email":"ada@example.com","weekly":true}"#).await;
+        let form = subscribe(
+            "application/x-www-form-urlencoded",
+            "email=ada%40example.com&weekly=true",
+        )
+        .await;
+
+        assert_eq!(json.0, StatusCode::OK);
+        assert_eq!(json.1, "ada@example.com weekly=true");
+        assert_eq!(json, form);
+    }
+
+    #[tokio::test]
+    async fn other_content_types_answer_415() {
+        assert_eq!(subscribe("text/plain", "ada@example.com").await.0, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[tokio::test]
+    async fn unreadable_bodies_are_rejected() {
+        assert_eq!(subscribe("application/json", "{").await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            subscribe("application/x-www-form-urlencoded", "email=ada%40example.com&weekly=maybe").await.0,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[test]
+    fn both_content_types_are_documented() {
+        let docs = serde_json::to_value(NewsletterController::api_doc()).unwrap();
+        let content = &docs["paths"]["subscribe"]["post"]["requestBody"]["content"];
+
+        assert!(content["application/json"]["schema"].is_object(), "{}", content);
+        assert!(content["application/x-www-form-urlencoded"]["schema"].is_object(), "{}", content);
+    }
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use argon_core::i18n::{AcceptLanguage, register_language};
+use argon_core::response::BaseErrorResponse;
+use argon_core::testing::TestApp;
+use axum::{
+    Json, Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+
+async fn missing(languages: AcceptLanguage) -> (StatusCode, Json<BaseErrorResponse<()>>) {
+    (StatusCode::NOT_FOUND, Json(BaseErrorResponse::localized("not_found", &languages, None)))
+}
+
+async fn message(app: &TestApp, accept_language: &str) -> String {
+    let request = Request::get("/missing")
+        .header("Accept-Language", accept_language)
+        .body(Body::empty())
+        .unwrap();
+
+    let body = common::body_json(app.request(request).await).await;
+
+    body["message"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn the_same_code_is_translated_per_accept_language() {
+    register_language("fa", [("not_found", "منبع درخواستی یافت نشد.")]);
+    let app = TestApp::new(Router::new().route("/missing", get(missing)));
+
+    assert_eq!(message(&app, "en").await, "The requested resource was not found.");
+    assert_eq!(message(&app, "fa-IR, en;q=0.5").await, "منبع درخواستی یافت نشد.");
+    // no message in that language, falls back to English
+    assert_eq!(message(&app, "de").await, "The requested resource was not found.");
+}
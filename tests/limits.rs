@@ -0,0 +1,250 @@
+mod common;
+
+use argon_core::limits::{RequestLimits, limit_request};
+use argon_core::testing::TestApp;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+
+fn app() -> TestApp {
+    let router = Router::new()
+        .route("/search", get(|| async { "results" }))
+        .layer(axum::middleware::from_fn_with_state(RequestLimits::new(64, 256), limit_request));
+
+    TestApp::new(router)
+}
+
+async fn status_of(request: Request<Body>) -> StatusCode {
+    app().request(request).await.status()
+}
+
+#[tokio::test]
+async fn requests_within_the_limits_pass() {
+    let request = Request::get("/search?q=tea").header("X-Trace", "abc").body(Body::empty()).unwrap();
+
+    assert_eq!(status_of(request).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn an_overlong_uri_answers_414() {
+    let uri = format!("/search?q={}", "a".repeat(64));
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+
+    assert_eq!(status_of(request).await, StatusCode::URI_TOO_LONG);
+}
+
+#[tokio::test]
+async fn oversized_headers_answer_431() {
+    let request = Request::get("/search")
+        .header("X-Padding", "a".repeat(200))
+        .header("X-More-Padding", "a".repeat(100))
+        .body(Body::empty())
+        .unwrap();
+
+    assert_eq!(status_of(request).await, StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+}
+
+mod concurrency {
+    use std::{sync::Arc, time::Duration};
+
+    use argon_core::limits::concurrency_limit;
+    use argon_core::testing::TestApp;
+    use axum::{
+        Router,
+        body::Body,
+        extract::State,
+        http::{Request, StatusCode, header},
+        response::Response,
+        routing::get,
+    };
+    use futures_util::future::join_all;
+    use tokio::sync::Semaphore;
+
+    /// Never granted, the handler waits until the test closes it
+    async fn slow(State(gate): State<Arc<Semaphore>>) -> &'static str {
+        let _ = gate.acquire().await;
+
+        "done"
+    }
+
+    #[tokio::test]
+    async fn requests_over_the_limit_answer_503_right_away() {
+        let gate = Arc::new(Semaphore::new(0));
+        let router = Router::new()
+            .route("/a", get(slow))
+            .route("/b", get(slow))
+            .with_state(gate.clone())
+            .layer(concurrency_limit(2));
+        let app = TestApp::new(router);
+
+        // the limit is shared by both routes
+        let requests = join_all(
+            ["/a", "/b", "/a", "/b", "/a"]
+                .map(|uri| app.request(Request::get(uri).body(Body::empty()).unwrap())),
+        );
+        let release = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            gate.close();
+        };
+        let responses: Vec<Response> = tokio::join!(requests, release).0;
+
+        let statuses: Vec<StatusCode> = responses.iter().map(Response::status).collect();
+        assert_eq!(statuses.iter().filter(|status| **status == StatusCode::OK).count(), 2, "{:?}", statuses);
+        for response in responses.iter().filter(|response| response.status() != StatusCode::OK) {
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+        }
+
+        // the slots are free again once the handlers are done
+        let response = app.request(Request::get("/a").body(Body::empty()).unwrap()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+mod content_length {
+    use argon_core::limits::{ContentLengthCheck, check_content_length};
+    use argon_core::testing::TestApp;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+        routing::post,
+    };
+
+    async fn status_of(headers: &[(header::HeaderName, &str)], body: &'static str) -> StatusCode {
+        let router = Router::new()
+            .route("/echo", post(|body: String| async move { body }))
+            .layer(axum::middleware::from_fn_with_state(
+                ContentLengthCheck::new(16),
+                check_content_length,
+            ));
+
+        let mut request = Request::post("/echo");
+        for (name, value) in headers {
+            request = request.header(name, *value);
+        }
+
+        TestApp::new(router)
+            .request(request.body(Body::from(body)).unwrap())
+            .await
+            .status()
+    }
+
+    #[tokio::test]
+    async fn a_matching_length_passes() {
+        assert_eq!(status_of(&[(header::CONTENT_LENGTH, "5")], "hello").await, StatusCode::OK);
+        assert_eq!(status_of(&[], "hello").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn a_mismatched_length_answers_400() {
+        assert_eq!(status_of(&[(header::CONTENT_LENGTH, "4")], "hello").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status_of(&[(header::CONTENT_LENGTH, "9")], "hello").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status_of(&[(header::CONTENT_LENGTH, "five")], "hello").await, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn ambiguous_lengths_answer_400() {
+        let conflicting = [(header::CONTENT_LENGTH, "5"), (header::CONTENT_LENGTH, "6")];
+        assert_eq!(status_of(&conflicting, "hello").await, StatusCode::BAD_REQUEST);
+
+        let chunked = [(header::CONTENT_LENGTH, "5"), (header::TRANSFER_ENCODING, "chunked")];
+        assert_eq!(status_of(&chunked, "hello").await, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn lengths_over_the_buffer_are_not_checked() {
+        assert_eq!(status_of(&[(header::CONTENT_LENGTH, "64")], "hello").await, StatusCode::OK);
+    }
+}
+
+mod content_types {
+    use argon_core::testing::TestApp;
+    use argon_macros::{accepts, controller, get, patch, post};
+    use axum::{
+        Json,
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use serde_json::{Value, json};
+
+    use super::common;
+
+    pub struct NoteController;
+
+    #[controller]
+    impl NoteController {
+        #[post("/notes")]
+        #[accepts("application/json")]
+        async fn create(Json(_note): Json<Value>) -> StatusCode {
+            StatusCode::CREATED
+        }
+
+        #[patch("/notes/{id}")]
+        #[accepts("application/json", "application/merge-patch+json")]
+        async fn update() -> StatusCode {
+            StatusCode::NO_CONTENT
+        }
+
+        #[get("/notes")]
+        #[accepts("application/json")]
+        async fn index() -> &'static str {
+            "notes"
+        }
+    }
+
+    fn send(method: &str, uri: &str, content_type: &str, body: &'static str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn another_content_type_answers_415_listing_the_accepted_ones() {
+        let app = TestApp::controller::<NoteController>();
+
+        let response = app.request(send("POST", "/notes", "text/plain", "hello")).await;
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response.headers()["accept-post"], "application/json");
+        assert_eq!(
+            common::body_json(response).await,
+            json!({ "message": "unsupported content type, expected one of: application/json", "detail": null })
+        );
+
+        let response = app.request(send("PATCH", "/notes/1", "text/plain", "hello")).await;
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(
+            response.headers()["accept-patch"],
+            "application/json, application/merge-patch+json"
+        );
+    }
+
+    #[tokio::test]
+    async fn accepted_types_pass_parameters_aside() {
+        let app = TestApp::controller::<NoteController>();
+
+        let response = app
+            .request(send("POST", "/notes", "application/json; charset=utf-8", "{}"))
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app
+            .request(send("PATCH", "/notes/1", "application/merge-patch+json", "{}"))
+            .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn methods_without_a_body_go_through() {
+        let app = TestApp::controller::<NoteController>();
+
+        let response = app.request(Request::get("/notes").body(Body::empty()).unwrap()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
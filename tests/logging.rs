@@ -0,0 +1,73 @@
+mod common;
+
+use argon_core::logging::{BodyLogger, log_bodies};
+use argon_core::testing::TestApp;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    routing::post,
+};
+
+use common::Logs;
+
+fn app(logger: BodyLogger) -> TestApp {
+    let router = Router::new()
+        .route("/login", post(|body: String| async move { body }))
+        .layer(axum::middleware::from_fn_with_state(logger, log_bodies));
+
+    TestApp::new(router)
+}
+
+fn login(body: &str) -> Request<Body> {
+    Request::post("/login")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn passwords_are_redacted_in_the_logged_body() {
+    let (logs, _guard) = Logs::capture();
+    let body = r#"{"username":"alice","password":"hunter2"}"#;
+
+    let response = app(BodyLogger::new()).request(login(body)).await;
+
+    // the handler still gets the untouched body
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_text(response).await, body);
+
+    let logs = logs.contents();
+    assert!(logs.contains(r#"request body: {"password":"***","username":"alice"}"#), "{}", logs);
+    assert!(!logs.contains("hunter2"), "{}", logs);
+}
+
+#[tokio::test]
+async fn bodies_past_the_cap_are_logged_by_size() {
+    let (logs, _guard) = Logs::capture();
+    let body = format!(r#"{{"password":"{}"}}"#, "x".repeat(100));
+
+    let response = app(BodyLogger::new().max_body_bytes(16)).request(login(&body)).await;
+    assert_eq!(common::body_text(response).await, body);
+
+    let logs = logs.contents();
+    assert!(logs.contains("request body: <115 bytes, truncated>"), "{}", logs);
+    assert!(logs.contains("response body: <115 bytes, truncated>"), "{}", logs);
+}
+
+#[tokio::test]
+async fn streamed_bodies_past_the_cap_are_passed_on_whole() {
+    let (logs, _guard) = Logs::capture();
+    let chunks = ["{\"password\":", "\"hunter2\"", "}"].map(Ok::<_, std::io::Error>);
+    let request = Request::post("/login")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from_stream(futures_util::stream::iter(chunks)))
+        .unwrap();
+
+    let response = app(BodyLogger::new().max_body_bytes(16)).request(request).await;
+    assert_eq!(common::body_text(response).await, r#"{"password":"hunter2"}"#);
+
+    let logs = logs.contents();
+    assert!(logs.contains("request body: <21 bytes, truncated>"), "{}", logs);
+    assert!(!logs.contains("hunter2"), "{}", logs);
+}
@@ -0,0 +1,11 @@
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+
+    cases.pass("tests/ui/impl_into_response.rs");
+    cases.pass("tests/ui/empty_controller.rs");
+    cases.compile_fail("tests/ui/impl_into_response_undocumented.rs");
+    cases.compile_fail("tests/ui/response_duplicate_status.rs");
+    cases.compile_fail("tests/ui/sync_route_handler.rs");
+    cases.compile_fail("tests/ui/misplaced_wildcard.rs");
+}
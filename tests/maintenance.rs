@@ -0,0 +1,63 @@
+mod common;
+
+use std::{net::SocketAddr, time::Duration};
+
+use argon_core::maintenance::{MaintenanceMode, is_enabled, maintenance, set_enabled};
+use argon_core::testing::TestApp;
+use axum::{
+    Router,
+    body::Body,
+    extract::ConnectInfo,
+    http::{Request, StatusCode, header},
+    routing::get,
+};
+use serde_json::json;
+
+fn app() -> TestApp {
+    let router = Router::new()
+        .route("/posts", get(|| async { "posts" }))
+        .route("/health", get(|| async { "ok" }))
+        .layer(axum::middleware::from_fn_with_state(
+            MaintenanceMode::new()
+                .allowed_ips(vec!["10.0.0.5".parse().unwrap()])
+                .retry_after(Duration::from_secs(120)),
+            maintenance,
+        ));
+
+    TestApp::new(router)
+}
+
+fn from(uri: &str, peer: &str) -> Request<Body> {
+    let mut request = Request::get(uri).body(Body::empty()).unwrap();
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 4000)));
+
+    request
+}
+
+// a single test, maintenance mode is shared by the whole binary
+#[tokio::test]
+async fn maintenance_mode_turns_away_all_but_health_checks_and_allowed_ips() {
+    let app = app();
+
+    assert!(!is_enabled());
+    assert_eq!(app.request(from("/posts", "203.0.113.9")).await.status(), StatusCode::OK);
+
+    set_enabled(true);
+
+    let response = app.request(from("/posts", "203.0.113.9")).await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()[header::RETRY_AFTER], "120");
+    assert_eq!(
+        common::body_json(response).await,
+        json!({ "message": "the service is under maintenance", "detail": null })
+    );
+
+    assert_eq!(app.request(from("/health", "203.0.113.9")).await.status(), StatusCode::OK);
+    assert_eq!(app.request(from("/posts", "10.0.0.5")).await.status(), StatusCode::OK);
+
+    set_enabled(false);
+
+    assert_eq!(app.request(from("/posts", "203.0.113.9")).await.status(), StatusCode::OK);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use argon_core::model::Patch;
+use sea_orm::ActiveValue;
+use serde::Deserialize;
+
+use common::post;
+
+#[derive(Deserialize)]
+struct UpdatePost {
+    #[serde(default)]
+    summary: Patch<String>,
+}
+
+fn parse(body: &str) -> Patch<String> {
+    serde_json::from_str::<UpdatePost>(body).unwrap().summary
+}
+
+#[test]
+fn absent_null_and_present_fields_deserialize_apart() {
+    assert_eq!(parse("{}"), Patch::Missing);
+    assert_eq!(parse(r#"{"summary": null}"#), Patch::Null);
+    assert_eq!(parse(r#"{"summary": "short"}"#), Patch::Value("short".to_string()));
+}
+
+#[test]
+fn patches_apply_onto_an_active_model() {
+    let model = post::Model {
+        id: 1,
+        title: "Hello".to_string(),
+        summary: Some("old".to_string()),
+    };
+
+    let mut missing: post::ActiveModel = model.clone().into();
+    parse("{}").apply(&mut missing.summary);
+    assert_eq!(missing.summary, ActiveValue::Unchanged(Some("old".to_string())));
+
+    let mut null: post::ActiveModel = model.clone().into();
+    parse(r#"{"summary": null}"#).apply(&mut null.summary);
+    assert_eq!(null.summary, ActiveValue::Set(None));
+
+    let mut value: post::ActiveModel = model.into();
+    parse(r#"{"summary": "new"}"#).apply(&mut value.summary);
+    assert_eq!(value.summary, ActiveValue::Set(Some("new".to_string())));
+}
+
+mod lang_field {
+    use argon_core::model::LangField;
+
+    #[test]
+    fn a_tuple_round_trips_through_lang_field() {
+        let field = LangField::from(("fr".to_string(), "Bonjour".to_string()));
+        assert_eq!(field, LangField::new("fr".to_string(), "Bonjour".to_string()));
+
+        let (lang, content): (String, String) = field.into();
+        assert_eq!((lang.as_str(), content.as_str()), ("fr", "Bonjour"));
+    }
+
+    #[test]
+    fn borrowed_tuples_convert_too() {
+        let field: LangField = ("en", "Hello").into();
+
+        assert_eq!(<(String, String)>::from(field), ("en".to_string(), "Hello".to_string()));
+    }
+}
+
+mod timestamp {
+    use argon_core::model::Timestamp;
+    use sea_orm::prelude::ChronoDateTime;
+    use serde_json::json;
+    use utoipa::PartialSchema;
+
+    #[test]
+    fn timestamps_round_trip_as_rfc3339_in_utc() {
+        let timestamp: Timestamp = serde_json::from_value(json!("2025-01-31T14:30:00+02:00")).unwrap();
+
+        assert_eq!(serde_json::to_value(timestamp).unwrap(), json!("2025-01-31T12:30:00Z"));
+        assert_eq!(
+            serde_json::from_value::<Timestamp>(json!("2025-01-31T12:30:00Z")).unwrap(),
+            timestamp
+        );
+        assert_eq!(
+            serde_json::to_value("2025-01-31T12:30:00.250Z".parse::<Timestamp>().unwrap()).unwrap(),
+            json!("2025-01-31T12:30:00.250Z")
+        );
+    }
+
+    #[test]
+    fn other_formats_are_rejected() {
+        let err = serde_json::from_value::<Timestamp>(json!("31/01/2025")).unwrap_err().to_string();
+
+        assert!(err.starts_with("`31/01/2025` is not an RFC 3339 timestamp"), "{}", err);
+    }
+
+    #[test]
+    fn naive_timestamps_are_read_as_utc() {
+        let naive = ChronoDateTime::parse_from_str("2025-01-31 12:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        assert_eq!(Timestamp::from(naive).to_string(), "2025-01-31T12:30:00Z");
+        assert_eq!(ChronoDateTime::from(Timestamp::from(naive)), naive);
+    }
+
+    #[test]
+    fn the_schema_is_a_date_time_string() {
+        assert_eq!(
+            serde_json::to_value(Timestamp::schema()).unwrap(),
+            json!({ "type": "string", "format": "date-time" })
+        );
+    }
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use argon_core::controller::{Controller, mounted_docs};
+use argon_core::testing::TestApp;
+use argon_macros::{controller, get, utoipa_response};
+use axum::{
+    Router,
+    body::Body,
+    extract::Path,
+    http::{Request, StatusCode},
+};
+
+pub struct OrderController;
+
+#[controller]
+impl OrderController {
+    #[get("/orders/{id}")]
+    #[utoipa_response(status = 200, body = String)]
+    async fn show(Path(_id): Path<i32>) -> String {
+        format!("order {}", _id)
+    }
+}
+
+// a single test, the mounted docs are shared by the whole binary
+#[tokio::test]
+async fn a_controller_mounted_at_a_prefix_is_routed_and_documented_there() {
+    let app = TestApp::new(OrderController::mount(Router::new(), "/api/"));
+
+    let response = app
+        .request(Request::get("/api/orders/7").body(Body::empty()).unwrap())
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_text(response).await, "order 7");
+
+    let response = app
+        .request(Request::get("/orders/7").body(Body::empty()).unwrap())
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let paths = |docs: utoipa::openapi::OpenApi| docs.paths.paths.into_keys().collect::<Vec<_>>();
+    assert_eq!(paths(mounted_docs()), ["/api/orders/{id}"]);
+
+    // building the routes again doesn't document the controller twice
+    let _ = OrderController::try_mount(Router::new(), "/api").unwrap();
+    assert_eq!(mounted_docs().paths.paths.len(), 1);
+
+    let _ = OrderController::mount(Router::new(), "/v2");
+    assert_eq!(paths(mounted_docs()), ["/api/orders/{id}", "/v2/orders/{id}"]);
+}
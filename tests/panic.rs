@@ -0,0 +1,54 @@
+mod common;
+
+use argon_core::panic::{catch_panic, install_panic_hook};
+use argon_core::testing::TestApp;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use serde_json::json;
+
+async fn explode() -> &'static str {
+    panic!("the report is missing")
+}
+
+#[tokio::test]
+async fn a_panic_is_logged_with_structured_fields_and_answers_500() {
+    install_panic_hook();
+
+    let app = TestApp::new(
+        Router::new()
+            .route("/explode", get(explode))
+            .route("/fine", get(|| async { "fine" }))
+            .layer(axum::middleware::from_fn(catch_panic)),
+    );
+
+    let (logs, _guard) = common::Logs::capture_json();
+    let response = app
+        .request(Request::get("/explode").body(Body::empty()).unwrap())
+        .await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        common::body_json(response).await,
+        json!({ "message": "internal server error", "detail": null })
+    );
+
+    let lines = logs.json_lines();
+    let event = lines
+        .iter()
+        .find(|line| line["fields"]["panic.message"].is_string())
+        .unwrap_or_else(|| panic!("no panic event in {:?}", lines));
+    assert_eq!(event["level"], "ERROR");
+    assert_eq!(event["fields"]["panic.message"], "the report is missing");
+    assert_eq!(event["fields"]["message"], "GET /explode panicked");
+
+    let location = event["fields"]["panic.location"].as_str().unwrap();
+    assert!(location.starts_with("tests/panic.rs:"), "{}", location);
+
+    // the server keeps answering
+    let response = app.request(Request::get("/fine").body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use argon_core::plugin::{Plugin, PluginRegistry};
+use argon_core::testing::TestApp;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+
+struct PingPlugin;
+
+impl Plugin for PingPlugin {
+    fn name(&self) -> &'static str {
+        "ping"
+    }
+
+    fn mount(&self, router: Router) -> Router {
+        router.route("/ping", get(|| async { "pong" }))
+    }
+}
+
+fn get_request(uri: &str) -> Request<Body> {
+    Request::get(uri).body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn a_registered_plugin_adds_its_route() {
+    let app = Router::new().route("/", get(|| async { "home" }));
+    let app = TestApp::new(PluginRegistry::new().register(PingPlugin).mount(app));
+
+    let response = app.request(get_request("/ping")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_text(response).await, "pong");
+
+    // the app routes are kept
+    assert_eq!(app.request(get_request("/")).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn a_plugin_registered_under_a_false_condition_is_skipped() {
+    let app = TestApp::new(PluginRegistry::new().register_if(false, PingPlugin).mount(Router::new()));
+
+    assert_eq!(app.request(get_request("/ping")).await.status(), StatusCode::NOT_FOUND);
+}
+
+mod docs {
+    use argon_core::plugin::{DocsPlugin, PluginRegistry};
+    use argon_core::testing::TestApp;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use utoipa::openapi::{InfoBuilder, OpenApiBuilder};
+
+    fn app(plugin: DocsPlugin) -> TestApp {
+        TestApp::new(PluginRegistry::new().register(plugin).mount(Router::new()))
+    }
+
+    fn spec() -> utoipa::openapi::OpenApi {
+        OpenApiBuilder::new()
+            .info(InfoBuilder::new().title("argon").version("1.0.0"))
+            .build()
+    }
+
+    async fn fetch(app: &TestApp, uri: &str, accept: Option<&str>) -> (StatusCode, String, String) {
+        let mut request = Request::get(uri);
+        if let Some(accept) = accept {
+            request = request.header(header::ACCEPT, accept);
+        }
+
+        let response = app.request(request.body(Body::empty()).unwrap()).await;
+        let status = response.status();
+        let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string();
+
+        (status, content_type, super::common::body_text(response).await)
+    }
+
+    #[tokio::test]
+    async fn the_format_follows_the_accept_header() {
+        let app = app(DocsPlugin::new(spec()));
+
+        for accept in [None, Some("application/json"), Some("*/*")] {
+            let (status, content_type, body) = fetch(&app, "/openapi", accept).await;
+
+            assert_eq!((status, content_type.as_str()), (StatusCode::OK, "application/json"), "{:?}", accept);
+            let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(body["info"]["title"], "argon");
+        }
+
+        for accept in ["application/yaml", "text/yaml"] {
+            let (status, content_type, body) = fetch(&app, "/openapi", Some(accept)).await;
+
+            assert_eq!((status, content_type.as_str()), (StatusCode::OK, "application/yaml"), "{}", accept);
+            let body: serde_json::Value = serde_norway::from_str(&body).unwrap();
+            assert_eq!(body["info"]["title"], "argon");
+        }
+    }
+
+    #[tokio::test]
+    async fn the_document_stays_at_openapi_json() {
+        let app = app(DocsPlugin::new(spec()));
+
+        let (status, content_type, _) = fetch(&app, "/openapi.json", None).await;
+        assert_eq!((status, content_type.as_str()), (StatusCode::OK, "application/json"));
+    }
+
+    #[tokio::test]
+    async fn a_custom_path_replaces_the_defaults() {
+        let app = app(DocsPlugin::new(spec()).path("/docs/spec"));
+
+        assert_eq!(fetch(&app, "/docs/spec", None).await.0, StatusCode::OK);
+
+        let response = app.request(Request::get("/openapi.json").body(Body::empty()).unwrap()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
+
+#[tokio::test]
+async fn docs_are_gzipped_for_clients_accepting_it() {
+    use std::io::Read;
+
+    use argon_core::plugin::DocsPlugin;
+    use axum::http::header;
+
+    let spec = utoipa::openapi::OpenApiBuilder::new()
+        .info(utoipa::openapi::InfoBuilder::new().title("argon").version("1.0.0"))
+        .build();
+    let app = TestApp::new(PluginRegistry::new().register(DocsPlugin::new(spec)).mount(Router::new()));
+
+    let request = Request::get("/openapi.json")
+        .header(header::ACCEPT_ENCODING, "br, gzip;q=0.8")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.request(request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+
+    let mut json = String::new();
+    flate2::read::GzDecoder::new(common::body_bytes(response).await.as_slice())
+        .read_to_string(&mut json)
+        .unwrap();
+    let spec: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(spec["info"]["title"], "argon");
+
+    // refused or absent gzip gets the plain body
+    for accept_encoding in [None, Some("gzip;q=0")] {
+        let mut request = Request::get("/openapi.json");
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(header::ACCEPT_ENCODING, accept_encoding);
+        }
+
+        let response = app.request(request.body(Body::empty()).unwrap()).await;
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none(), "{:?}", accept_encoding);
+        assert_eq!(common::body_json(response).await["info"]["title"], "argon");
+    }
+}
+
+mod metrics {
+    use std::time::Duration;
+
+    use argon_core::plugin::{MetricsPlugin, PluginRegistry};
+    use argon_core::testing::TestApp;
+    use axum::{Router, body::Body, http::Request, routing::get};
+
+    use super::common::{self, Logs};
+
+    fn app() -> TestApp {
+        let router = Router::new()
+            .route(
+                "/reports/{id}",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(60)).await;
+                    "report"
+                }),
+            )
+            .route("/fast", get(|| async { "0123456789" }));
+
+        TestApp::new(
+            PluginRegistry::new()
+                .register(MetricsPlugin::new().slow_request(Duration::from_millis(30)))
+                .mount(router),
+        )
+    }
+
+    async fn fetch(app: &TestApp, uri: &str) -> String {
+        common::body_text(app.request(Request::get(uri).body(Body::empty()).unwrap()).await).await
+    }
+
+    #[tokio::test]
+    async fn slow_requests_are_logged_with_their_route_and_duration() {
+        let (logs, _guard) = Logs::capture();
+        let app = app();
+
+        fetch(&app, "/fast").await;
+        fetch(&app, "/reports/7").await;
+
+        let logs = logs.contents();
+        let line = logs
+            .lines()
+            .find(|line| line.contains("slow request"))
+            .unwrap_or_else(|| panic!("no slow request warning in {}", logs));
+
+        assert!(line.contains("WARN"), "{}", line);
+        assert!(line.contains("slow request GET /reports/{id} took "), "{}", line);
+        let millis: u64 = line.rsplit("took ").next().unwrap().trim_end_matches(" ms").parse().unwrap();
+        assert!(millis >= 60, "{}", line);
+        assert!(!logs.contains("/fast"), "{}", logs);
+
+        let metrics = fetch(&app, "/metrics").await;
+        assert!(metrics.contains("http_slow_requests_total 1\n"), "{}", metrics);
+        assert!(metrics.contains("http_response_body_bytes_total 16\n"), "{}", metrics);
+    }
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use argon_core::error::AppError;
+use argon_core::repository::Repository;
+use argon_core::state::{AppState, Db};
+use argon_core::testing::TestApp;
+use axum::{
+    Json, Router,
+    http::{Method, StatusCode},
+    routing::post,
+};
+use sea_orm::{
+    ActiveValue::{NotSet, Set},
+    DbErr,
+};
+use serde_json::json;
+
+use common::post;
+
+#[derive(serde::Deserialize)]
+struct NewPost {
+    title: String,
+}
+
+async fn create(Db(db): Db, Json(body): Json<NewPost>) -> Result<Json<post::Model>, AppError> {
+    let post = post::ActiveModel {
+        id: NotSet,
+        title: Set(body.title),
+        summary: Set(None),
+    };
+
+    Ok(Json(Repository::<post::Entity>::new(db).create(post).await?))
+}
+
+#[tokio::test]
+async fn a_duplicate_unique_column_answers_409_naming_it() {
+    let db = common::sqlite_with_posts().await;
+    let app = TestApp::new(Router::new().route("/posts", post(create))).with_state(AppState::new(db));
+
+    let (status, _) = app.send_json(Method::POST, "/posts", &json!({ "title": "Hello" })).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app.send_json(Method::POST, "/posts", &json!({ "title": "Hello" })).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body, json!({ "message": "`title` is already taken", "detail": null }));
+}
+
+#[tokio::test]
+async fn updates_report_conflicts_too() {
+    let db = common::sqlite_with_posts().await;
+    let posts = Repository::<post::Entity>::new(db);
+
+    for title in ["Hello", "World"] {
+        posts
+            .create(post::ActiveModel {
+                id: NotSet,
+                title: Set(title.to_string()),
+                summary: Set(None),
+            })
+            .await
+            .unwrap();
+    }
+
+    let err = posts
+        .update(post::ActiveModel {
+            id: Set(2),
+            title: Set("Hello".to_string()),
+            summary: NotSet,
+        })
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, AppError::Conflict(Some(ref column)) if column == "title"), "{:?}", err);
+}
+
+#[test]
+fn other_database_errors_stay_database_errors() {
+    let err = AppError::from_db(DbErr::Custom("disk full".to_string()), "post");
+
+    assert!(matches!(err, AppError::Db(_)));
+}
@@ -0,0 +1,129 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use argon_core::resilience::{
+    CircuitBreaker, CircuitError, CircuitState, RetryPolicy, register_circuit_breaker, with_circuit_breaker,
+};
+
+/// Fails its first `failures` calls, then succeeds with the number of the call
+struct Flaky {
+    calls: AtomicU32,
+    failures: u32,
+}
+
+impl Flaky {
+    fn new(failures: u32) -> Self {
+        Self {
+            calls: AtomicU32::new(0),
+            failures,
+        }
+    }
+
+    async fn call(&self) -> Result<u32, String> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+
+        if call <= self.failures {
+            Err(format!("call {} failed", call))
+        } else {
+            Ok(call)
+        }
+    }
+
+    fn calls(&self) -> u32 {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[test]
+fn the_delay_doubles_up_to_the_cap() {
+    let policy = RetryPolicy::new(5, Duration::from_millis(100)).max_delay(Duration::from_millis(300));
+
+    assert_eq!(policy.delay(0), Duration::from_millis(100));
+    assert_eq!(policy.delay(1), Duration::from_millis(200));
+    assert_eq!(policy.delay(2), Duration::from_millis(300));
+    assert_eq!(policy.delay(40), Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn a_call_is_retried_until_it_succeeds() {
+    let flaky = Flaky::new(2);
+
+    assert_eq!(RetryPolicy::new(2, Duration::from_millis(1)).retry(|| flaky.call()).await, Ok(3));
+
+    let flaky = Flaky::new(5);
+    assert_eq!(
+        RetryPolicy::new(2, Duration::from_millis(1)).retry(|| flaky.call()).await,
+        Err("call 3 failed".to_string())
+    );
+    assert_eq!(flaky.calls(), 3);
+}
+
+#[tokio::test]
+async fn the_breaker_opens_after_the_threshold_and_refuses_calls() {
+    let breaker = CircuitBreaker::new()
+        .failure_threshold(3)
+        .cooldown(Duration::from_secs(60))
+        .retry(RetryPolicy::none());
+    let flaky = Flaky::new(u32::MAX);
+
+    for failures in 1..3 {
+        assert!(matches!(breaker.call(|| flaky.call()).await, Err(CircuitError::Failed(_))));
+        assert_eq!(breaker.state(), CircuitState::Closed { failures });
+    }
+
+    assert!(matches!(breaker.call(|| flaky.call()).await, Err(CircuitError::Failed(_))));
+    assert!(matches!(breaker.state(), CircuitState::Open { .. }));
+
+    // refused without calling
+    assert!(matches!(breaker.call(|| flaky.call()).await, Err(CircuitError::Open)));
+    assert_eq!(flaky.calls(), 3);
+}
+
+#[tokio::test]
+async fn retries_stop_once_the_breaker_opens() {
+    let breaker = CircuitBreaker::new()
+        .failure_threshold(2)
+        .retry(RetryPolicy::new(5, Duration::from_millis(1)));
+    let flaky = Flaky::new(u32::MAX);
+
+    assert!(matches!(breaker.call(|| flaky.call()).await, Err(CircuitError::Failed(_))));
+    assert_eq!(flaky.calls(), 2);
+}
+
+#[tokio::test]
+async fn a_trial_call_after_the_cooldown_closes_or_reopens_the_breaker() {
+    let breaker = CircuitBreaker::new()
+        .failure_threshold(1)
+        .cooldown(Duration::from_millis(20))
+        .retry(RetryPolicy::none());
+
+    // the trial fails, opening the breaker again
+    let flaky = Flaky::new(2);
+    assert!(breaker.call(|| flaky.call()).await.is_err());
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert!(matches!(breaker.call(|| flaky.call()).await, Err(CircuitError::Failed(_))));
+    assert!(matches!(breaker.call(|| flaky.call()).await, Err(CircuitError::Open)));
+
+    // the trial succeeds, closing it
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(breaker.call(|| flaky.call()).await.ok(), Some(3));
+    assert_eq!(breaker.state(), CircuitState::Closed { failures: 0 });
+}
+
+#[tokio::test]
+async fn breakers_are_shared_by_name() {
+    register_circuit_breaker(
+        "rates",
+        CircuitBreaker::new().failure_threshold(1).retry(RetryPolicy::none()),
+    );
+    let flaky = Flaky::new(u32::MAX);
+
+    assert!(matches!(with_circuit_breaker("rates", || flaky.call()).await, Err(CircuitError::Failed(_))));
+    assert!(matches!(with_circuit_breaker("rates", || flaky.call()).await, Err(CircuitError::Open)));
+
+    // another name has a breaker of its own
+    let healthy = Flaky::new(0);
+    assert_eq!(with_circuit_breaker("quotes", || healthy.call()).await.ok(), Some(1));
+}
@@ -0,0 +1,296 @@
+mod common;
+
+use argon_core::testing::TestApp;
+use argon_macros::response;
+use axum::{
+    Router,
+    body::Body,
+    extract::Path,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use serde_json::json;
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub struct Missing {
+    pub id: i32,
+}
+
+response! {
+    UserResponse {
+        StatusCode::OK = String, "user found",
+        StatusCode::NOT_FOUND = Missing, "user not found",
+        StatusCode::CONFLICT = String, "user is locked"
+    }
+}
+
+enum UserError {
+    NotFound(i32),
+    Locked,
+}
+
+impl From<UserError> for UserResponse {
+    fn from(err: UserError) -> Self {
+        match err {
+            UserError::NotFound(id) => Self::not_found(Missing { id }),
+            UserError::Locked => Self::conflict("locked".to_string()),
+        }
+    }
+}
+
+fn find_user(id: i32) -> Result<String, UserError> {
+    match id {
+        1 => Ok("alice".to_string()),
+        2 => Err(UserError::Locked),
+        id => Err(UserError::NotFound(id)),
+    }
+}
+
+async fn show(Path(id): Path<i32>) -> Result<UserResponse, UserResponse> {
+    let name = find_user(id)?;
+
+    Ok(UserResponse::ok(name))
+}
+
+async fn get_json(app: &TestApp, uri: &str) -> (StatusCode, serde_json::Value) {
+    let response = app.request(Request::get(uri).body(Body::empty()).unwrap()).await;
+
+    (response.status(), common::body_json(response).await)
+}
+
+#[tokio::test]
+async fn domain_errors_map_onto_the_response_variants() {
+    let app = TestApp::new(Router::new().route("/users/{id}", get(show)));
+
+    assert_eq!(get_json(&app, "/users/1").await, (StatusCode::OK, json!("alice")));
+    assert_eq!(get_json(&app, "/users/2").await, (StatusCode::CONFLICT, json!("locked")));
+    assert_eq!(get_json(&app, "/users/7").await, (StatusCode::NOT_FOUND, json!({ "id": 7 })));
+}
+
+mod binary {
+    use argon_core::controller::Controller;
+    use argon_core::response::Binary;
+    use argon_core::testing::TestApp;
+    use argon_macros::{controller, get, utoipa_response};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+
+    /// Signature and IHDR start of a PNG
+    const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0, 0, 0, 0x0d];
+
+    pub struct AvatarController;
+
+    #[controller]
+    impl AvatarController {
+        #[get("/avatar")]
+        #[utoipa_response(status = 200, body = Binary, content_type = "image/png")]
+        async fn avatar() -> Binary {
+            Binary::new("image/png", PNG)
+        }
+    }
+
+    #[tokio::test]
+    async fn binary_bodies_keep_their_bytes_and_content_type() {
+        let app = TestApp::controller::<AvatarController>();
+        let response = app.request(Request::get("/avatar").body(Body::empty()).unwrap()).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+        assert_eq!(super::common::body_bytes(response).await, PNG);
+    }
+
+    #[test]
+    fn binary_bodies_are_documented_as_binary_strings() {
+        let spec = serde_json::to_value(
+            utoipa::openapi::OpenApi::default().nest("/", AvatarController::api_doc()),
+        )
+        .unwrap();
+        let content = &spec["paths"]["/avatar"]["get"]["responses"]["200"]["content"];
+        assert_eq!(content["image/png"]["schema"]["$ref"], "#/components/schemas/Binary");
+
+        let schema = &spec["components"]["schemas"]["Binary"];
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["format"], "binary");
+    }
+}
+
+mod success {
+    use argon_core::response::Success;
+    use axum::{http::StatusCode, response::IntoResponse};
+    use serde_json::json;
+    use utoipa::{PartialSchema, ToSchema};
+
+    #[derive(serde::Serialize, utoipa::ToSchema)]
+    struct Job {
+        id: i32,
+    }
+
+    #[tokio::test]
+    async fn constructors_answer_their_status_with_the_json_body() {
+        for (success, status) in [
+            (Success::ok(Job { id: 1 }), StatusCode::OK),
+            (Success::created(Job { id: 1 }), StatusCode::CREATED),
+            (Success::accepted(Job { id: 1 }), StatusCode::ACCEPTED),
+        ] {
+            let response = success.into_response();
+
+            assert_eq!(response.status(), status);
+            assert_eq!(super::common::body_json(response).await, json!({"id": 1}));
+        }
+    }
+
+    #[test]
+    fn the_schema_is_the_body_one() {
+        assert_eq!(Success::<Job>::name(), Job::name());
+        assert_eq!(
+            serde_json::to_value(Success::<Job>::schema()).unwrap(),
+            serde_json::to_value(Job::schema()).unwrap()
+        );
+    }
+}
+
+mod content_types {
+    use argon_macros::response;
+    use axum::{
+        http::{StatusCode, header},
+        response::IntoResponse,
+    };
+    use utoipa::IntoResponses;
+
+    response! {
+        ReportResponse {
+            StatusCode::OK = String, "the report", content_type = "text/csv",
+            StatusCode::SEE_OTHER = String, "the report moved", redirect,
+            StatusCode::NOT_FOUND = String, "no such report"
+        }
+    }
+
+    #[tokio::test]
+    async fn a_plain_text_variant_sets_its_content_type() {
+        let response = ReportResponse::ok("id,total\n1,20\n".to_string()).into_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/csv");
+        assert_eq!(super::common::body_text(response).await, "id,total\n1,20\n");
+    }
+
+    #[tokio::test]
+    async fn a_redirect_variant_sets_the_location_without_a_body() {
+        let response = ReportResponse::see_other("/reports/7".to_string()).into_response();
+
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.headers()[header::LOCATION], "/reports/7");
+        assert_eq!(super::common::body_text(response).await, "");
+    }
+
+    #[tokio::test]
+    async fn other_variants_stay_json() {
+        let response = ReportResponse::not_found("report 7".to_string()).into_response();
+
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(super::common::body_text(response).await, "\"report 7\"");
+    }
+
+    #[test]
+    fn the_docs_follow_each_variant() {
+        let responses = serde_json::to_value(ReportResponse::responses()).unwrap();
+
+        assert!(responses["200"]["content"]["text/csv"].is_object(), "{}", responses["200"]);
+        assert!(responses["303"]["headers"]["Location"].is_object(), "{}", responses["303"]);
+        assert!(responses["303"].get("content").is_none(), "{}", responses["303"]);
+    }
+}
+
+mod redirect {
+    use argon_core::response::Redirect;
+    use axum::{
+        http::{StatusCode, header},
+        response::IntoResponse,
+    };
+    use utoipa::IntoResponses;
+
+    #[test]
+    fn constructors_set_their_status_and_the_location() {
+        for (redirect, status) in [
+            (Redirect::temporary("/login"), StatusCode::TEMPORARY_REDIRECT),
+            (Redirect::permanent("/login"), StatusCode::PERMANENT_REDIRECT),
+            (Redirect::see_other("/login"), StatusCode::SEE_OTHER),
+        ] {
+            let response = redirect.into_response();
+
+            assert_eq!(response.status(), status);
+            assert_eq!(response.headers()[header::LOCATION], "/login");
+        }
+    }
+
+    #[test]
+    fn an_invalid_location_answers_500() {
+        let response = Redirect::temporary("/login\n").into_response();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.headers().get(header::LOCATION).is_none());
+    }
+
+    #[test]
+    fn it_is_documented_as_a_3xx_with_a_location() {
+        let responses = serde_json::to_value(Redirect::responses()).unwrap();
+
+        assert!(responses["3XX"]["headers"]["Location"].is_object(), "{}", responses);
+    }
+}
+
+mod api_model {
+    use argon_macros::api_model;
+    use serde_json::json;
+    use utoipa::PartialSchema;
+
+    #[api_model(rename_all = "camelCase")]
+    #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+    struct Session {
+        user_id: i32,
+        created_at: String,
+    }
+
+    #[api_model]
+    #[derive(serde::Serialize, utoipa::ToSchema)]
+    struct Project {
+        created_at: String,
+    }
+
+    /// Property names of the schema of `T`
+    fn properties<T: PartialSchema>() -> Vec<String> {
+        let schema = serde_json::to_value(T::schema()).unwrap();
+
+        schema["properties"].as_object().unwrap().keys().cloned().collect()
+    }
+
+    #[test]
+    fn the_wire_format_and_the_schema_share_the_casing() {
+        let session = Session {
+            user_id: 1,
+            created_at: "2025-01-31T12:00:00".to_string(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&session).unwrap(),
+            json!({ "userId": 1, "createdAt": "2025-01-31T12:00:00" })
+        );
+        assert_eq!(properties::<Session>(), ["createdAt", "userId"]);
+
+        let parsed: Session = serde_json::from_value(json!({ "userId": 2, "createdAt": "now" })).unwrap();
+        assert_eq!(parsed.user_id, 2);
+    }
+
+    #[test]
+    fn the_project_casing_is_the_default() {
+        let expected = if cfg!(feature = "camel-case") { "createdAt" } else { "created_at" };
+        let project = Project {
+            created_at: "now".to_string(),
+        };
+
+        assert_eq!(serde_json::to_value(&project).unwrap(), json!({ expected: "now" }));
+        assert_eq!(properties::<Project>(), [expected]);
+    }
+}
@@ -0,0 +1,452 @@
+mod common;
+
+use argon_core::controller::Controller;
+use argon_core::testing::TestApp;
+use argon_macros::{controller, get, utoipa_response};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+
+pub struct ItemController;
+
+#[controller]
+impl ItemController {
+    #[get("/items")]
+    #[get("/catalog/items")]
+    #[utoipa_response(status = 200, body = String)]
+    async fn items() -> String {
+        "all items".to_string()
+    }
+}
+
+async fn fetch(app: &TestApp, uri: &str) -> (StatusCode, String) {
+    let response = app.request(Request::get(uri).body(Body::empty()).unwrap()).await;
+
+    (response.status(), common::body_text(response).await)
+}
+
+#[tokio::test]
+async fn a_handler_answers_on_each_of_its_paths() {
+    let app = TestApp::new(ItemController::router());
+
+    assert_eq!(fetch(&app, "/items").await, (StatusCode::OK, "all items".to_string()));
+    assert_eq!(fetch(&app, "/catalog/items").await, (StatusCode::OK, "all items".to_string()));
+}
+
+#[test]
+fn each_path_is_documented() {
+    let mut paths: Vec<_> = ItemController::api_doc().paths.paths.into_keys().collect();
+    paths.sort();
+
+    assert_eq!(paths, ["catalog/items", "items"]);
+}
+
+mod trailing_slash {
+    use argon_core::routing::{TrailingSlash, trailing_slash};
+    use argon_core::testing::TestApp;
+    use axum::{
+        Router,
+        body::Body,
+        extract::Path,
+        http::{Request, StatusCode, header},
+        routing::get,
+    };
+
+    fn app(mode: TrailingSlash) -> TestApp {
+        let router = Router::new()
+            .route("/users", get(|| async { "users" }))
+            .route("/teams/", get(|| async { "teams" }))
+            .route("/files/{*path}", get(|Path(path): Path<String>| async move { path }))
+            .layer(axum::middleware::from_fn_with_state(mode, trailing_slash));
+
+        TestApp::new(router)
+    }
+
+    /// Status and `Location` of `GET uri`
+    async fn redirect(app: &TestApp, uri: &str) -> (StatusCode, Option<String>) {
+        let response = app.request(Request::get(uri).body(Body::empty()).unwrap()).await;
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .map(|location| location.to_str().unwrap().to_string());
+
+        (response.status(), location)
+    }
+
+    /// Follow the redirect of `uri`, if any, and return the body
+    async fn follow(app: &TestApp, uri: &str) -> (StatusCode, String) {
+        let uri = match redirect(app, uri).await {
+            (StatusCode::PERMANENT_REDIRECT, Some(location)) => location,
+            _ => uri.to_string(),
+        };
+        let response = app.request(Request::get(uri).body(Body::empty()).unwrap()).await;
+
+        (response.status(), super::common::body_text(response).await)
+    }
+
+    #[tokio::test]
+    async fn strict_serves_paths_as_they_are() {
+        let app = app(TrailingSlash::Strict);
+
+        assert_eq!(redirect(&app, "/users/").await, (StatusCode::NOT_FOUND, None));
+        assert_eq!(follow(&app, "/users").await, (StatusCode::OK, "users".to_string()));
+    }
+
+    #[tokio::test]
+    async fn redirect_to_no_slash_drops_the_trailing_slash() {
+        let app = app(TrailingSlash::RedirectToNoSlash);
+
+        assert_eq!(
+            redirect(&app, "/users/?page=2").await,
+            (StatusCode::PERMANENT_REDIRECT, Some("/users?page=2".to_string()))
+        );
+        assert_eq!(follow(&app, "/users/").await, (StatusCode::OK, "users".to_string()));
+        // a route declared with the slash is served as it is
+        assert_eq!(follow(&app, "/teams/").await, (StatusCode::OK, "teams".to_string()));
+    }
+
+    #[tokio::test]
+    async fn redirect_to_slash_appends_it() {
+        let app = app(TrailingSlash::RedirectToSlash);
+
+        assert_eq!(
+            redirect(&app, "/teams").await,
+            (StatusCode::PERMANENT_REDIRECT, Some("/teams/".to_string()))
+        );
+        assert_eq!(follow(&app, "/teams").await, (StatusCode::OK, "teams".to_string()));
+        assert_eq!(follow(&app, "/users").await, (StatusCode::OK, "users".to_string()));
+    }
+
+    #[tokio::test]
+    async fn wildcard_routes_are_left_alone() {
+        for mode in [TrailingSlash::RedirectToNoSlash, TrailingSlash::RedirectToSlash] {
+            let app = app(mode);
+
+            assert_eq!(redirect(&app, "/files/docs/a.txt").await, (StatusCode::OK, None));
+            assert_eq!(follow(&app, "/files/docs/").await, (StatusCode::OK, "docs/".to_string()));
+        }
+    }
+}
+
+mod routes_macro {
+    use argon_core::testing::TestApp;
+    use argon_macros::{controller, get, routes, utoipa_response};
+    use axum::{
+        body::Body,
+        http::{HeaderValue, Request, StatusCode},
+        middleware::map_response,
+        response::Response,
+    };
+
+    pub struct UserController;
+
+    #[controller]
+    impl UserController {
+        #[get("/users")]
+        #[utoipa_response(status = 200, body = String)]
+        async fn users() -> String {
+            "users".to_string()
+        }
+    }
+
+    pub struct PostController;
+
+    #[controller]
+    impl PostController {
+        #[get("/posts")]
+        #[utoipa_response(status = 200, body = String)]
+        async fn posts() -> String {
+            "posts".to_string()
+        }
+    }
+
+    pub struct AdminController;
+
+    #[controller]
+    impl AdminController {
+        #[get("/stats")]
+        #[utoipa_response(status = 200, body = String)]
+        async fn stats() -> String {
+            "stats".to_string()
+        }
+    }
+
+    /// Appends `name` to the `X-Layers` response header, the innermost layer first
+    fn tag(mut response: Response, name: &'static str) -> Response {
+        response.headers_mut().append("x-layers", HeaderValue::from_static(name));
+        response
+    }
+
+    async fn auth(response: Response) -> Response {
+        tag(response, "auth")
+    }
+
+    async fn admin(response: Response) -> Response {
+        tag(response, "admin")
+    }
+
+    async fn fetch(app: &TestApp, uri: &str) -> (StatusCode, String, String) {
+        let response = app.request(Request::get(uri).body(Body::empty()).unwrap()).await;
+        let layers = response
+            .headers()
+            .get_all("x-layers")
+            .iter()
+            .map(|layer| layer.to_str().unwrap())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        (response.status(), layers, super::common::body_text(response).await)
+    }
+
+    #[tokio::test]
+    async fn controllers_resolve_under_their_prefix_with_their_layers() {
+        let auth = map_response(auth);
+        let router = routes! {
+            "/api" => [UserController, PostController] with [auth],
+            "/admin" => [AdminController] with [auth, map_response(admin)]
+        }
+        .unwrap();
+        let app = TestApp::new(router);
+
+        assert_eq!(
+            fetch(&app, "/api/users").await,
+            (StatusCode::OK, "auth".to_string(), "users".to_string())
+        );
+        assert_eq!(
+            fetch(&app, "/api/posts").await,
+            (StatusCode::OK, "auth".to_string(), "posts".to_string())
+        );
+        // the first listed layer is the outermost, it appends last
+        assert_eq!(
+            fetch(&app, "/admin/stats").await,
+            (StatusCode::OK, "admin,auth".to_string(), "stats".to_string())
+        );
+
+        assert_eq!(fetch(&app, "/users").await.0, StatusCode::NOT_FOUND);
+        assert_eq!(fetch(&app, "/api/stats").await.0, StatusCode::NOT_FOUND);
+    }
+}
+
+mod wildcard {
+    use argon_core::controller::Controller;
+    use argon_core::testing::TestApp;
+    use argon_macros::{controller, get, utoipa_response};
+    use axum::{extract::Path, http::StatusCode};
+
+    pub struct FileController;
+
+    #[controller]
+    impl FileController {
+        #[get("/files/{*path}")]
+        #[utoipa_response(status = 200, body = String)]
+        async fn file(Path(_path): Path<String>) -> String {
+            format!("file {}", _path)
+        }
+    }
+
+    #[tokio::test]
+    async fn the_rest_of_the_path_is_captured() {
+        let app = TestApp::new(FileController::router());
+
+        assert_eq!(super::fetch(&app, "/files/a/b/c").await, (StatusCode::OK, "file a/b/c".to_string()));
+        assert_eq!(super::fetch(&app, "/files/").await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn the_capture_is_documented_as_a_path_parameter() {
+        let spec = serde_json::to_value(FileController::api_doc()).unwrap();
+        let parameters = &spec["paths"]["files/{path}"]["get"]["parameters"];
+
+        assert_eq!(parameters[0]["name"], "path");
+        assert_eq!(parameters[0]["in"], "path");
+    }
+}
+
+mod method_not_allowed {
+    use argon_core::controller::Controller;
+    use argon_core::testing::TestApp;
+    use argon_macros::{controller, get, post};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use serde_json::json;
+
+    pub struct CommentController;
+
+    #[controller]
+    impl CommentController {
+        #[get("/comments")]
+        async fn list() -> &'static str {
+            "comments"
+        }
+
+        #[post("/comments")]
+        async fn create() -> &'static str {
+            "created"
+        }
+    }
+
+    #[tokio::test]
+    async fn other_verbs_answer_405_with_the_allowed_ones() {
+        let app = TestApp::new(CommentController::router());
+
+        let response = app
+            .request(Request::delete("/comments").body(Body::empty()).unwrap())
+            .await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[header::ALLOW], "GET, POST");
+        assert_eq!(
+            super::common::body_json(response).await,
+            json!({ "message": "Method Not Allowed", "detail": null })
+        );
+
+        assert_eq!(super::fetch(&app, "/comments").await, (StatusCode::OK, "comments".to_string()));
+    }
+}
+
+mod preflight {
+    use argon_core::controller::Controller;
+    use argon_core::testing::TestApp;
+    use argon_macros::{controller, get, post, routes};
+    use axum::{
+        body::Body,
+        extract::Request,
+        http::{HeaderValue, Method, StatusCode, header},
+        middleware::{self, Next},
+        response::Response,
+    };
+    use tower_http::cors::CorsLayer;
+
+    async fn require_token(request: Request, next: Next) -> Result<Response, StatusCode> {
+        match request.headers().get("X-Token") {
+            Some(token) if token == "secret" => Ok(next.run(request).await),
+            _ => Err(StatusCode::UNAUTHORIZED),
+        }
+    }
+
+    pub struct InvoiceController;
+
+    #[controller(preflight, middleware(require_token))]
+    impl InvoiceController {
+        #[get("/invoices")]
+        async fn list() -> &'static str {
+            "invoices"
+        }
+
+        #[post("/invoices")]
+        async fn create() -> StatusCode {
+            StatusCode::CREATED
+        }
+    }
+
+    fn preflight() -> Request<Body> {
+        Request::options("/invoices")
+            .header(header::ORIGIN, "https://app.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type, x-token")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn a_preflight_to_a_post_route_answers_204_with_the_allow_headers() {
+        let response = TestApp::new(InvoiceController::router()).request(preflight()).await;
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(response.headers()[header::ALLOW], "GET, POST, OPTIONS");
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_METHODS], "GET, POST, OPTIONS");
+        // which origins may call is left to the CORS layer
+        assert!(!response.headers().contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert!(!response.headers().contains_key(header::ACCESS_CONTROL_ALLOW_HEADERS));
+    }
+
+    #[tokio::test]
+    async fn a_restrictive_cors_layer_decides_the_allowed_origins() {
+        let cors = CorsLayer::new()
+            .allow_origin([HeaderValue::from_static("https://app.example.com")])
+            .allow_methods([Method::GET, Method::POST])
+            .allow_headers([header::CONTENT_TYPE]);
+        let app = TestApp::new(InvoiceController::router().layer(cors));
+
+        let response = app.request(preflight()).await;
+        assert!(response.status().is_success());
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://app.example.com");
+
+        let response = app
+            .request(
+                Request::options("/invoices")
+                    .header(header::ORIGIN, "https://evil.example.com")
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert!(!response.headers().contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn routes_mounts_preflights_outside_the_group_layers() {
+        let auth = middleware::from_fn(require_token);
+        let router = routes! {
+            "/api" => [InvoiceController] with [auth]
+        }
+        .unwrap();
+        let app = TestApp::new(router);
+
+        let response = app
+            .request(
+                Request::options("/api/invoices")
+                    .header(header::ORIGIN, "https://app.example.com")
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(response.headers()[header::ALLOW], "GET, POST, OPTIONS");
+
+        let response = app.request(Request::get("/api/invoices").body(Body::empty()).unwrap()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .request(
+                Request::delete("/api/invoices")
+                    .header("X-Token", "secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn the_controller_middleware_still_guards_the_other_verbs() {
+        let app = TestApp::new(InvoiceController::router());
+
+        let response = app.request(Request::post("/invoices").body(Body::empty()).unwrap()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .request(
+                Request::post("/invoices")
+                    .header("X-Token", "secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app
+            .request(
+                Request::delete("/invoices")
+                    .header("X-Token", "secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[header::ALLOW], "GET, POST, OPTIONS");
+    }
+}
@@ -0,0 +1,192 @@
+use std::{net::SocketAddr, time::Duration};
+
+use argon_core::server::{ServerOptions, serve, serve_many};
+use axum::{Router, routing::get};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::oneshot,
+};
+
+/// Serve a `/ping` route with `options` on an ephemeral port until the sender is dropped
+async fn start(options: ServerOptions) -> (SocketAddr, oneshot::Sender<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = oneshot::channel::<()>();
+
+    let app = Router::new().route("/ping", get(|| async { "pong" }));
+    tokio::spawn(serve(listener, app, options, async {
+        let _ = stopped.await;
+    }));
+
+    (addr, stop)
+}
+
+/// Whatever the server answers to `bytes` before closing or going quiet
+async fn exchange(addr: SocketAddr, bytes: &[u8]) -> Vec<u8> {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(bytes).await.unwrap();
+
+    let mut received = Vec::new();
+    let mut buffer = [0; 1024];
+    while let Ok(Ok(read)) = tokio::time::timeout(Duration::from_millis(200), stream.read(&mut buffer)).await {
+        if read == 0 {
+            break;
+        }
+        received.extend_from_slice(&buffer[..read]);
+    }
+
+    received
+}
+
+const GET_PING: &[u8] = b"GET /ping HTTP/1.1\r\nHost: localhost\r\n\r\n";
+const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+#[test]
+fn the_builder_serves_http2_only_when_enabled() {
+    let builder = ServerOptions::default().builder();
+    assert!(builder.is_http1_available());
+    assert!(builder.is_http2_available());
+
+    let builder = ServerOptions {
+        http2: false,
+        ..ServerOptions::default()
+    }
+    .builder();
+    assert!(builder.is_http1_available());
+    assert!(!builder.is_http2_available());
+}
+
+#[tokio::test]
+async fn keep_alive_is_applied_to_http1_connections() {
+    let (addr, _stop) = start(ServerOptions::default()).await;
+    let response = String::from_utf8(exchange(addr, GET_PING).await).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(!response.contains("connection: close"), "{}", response);
+
+    let (addr, _stop) = start(ServerOptions {
+        keep_alive: false,
+        ..ServerOptions::default()
+    })
+    .await;
+    let response = String::from_utf8(exchange(addr, GET_PING).await).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.contains("connection: close"), "{}", response);
+}
+
+#[tokio::test]
+async fn http2_prior_knowledge_is_answered_when_enabled() {
+    // an HTTP/2 server answers the preface with its SETTINGS frame, type 0x4
+    let (addr, _stop) = start(ServerOptions::default()).await;
+    let received = exchange(addr, H2_PREFACE).await;
+    assert!(received.len() >= 9, "{:?}", received);
+    assert_eq!(received[3], 0x4);
+
+    let (addr, _stop) = start(ServerOptions {
+        http2: false,
+        ..ServerOptions::default()
+    })
+    .await;
+    let received = exchange(addr, H2_PREFACE).await;
+    // HTTP/1 alone rejects the preface as a malformed request
+    assert!(received.is_empty() || received.starts_with(b"HTTP/1.1 "), "{:?}", received);
+}
+
+#[tokio::test]
+async fn every_listener_serves_its_router_until_shutdown() {
+    let api = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let admin = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let (api_addr, admin_addr) = (api.local_addr().unwrap(), admin.local_addr().unwrap());
+
+    let (stop, stopped) = oneshot::channel::<()>();
+    let server = tokio::spawn(serve_many(
+        vec![
+            (api, Router::new().route("/ping", get(|| async { "pong" }))),
+            (admin, Router::new().route("/metrics", get(|| async { "requests 1" }))),
+        ],
+        ServerOptions::default(),
+        async {
+            let _ = stopped.await;
+        },
+    ));
+
+    let get = |addr: SocketAddr, path: &str| {
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+        async move { String::from_utf8(exchange(addr, request.as_bytes()).await).unwrap() }
+    };
+
+    let response = get(api_addr, "/ping").await;
+    assert!(response.starts_with("HTTP/1.1 200 OK") && response.ends_with("pong"), "{}", response);
+    let response = get(admin_addr, "/metrics").await;
+    assert!(response.starts_with("HTTP/1.1 200 OK") && response.ends_with("requests 1"), "{}", response);
+
+    // each port only serves its own router
+    assert!(get(api_addr, "/metrics").await.starts_with("HTTP/1.1 404"));
+
+    // one signal stops both
+    stop.send(()).unwrap();
+    server.await.unwrap().unwrap();
+    assert!(TcpStream::connect(api_addr).await.is_err());
+    assert!(TcpStream::connect(admin_addr).await.is_err());
+}
+
+#[cfg(unix)]
+mod unix {
+    use argon_core::server::{ServerOptions, bind_unix, serve};
+    use axum::{Router, routing::get};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::UnixStream,
+    };
+
+    /// A socket path of its own for each test
+    fn socket_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("argon-{}-{}.sock", name, std::process::id()))
+    }
+
+    async fn ping(path: &std::path::Path) -> String {
+        let mut stream = UnixStream::connect(path).await.unwrap();
+        stream
+            .write_all(b"GET /ping HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        response
+    }
+
+    #[tokio::test]
+    async fn requests_are_served_over_the_socket() {
+        let path = socket_path("serve");
+        // left behind by a previous run
+        drop(std::os::unix::net::UnixListener::bind(&path));
+
+        let listener = bind_unix(&path).unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let app = Router::new().route("/ping", get(|| async { "pong" }));
+        let server = tokio::spawn(serve(listener, app, ServerOptions::default(), async {
+            let _ = stopped.await;
+        }));
+
+        let response = ping(&path).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("pong"));
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_regular_file_is_not_replaced() {
+        let path = socket_path("file");
+        std::fs::write(&path, "keep me").unwrap();
+
+        assert!(bind_unix(&path).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep me");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use argon_core::singleflight::{SingleFlight, single_flight};
+use argon_core::testing::TestApp;
+use axum::{
+    Router,
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use futures_util::future::join_all;
+use tokio::sync::Semaphore;
+
+/// Counts its runs and answers once the test closes the gate
+#[derive(Clone)]
+struct Report {
+    runs: Arc<AtomicUsize>,
+    gate: Arc<Semaphore>,
+    size: usize,
+}
+
+async fn summary(State(report): State<Report>) -> String {
+    let run = report.runs.fetch_add(1, Ordering::SeqCst) + 1;
+    // never granted, it only waits for the gate to close
+    let _ = report.gate.acquire().await;
+
+    format!("report {}", run) + &".".repeat(report.size)
+}
+
+fn app(single: SingleFlight, size: usize) -> (TestApp, Report) {
+    let report = Report {
+        runs: Arc::default(),
+        gate: Arc::new(Semaphore::new(0)),
+        size,
+    };
+    let router = Router::new()
+        .route("/report", get(summary))
+        .with_state(report.clone())
+        .layer(axum::middleware::from_fn_with_state(single, single_flight));
+
+    (TestApp::new(router), report)
+}
+
+/// Fire one `GET /report` per header set at once, releasing the handler once they are all in
+async fn fire(app: &TestApp, report: &Report, requests: Vec<Vec<(&str, &str)>>) -> Vec<(StatusCode, String)> {
+    let responses = join_all(requests.into_iter().map(|headers| async move {
+        let mut request = Request::get("/report");
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
+        let response = app.request(request.body(Body::empty()).unwrap()).await;
+        (response.status(), common::body_text(response).await)
+    }));
+
+    let release = async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        report.gate.close();
+    };
+
+    tokio::join!(responses, release).0
+}
+
+#[tokio::test]
+async fn concurrent_identical_gets_run_the_handler_once() {
+    let (app, report) = app(SingleFlight::new(), 0);
+
+    let responses = fire(&app, &report, vec![vec![]; 8]).await;
+
+    assert_eq!(report.runs.load(Ordering::SeqCst), 1);
+    assert_eq!(responses, vec![(StatusCode::OK, "report 1".to_string()); 8]);
+
+    // nothing is kept once the response is out
+    let responses = fire(&app, &report, vec![vec![]]).await;
+    assert_eq!(responses, [(StatusCode::OK, "report 2".to_string())]);
+}
+
+#[tokio::test]
+async fn requests_differing_in_a_varying_header_are_not_shared() {
+    let (app, report) = app(SingleFlight::new().vary("x-region".parse().unwrap()), 0);
+
+    fire(
+        &app,
+        &report,
+        vec![
+            vec![("Accept-Language", "en")],
+            vec![("Accept-Language", "fr")],
+            vec![("X-Tenant-Id", "acme")],
+            vec![("Accept-Encoding", "gzip")],
+            vec![("Accept-Version", "2")],
+            vec![("X-Region", "eu")],
+            vec![("Accept-Language", "en")],
+        ],
+    )
+    .await;
+
+    // the two english requests only
+    assert_eq!(report.runs.load(Ordering::SeqCst), 6);
+}
+
+#[tokio::test]
+async fn responses_over_the_cap_are_served_to_each_caller() {
+    let (app, report) = app(SingleFlight::new().max_body_bytes(16), 32);
+
+    let responses = fire(&app, &report, vec![vec![]; 3]).await;
+
+    // the first run is not shared, every caller runs the handler itself
+    assert_eq!(report.runs.load(Ordering::SeqCst), 4);
+    for (status, body) in responses {
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.ends_with(&".".repeat(32)));
+    }
+}
@@ -0,0 +1,101 @@
+use argon_core::controller::Controller;
+use argon_core::docs::with_schemas_of;
+use argon_core::testing::{SpecValidator, TestApp};
+use argon_macros::{controller, get, post, utoipa_response};
+use axum::{
+    Json,
+    http::{Method, StatusCode},
+};
+use serde_json::{Value, json};
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Profile {
+    pub id: i32,
+    pub name: String,
+}
+
+pub struct ProfileController;
+
+#[controller]
+impl ProfileController {
+    #[get("/profiles/{id}")]
+    #[utoipa_response(status = 200, body = Profile)]
+    async fn show() -> Json<Profile> {
+        Json(Profile {
+            id: 1,
+            name: "alice".to_string(),
+        })
+    }
+
+    // drifted from its docs
+    #[get("/profiles/{id}/full")]
+    #[utoipa_response(status = 200, body = Profile)]
+    async fn full() -> Json<Value> {
+        Json(json!({ "id": 1, "name": "alice", "password": "secret" }))
+    }
+
+    #[post("/profiles")]
+    #[utoipa_response(status = 201, body = Profile)]
+    async fn create(Json(_profile): Json<Profile>) -> (StatusCode, Json<Profile>) {
+        (StatusCode::CREATED, Json(_profile))
+    }
+}
+
+fn validator() -> SpecValidator {
+    let docs = utoipa::openapi::OpenApi::default().nest("/", ProfileController::api_doc());
+
+    SpecValidator::new(&with_schemas_of(docs, concat!(module_path!(), "::ProfileController")))
+}
+
+#[tokio::test]
+async fn a_documented_response_conforms() {
+    let (status, body) = TestApp::controller::<ProfileController>().get_json("/profiles/1").await;
+
+    validator().assert_response(Method::GET, "/profiles/1", status, &body);
+}
+
+#[tokio::test]
+async fn an_undocumented_field_is_flagged() {
+    let (status, body) = TestApp::controller::<ProfileController>().get_json("/profiles/1/full").await;
+
+    assert_eq!(
+        validator().validate_response(Method::GET, "/profiles/1/full", status, &body),
+        Err(vec!["$.password: undocumented field".to_string()])
+    );
+}
+
+#[test]
+fn mistyped_and_missing_fields_are_flagged() {
+    let errors = validator()
+        .validate_response(Method::GET, "/profiles/1", StatusCode::OK, &json!({ "id": "1" }))
+        .unwrap_err();
+
+    assert!(errors.contains(&"$: missing required field `name`".to_string()), "{:?}", errors);
+    assert!(errors.iter().any(|error| error.starts_with("$.id: expected integer")), "{:?}", errors);
+}
+
+#[test]
+fn undocumented_operations_and_statuses_are_flagged() {
+    let validator = validator();
+
+    assert_eq!(
+        validator.validate_response(Method::DELETE, "/profiles/1", StatusCode::OK, &Value::Null),
+        Err(vec!["the spec has no operation for DELETE /profiles/1".to_string()])
+    );
+    assert!(
+        validator
+            .validate_response(Method::GET, "/profiles/1", StatusCode::IM_A_TEAPOT, &Value::Null)
+            .is_err()
+    );
+}
+
+#[test]
+fn request_bodies_are_checked_too() {
+    let validator = validator();
+
+    assert_eq!(
+        validator.validate_request(Method::POST, "/profiles", &json!({ "id": 1, "name": "bob" })),
+        Ok(())
+    );
+    assert!(validator.validate_request(Method::POST, "/profiles", &json!({ "id": 1 })).is_err());
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use argon_core::state::{Db, Service};
+use argon_core::testing::{TestApp, empty_state};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+
+#[derive(Clone)]
+struct Config {
+    name: &'static str,
+}
+
+#[derive(Clone)]
+struct Mailer;
+
+async fn describe(Db(db): Db, Service(config): Service<Config>) -> String {
+    format!("{} on {:?}", config.name, db.get_database_backend())
+}
+
+async fn mail(Service(_mailer): Service<Mailer>) -> &'static str {
+    "sent"
+}
+
+fn app() -> TestApp {
+    let router = Router::new().route("/describe", get(describe)).route("/mail", get(mail));
+
+    TestApp::new(router).with_state(empty_state().with(Config { name: "argon" }))
+}
+
+#[tokio::test]
+async fn db_and_config_come_from_one_app_state() {
+    let response = app()
+        .request(Request::get("/describe").body(Body::empty()).unwrap())
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_text(response).await, "argon on Postgres");
+}
+
+#[tokio::test]
+async fn an_unregistered_service_answers_500() {
+    let response = app().request(Request::get("/mail").body(Body::empty()).unwrap()).await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
@@ -0,0 +1,19 @@
+mod common;
+
+use argon_core::tasks::spawn_task;
+
+use common::Logs;
+
+#[tokio::test]
+async fn a_panicking_task_is_logged_and_the_runtime_keeps_going() {
+    let (logs, _guard) = Logs::capture();
+
+    let panicked = spawn_task("doomed", async { panic!("boom") }).await;
+    assert!(panicked.is_ok(), "the panic should be caught inside the task");
+
+    let survived = spawn_task("next", async {}).await;
+    assert!(survived.is_ok());
+
+    let logs = logs.contents();
+    assert!(logs.contains("background task `doomed` panicked: boom"), "{}", logs);
+}
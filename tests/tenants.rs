@@ -0,0 +1,92 @@
+mod common;
+
+use argon_core::db::{TenantConnections, TenantResolver, tenant_middleware};
+use argon_core::state::Db;
+use argon_core::testing::{TestApp, empty_state};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbErr, Statement};
+
+/// A fresh in-memory SQLite database per tenant, named after it, `acme` and `globex` only
+#[derive(Clone)]
+struct SqliteTenants;
+
+impl TenantResolver for SqliteTenants {
+    async fn connect(&self, tenant: &str) -> Result<DatabaseConnection, DbErr> {
+        if !["acme", "globex"].contains(&tenant) {
+            return Err(DbErr::Custom(format!("unknown tenant `{}`", tenant)));
+        }
+
+        let db = Database::connect("sqlite::memory:").await?;
+        db.execute_unprepared("CREATE TABLE tenant (name TEXT NOT NULL)").await?;
+        db.execute_unprepared(&format!("INSERT INTO tenant (name) VALUES ('{}')", tenant))
+            .await?;
+
+        Ok(db)
+    }
+}
+
+async fn whoami(Db(db): Db) -> String {
+    let row = db
+        .query_one_raw(Statement::from_string(db.get_database_backend(), "SELECT name FROM tenant"))
+        .await
+        .unwrap()
+        .unwrap();
+
+    row.try_get::<String>("", "name").unwrap()
+}
+
+fn app(tenants: TenantConnections<SqliteTenants>) -> TestApp {
+    let router = Router::new()
+        .route("/whoami", get(whoami))
+        .layer(axum::middleware::from_fn_with_state(tenants, tenant_middleware::<SqliteTenants>));
+
+    TestApp::new(router).with_state(empty_state())
+}
+
+async fn whoami_as(app: &TestApp, tenant: Option<&str>) -> (StatusCode, String) {
+    let mut request = Request::get("/whoami");
+    if let Some(tenant) = tenant {
+        request = request.header("X-Tenant-Id", tenant);
+    }
+
+    let response = app.request(request.body(Body::empty()).unwrap()).await;
+
+    (response.status(), common::body_text(response).await)
+}
+
+#[tokio::test]
+async fn each_tenant_gets_its_own_database() {
+    let tenants = TenantConnections::new(SqliteTenants);
+    let app = app(tenants.clone());
+
+    assert_eq!(whoami_as(&app, Some("acme")).await, (StatusCode::OK, "acme".to_string()));
+    assert_eq!(whoami_as(&app, Some("globex")).await, (StatusCode::OK, "globex".to_string()));
+    // the cached connection is reused, a new in-memory one would have no table
+    assert_eq!(whoami_as(&app, Some("acme")).await, (StatusCode::OK, "acme".to_string()));
+    assert_eq!(tenants.cached(), 2);
+}
+
+#[tokio::test]
+async fn unknown_or_missing_tenants_are_rejected_and_not_cached() {
+    let tenants = TenantConnections::new(SqliteTenants);
+    let app = app(tenants.clone());
+
+    assert_eq!(whoami_as(&app, None).await.0, StatusCode::BAD_REQUEST);
+    assert_eq!(whoami_as(&app, Some("initech")).await.0, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(tenants.cached(), 0);
+}
+
+#[tokio::test]
+async fn the_cache_keeps_at_most_max_tenants() {
+    let tenants = TenantConnections::new(SqliteTenants).max_tenants(1);
+    let app = app(tenants.clone());
+
+    assert_eq!(whoami_as(&app, Some("acme")).await.1, "acme");
+    assert_eq!(whoami_as(&app, Some("globex")).await.1, "globex");
+    assert_eq!(tenants.cached(), 1);
+}
@@ -0,0 +1,26 @@
+use argon_core::controller::Controller;
+use argon_macros::controller;
+
+pub struct EmptyController;
+
+#[controller]
+impl EmptyController {
+    fn helper() -> u32 {
+        1
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    assert_eq!(EmptyController::helper(), 1);
+    assert!(EmptyController::routes().is_empty());
+    assert_eq!(EmptyController::operation_count(), 0);
+
+    let spec = utoipa::openapi::OpenApi::default().nest("/", EmptyController::api_doc());
+    assert!(spec.paths.paths.is_empty());
+    spec.to_json().unwrap();
+
+    let app = argon_core::testing::TestApp::controller::<EmptyController>();
+    let (status, _) = app.get_json("/").await;
+    assert_eq!(status, axum::http::StatusCode::NOT_FOUND);
+}
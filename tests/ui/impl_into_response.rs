@@ -0,0 +1,20 @@
+use argon_macros::{controller, get, utoipa_response};
+use axum::response::IntoResponse;
+
+pub struct HealthController;
+
+#[controller]
+impl HealthController {
+    #[get("/health")]
+    #[utoipa_response(status = 200, body = String, description = "the service is up")]
+    async fn health() -> impl IntoResponse {
+        "up"
+    }
+}
+
+fn main() {
+    use argon_core::controller::Controller;
+
+    let _ = HealthController::router();
+    assert_eq!(HealthController::operation_count(), 1);
+}
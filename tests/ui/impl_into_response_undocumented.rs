@@ -0,0 +1,14 @@
+use argon_macros::{controller, get};
+use axum::response::IntoResponse;
+
+pub struct HealthController;
+
+#[controller]
+impl HealthController {
+    #[get("/health")]
+    async fn health() -> impl IntoResponse {
+        "up"
+    }
+}
+
+fn main() {}
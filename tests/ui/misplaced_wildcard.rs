@@ -0,0 +1,13 @@
+use argon_macros::{controller, get};
+
+pub struct FileController;
+
+#[controller]
+impl FileController {
+    #[get("/files/{*path}/meta")]
+    async fn meta() -> &'static str {
+        "meta"
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use argon_macros::response;
+use axum::http::StatusCode;
+
+response! {
+    UserResponse {
+        StatusCode::OK = String, "user found",
+        StatusCode::OK = u32, "user count"
+    }
+}
+
+fn main() {}
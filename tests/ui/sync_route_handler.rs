@@ -0,0 +1,13 @@
+use argon_macros::controller;
+
+pub struct ClockController;
+
+#[controller]
+impl ClockController {
+    #[argon_macros::get("/now")]
+    fn now() -> String {
+        "12:00".to_string()
+    }
+}
+
+fn main() {}
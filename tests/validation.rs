@@ -0,0 +1,105 @@
+mod common;
+
+use argon_core::controller::Controller;
+use argon_core::extract::{Valid, Validate};
+use argon_core::response::ValidationErrorResponse;
+use argon_core::testing::TestApp;
+use argon_macros::{controller, post, utoipa_response};
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+pub struct NewPost {
+    pub title: String,
+}
+
+impl Validate for NewPost {
+    fn validate(&self) -> Result<(), ValidationErrorResponse> {
+        let mut errors = ValidationErrorResponse::new();
+        if self.title.trim().is_empty() {
+            errors = errors.field("title", "must not be blank");
+        }
+
+        errors.into_result()
+    }
+}
+
+pub struct PostController;
+
+#[controller]
+impl PostController {
+    #[post("/posts")]
+    #[utoipa_response(status = 201, body = String)]
+    async fn create(Valid(_post): Valid<NewPost>) -> (StatusCode, axum::Json<String>) {
+        (StatusCode::CREATED, axum::Json("created".to_string()))
+    }
+}
+
+#[test]
+fn validated_endpoints_document_their_422() {
+    let spec = serde_json::to_value(utoipa::openapi::OpenApi::default().nest("/", PostController::api_doc())).unwrap();
+    let response = &spec["paths"]["/posts"]["post"]["responses"]["422"];
+
+    assert_eq!(
+        response["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/ValidationErrorResponse"
+    );
+}
+
+#[tokio::test]
+async fn invalid_bodies_answer_the_documented_shape() {
+    let app = TestApp::controller::<PostController>();
+
+    let (status, body) = app.send_json(Method::POST, "/posts", &json!({ "title": " " })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body,
+        json!({ "message": "validation failed", "detail": { "title": ["must not be blank"] } })
+    );
+
+    let (status, _) = app.send_json(Method::POST, "/posts", &json!({ "title": "Hello" })).await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+mod field_errors {
+    use argon_core::response::{BaseErrorResponse, FieldErrors, ValidationErrorResponse};
+    use serde_json::json;
+
+    fn errors() -> FieldErrors {
+        let mut errors = FieldErrors::new().field("title", "must not be blank");
+        errors.add("body", "is too short");
+        errors.add("title", "is taken");
+
+        errors
+    }
+
+    #[test]
+    fn base_error_response_serializes_the_fields_as_detail() {
+        let body = serde_json::to_value(BaseErrorResponse::new("validation failed", errors())).unwrap();
+
+        assert_eq!(
+            body,
+            json!({
+                "message": "validation failed",
+                "detail": { "body": ["is too short"], "title": ["must not be blank", "is taken"] }
+            })
+        );
+        // sorted by field name
+        assert_eq!(
+            serde_json::to_string(&errors()).unwrap(),
+            r#"{"body":["is too short"],"title":["must not be blank","is taken"]}"#
+        );
+    }
+
+    #[test]
+    fn the_validation_response_has_the_same_shape() {
+        let errors = errors();
+        let response = ValidationErrorResponse::from(errors.clone());
+
+        assert_eq!(response.fields(), &errors);
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::to_value(BaseErrorResponse::new("validation failed", errors)).unwrap()
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use argon_core::routing::Versioned;
+use argon_core::testing::TestApp;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+
+fn versioned() -> Versioned {
+    Versioned::new()
+        .version(1, Router::new().route("/users", get(|| async { "v1" })))
+        .version(2, Router::new().route("/users", get(|| async { "v2" })))
+}
+
+async fn get_text(app: &TestApp, uri: &str, version: Option<&str>) -> (StatusCode, String) {
+    let mut request = Request::get(uri);
+    if let Some(version) = version {
+        request = request.header("Accept-Version", version);
+    }
+
+    let response = app.request(request.body(Body::empty()).unwrap()).await;
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), 1024).await.unwrap();
+
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn path_router_dispatches_on_the_prefix() {
+    let app = TestApp::new(versioned().into_path_router());
+
+    assert_eq!(get_text(&app, "/v1/users", None).await, (StatusCode::OK, "v1".to_string()));
+    assert_eq!(get_text(&app, "/v2/users", None).await, (StatusCode::OK, "v2".to_string()));
+    assert_eq!(get_text(&app, "/v3/users", None).await.0, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn header_router_dispatches_on_accept_version() {
+    let app = TestApp::new(versioned().into_header_router());
+
+    assert_eq!(get_text(&app, "/users", Some("1")).await, (StatusCode::OK, "v1".to_string()));
+    assert_eq!(get_text(&app, "/users", Some("v2")).await, (StatusCode::OK, "v2".to_string()));
+}
+
+#[tokio::test]
+async fn header_router_falls_back_to_the_default_version() {
+    let app = TestApp::new(versioned().into_header_router());
+    assert_eq!(get_text(&app, "/users", None).await, (StatusCode::OK, "v2".to_string()));
+
+    let app = TestApp::new(versioned().default_version(1).into_header_router());
+    assert_eq!(get_text(&app, "/users", None).await, (StatusCode::OK, "v1".to_string()));
+}
+
+#[tokio::test]
+async fn header_router_rejects_unknown_versions() {
+    let app = TestApp::new(versioned().into_header_router());
+
+    assert_eq!(get_text(&app, "/users", Some("3")).await.0, StatusCode::BAD_REQUEST);
+    assert_eq!(get_text(&app, "/users", Some("latest")).await.0, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,41 @@
+use argon_core::ws::{Broadcaster, Received};
+
+#[tokio::test]
+async fn every_subscriber_receives_a_published_message() {
+    let chat = Broadcaster::<String>::new(8);
+    let mut alice = chat.subscribe();
+    let mut bob = chat.clone().subscribe();
+
+    assert_eq!(chat.subscriber_count(), 2);
+    assert_eq!(chat.publish("hello".to_string()), 2);
+
+    assert_eq!(alice.recv().await, Some(Received::Message("hello".to_string())));
+    assert_eq!(bob.recv().await, Some(Received::Message("hello".to_string())));
+}
+
+#[tokio::test]
+async fn a_lagged_subscriber_is_told_how_many_it_skipped() {
+    let chat = Broadcaster::<u32>::new(2);
+    let mut slow = chat.subscribe();
+
+    for message in 0..5 {
+        chat.publish(message);
+    }
+
+    assert_eq!(slow.recv().await, Some(Received::Lagged(3)));
+    // it then picks up with the oldest message still buffered
+    assert_eq!(slow.recv().await, Some(Received::Message(3)));
+    assert_eq!(slow.recv().await, Some(Received::Message(4)));
+}
+
+#[tokio::test]
+async fn subscriptions_end_once_the_broadcaster_is_dropped() {
+    let chat = Broadcaster::<u32>::new(2);
+    let mut subscription = chat.subscribe();
+
+    assert_eq!(chat.publish(1), 1);
+    drop(chat);
+
+    assert_eq!(subscription.recv().await, Some(Received::Message(1)));
+    assert_eq!(subscription.recv().await, None);
+}